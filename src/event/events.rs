@@ -1,5 +1,7 @@
 use crate::event::Event;
 use crate::sys;
+#[cfg(feature = "time")]
+use crate::Token;
 
 use std::fmt;
 
@@ -187,6 +189,21 @@ impl Events {
     }
 }
 
+cfg_time! {
+    impl Events {
+        /// Add an event for an expired timeout of the `TimerWheel`, returns
+        /// `false` if there is no room for it.
+        pub(crate) fn push_timeout(&mut self, token: Token) -> bool {
+            if self.inner.len() < self.inner.capacity() {
+                self.inner.push(sys::event::timeout(token));
+                true
+            } else {
+                false
+            }
+        }
+    }
+}
+
 impl<'a> IntoIterator for &'a Events {
     type Item = &'a Event;
     type IntoIter = Iter<'a>;
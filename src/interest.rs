@@ -32,6 +32,12 @@ const WRITABLE: u8 = 0b0_010;
 const AIO: u8 = 0b0_100;
 #[cfg_attr(not(target_os = "freebsd"), allow(dead_code))]
 const LIO: u8 = 0b1_000;
+// Registration mode, if neither is set the registration is edge-triggered.
+const LEVEL: u8 = 0b01_0000;
+const ONESHOT: u8 = 0b10_0000;
+const MODE: u8 = LEVEL | ONESHOT;
+// Set by `Registry::register_exclusive`.
+const EXCLUSIVE: u8 = 0b100_0000;
 
 impl Interest {
     /// Returns a `Interest` set representing readable interests.
@@ -58,6 +64,9 @@ impl Interest {
     /// This does the same thing as the `BitOr` implementation, but is a
     /// constant function.
     ///
+    /// If the interests use different registration modes the result uses the
+    /// most restrictive one, see [`mode`].
+    ///
     /// ```
     /// use mio::Interest;
     ///
@@ -65,11 +74,70 @@ impl Interest {
     /// # fn silent_dead_code_warning(_: Interest) { }
     /// # silent_dead_code_warning(INTERESTS)
     /// ```
+    ///
+    /// [`mode`]: #method.mode
     #[allow(clippy::should_implement_trait)]
     pub const fn add(self, other: Interest) -> Interest {
         Interest(unsafe { NonZeroU8::new_unchecked(self.0.get() | other.0.get()) })
     }
 
+    /// Returns the same interests using registration `mode`.
+    ///
+    /// By default all registrations are [edge-triggered]. This replaces the
+    /// mode of all interests, set the mode after combining the interests.
+    ///
+    /// ```
+    /// use mio::{Interest, Mode};
+    ///
+    /// let interests = Interest::READABLE.with_mode(Mode::Level);
+    /// assert!(interests.is_readable());
+    /// assert_eq!(interests.mode(), Mode::Level);
+    /// ```
+    ///
+    /// [edge-triggered]: enum.Mode.html#variant.Edge
+    pub fn with_mode(self, mode: Mode) -> Interest {
+        let mode = match mode {
+            Mode::Edge => 0,
+            Mode::Level => LEVEL,
+            Mode::Oneshot => ONESHOT,
+        };
+        Interest(unsafe { NonZeroU8::new_unchecked((self.0.get() & !MODE) | mode) })
+    }
+
+    /// Returns the registration mode.
+    ///
+    /// A registration has a single mode. Interests with different modes
+    /// combined using `|` or [`add`] use the most restrictive one: oneshot
+    /// over level-triggered, and level-triggered over edge-triggered.
+    ///
+    /// ```
+    /// use mio::{Interest, Mode};
+    ///
+    /// let interests = Interest::READABLE.with_mode(Mode::Level) | Interest::WRITABLE;
+    /// assert_eq!(interests.mode(), Mode::Level);
+    /// ```
+    ///
+    /// [`add`]: #method.add
+    pub fn mode(self) -> Mode {
+        if (self.0.get() & ONESHOT) != 0 {
+            Mode::Oneshot
+        } else if (self.0.get() & LEVEL) != 0 {
+            Mode::Level
+        } else {
+            Mode::Edge
+        }
+    }
+
+    /// Returns the same interests for an exclusive registration.
+    pub(crate) fn exclusive(self) -> Interest {
+        Interest(unsafe { NonZeroU8::new_unchecked(self.0.get() | EXCLUSIVE) })
+    }
+
+    /// Returns true if the interests are for an exclusive registration.
+    pub(crate) fn is_exclusive(self) -> bool {
+        (self.0.get() & EXCLUSIVE) != 0
+    }
+
     /// Returns true if the value includes readable readiness.
     pub const fn is_readable(self) -> bool {
         (self.0.get() & READABLE) != 0
@@ -149,7 +217,61 @@ impl fmt::Debug for Interest {
                 one = true
             }
         }
+        match self.mode() {
+            Mode::Edge => {}
+            Mode::Level => write!(fmt, " | LEVEL")?,
+            Mode::Oneshot => write!(fmt, " | ONESHOT")?,
+        }
+        if self.is_exclusive() {
+            write!(fmt, " | EXCLUSIVE")?;
+        }
         debug_assert!(one, "printing empty interests");
         Ok(())
     }
 }
+
+/// Registration mode, set using [`Interest::with_mode`].
+///
+/// The mode determines when [`Poll::poll`] returns events for a registered
+/// [`event::Source`].
+///
+/// Only `Edge` is supported on all platforms, `Level` and `Oneshot` are
+/// supported on platforms using epoll or kqueue. Registering with an
+/// unsupported mode returns an error.
+///
+/// [`Interest::with_mode`]: struct.Interest.html#method.with_mode
+/// [`Poll::poll`]: struct.Poll.html#method.poll
+/// [`event::Source`]: ./event/trait.Source.html
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Mode {
+    /// Edge-triggered, the default.
+    ///
+    /// An event is returned once the readiness changes, after which the
+    /// operation must be performed until it returns `WouldBlock` before
+    /// another event is returned, see [draining readiness].
+    ///
+    /// [draining readiness]: struct.Poll.html#draining-readiness
+    Edge,
+    /// Level-triggered.
+    ///
+    /// An event is returned by every call to [`Poll::poll`] for as long as
+    /// the source is ready, e.g. until all data is read from a socket.
+    ///
+    /// [`Poll::poll`]: struct.Poll.html#method.poll
+    Level,
+    /// Oneshot.
+    ///
+    /// Once an event is returned the registration is disabled, no more events
+    /// are returned until the source is reregistered using
+    /// [`Registry::reregister`]. If the source is still ready when it's
+    /// reregistered an event is returned again. This makes it possible for
+    /// multiple threads to poll the same `Poll` instance while ensuring only
+    /// a single thread handles the events of a source.
+    ///
+    /// On platforms using kqueue the readable and writable interests are
+    /// separate registrations, each of which would be disabled on its own, so
+    /// registering both interests in oneshot mode returns an error there.
+    ///
+    /// [`Registry::reregister`]: struct.Registry.html#method.reregister
+    Oneshot,
+}
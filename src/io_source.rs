@@ -142,7 +142,11 @@ where
     ) -> io::Result<()> {
         #[cfg(debug_assertions)]
         self.selector_id.associate(registry)?;
-        poll::selector(registry).register(self.inner.as_raw_fd(), token, interests)
+        let fd = self.inner.as_raw_fd();
+        poll::selector(registry).register(fd, token, interests)?;
+        self.state
+            .registered(poll::selector(registry), fd, interests);
+        Ok(())
     }
 
     fn reregister(
@@ -153,13 +157,21 @@ where
     ) -> io::Result<()> {
         #[cfg(debug_assertions)]
         self.selector_id.check_association(registry)?;
+        if self.state.is_exclusive() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "exclusive registrations can't be reregistered",
+            ));
+        }
         poll::selector(registry).reregister(self.inner.as_raw_fd(), token, interests)
     }
 
     fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
         #[cfg(debug_assertions)]
         self.selector_id.remove_association(registry)?;
-        poll::selector(registry).deregister(self.inner.as_raw_fd())
+        poll::selector(registry).deregister(self.inner.as_raw_fd())?;
+        self.state.deregistered();
+        Ok(())
     }
 }
 
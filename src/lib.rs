@@ -54,15 +54,21 @@ mod waker;
 
 pub mod event;
 
-cfg_net! {
+cfg_io_source! {
     mod io_source;
+}
 
+cfg_net! {
     pub mod net;
 }
 
+cfg_time! {
+    pub mod time;
+}
+
 #[doc(no_inline)]
 pub use event::Events;
-pub use interest::Interest;
+pub use interest::{Interest, Mode};
 pub use poll::{Poll, Registry};
 pub use token::Token;
 pub use waker::Waker;
@@ -72,6 +78,32 @@ cfg_any_os_util! {
     pub mod unix {
         //! Unix only extensions.
         pub use crate::sys::SourceFd;
+
+        cfg_os_poll! {
+            #[cfg(feature = "os-util")]
+            pub use crate::sys::{SignalInfo, Signals};
+
+            #[cfg(all(feature = "os-util", target_os = "linux"))]
+            pub mod inotify;
+            #[cfg(all(feature = "os-util", target_os = "linux"))]
+            pub use self::inotify::Inotify;
+
+            #[cfg(all(feature = "os-util", target_os = "linux"))]
+            pub mod netlink;
+            #[cfg(all(feature = "os-util", target_os = "linux"))]
+            pub use self::netlink::Netlink;
+
+            #[cfg(all(feature = "os-util", target_os = "linux"))]
+            mod packet;
+            #[cfg(all(feature = "os-util", target_os = "linux"))]
+            pub use self::packet::{LinkAddr, PacketSocket, SockFilter};
+
+            #[cfg(feature = "os-util")]
+            pub mod pipe;
+
+            #[cfg(feature = "os-util")]
+            pub mod process;
+        }
     }
 }
 
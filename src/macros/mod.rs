@@ -48,6 +48,38 @@ macro_rules! cfg_net {
     }
 }
 
+/// One of the features using `IoSource` enabled.
+#[cfg(unix)]
+macro_rules! cfg_io_source {
+    ($($item:item)*) => {
+        $(
+            #[cfg(any(
+                feature = "tcp",
+                feature = "udp",
+                feature = "uds",
+                all(feature = "os-poll", feature = "os-util"),
+                all(
+                    feature = "os-poll",
+                    feature = "time",
+                    any(target_os = "linux", target_os = "android")
+                ),
+            ))]
+            $item
+        )*
+    }
+}
+
+/// One of the features using `IoSource` enabled.
+#[cfg(windows)]
+macro_rules! cfg_io_source {
+    ($($item:item)*) => {
+        $(
+            #[cfg(any(feature = "tcp", feature = "udp"))]
+            $item
+        )*
+    }
+}
+
 /// Feature `tcp` enabled.
 macro_rules! cfg_tcp {
     ($($item:item)*) => {
@@ -82,6 +114,29 @@ macro_rules! cfg_uds {
     }
 }
 
+/// Feature `time` enabled.
+macro_rules! cfg_time {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "time")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+            $item
+        )*
+    }
+}
+
+/// Feature `os-util` enabled.
+#[cfg(unix)]
+macro_rules! cfg_os_util {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "os-util")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "os-util")))]
+            $item
+        )*
+    }
+}
+
 /// Feature `os-util` enabled, or one of the features that need `os-util`.
 #[cfg(unix)]
 macro_rules! cfg_any_os_util {
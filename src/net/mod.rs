@@ -9,16 +9,49 @@
 
 cfg_tcp! {
     mod tcp;
-    pub use self::tcp::{TcpListener, TcpStream};
+    pub use self::tcp::{TcpListener, TcpSocket, TcpStream};
+
+    #[cfg(target_os = "linux")]
+    pub use crate::sys::TcpInfo;
 }
 
 cfg_udp! {
     mod udp;
-    pub use self::udp::UdpSocket;
+    pub use self::udp::{UdpSocket, UdpSocketBuilder};
+
+    #[cfg(unix)]
+    mod raw;
+    #[cfg(unix)]
+    pub use self::raw::RawSocket;
+
+    #[cfg(unix)]
+    pub use crate::sys::{PacketInfo, RecvMsg, Tos};
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub use crate::sys::SegmentSize;
 }
 
+#[cfg(all(unix, any(feature = "tcp", feature = "udp", feature = "uds")))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "tcp", feature = "udp", feature = "uds")))
+)]
+pub use crate::sys::{ControlMessage, ControlMessageEncoder, ControlMessages, MsgFlags};
+
 #[cfg(unix)]
 cfg_uds! {
     mod uds;
     pub use self::uds::{SocketAddr, UnixDatagram, UnixListener, UnixStream};
+    #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+    pub use self::uds::{UnixSeqpacket, UnixSeqpacketListener};
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    pub use self::uds::UCred;
 }
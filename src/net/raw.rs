@@ -0,0 +1,235 @@
+//! Primitives for working with raw IP sockets.
+
+use crate::io_source::IoSource;
+use crate::{event, sys, Interest, Registry, Token};
+
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::net::SocketAddr;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+
+/// A raw IP socket.
+///
+/// Raw sockets send and receive IP datagrams of a single protocol, e.g. ICMP,
+/// directly, the caller is responsible for creating the protocol header.
+/// Creating a raw socket generally requires elevated privileges, e.g. the
+/// `CAP_NET_RAW` capability on Linux.
+///
+/// On Linux and Android [`ping_v4`] and [`ping_v6`] can be used to create
+/// unprivileged ICMP echo ("ping") sockets.
+///
+/// Note that received IPv4 datagrams include the IP header, IPv6 datagrams
+/// don't.
+///
+/// [`ping_v4`]: #method.ping_v4
+/// [`ping_v6`]: #method.ping_v6
+///
+/// # Examples
+///
+/// Sending an ICMP echo request.
+///
+/// ```no_run
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use mio::net::RawSocket;
+///
+/// // ICMP protocol number.
+/// const IPPROTO_ICMP: i32 = 1;
+///
+/// let socket = RawSocket::new_v4(IPPROTO_ICMP)?;
+///
+/// // Echo request (type 8, code 0), with the checksum, identifier and
+/// // sequence number set.
+/// let request = [8, 0, 0xf7, 0xfe, 0, 1, 0, 0];
+/// socket.send_to(&request, "127.0.0.1:0".parse()?)?;
+/// #     Ok(())
+/// # }
+/// ```
+pub struct RawSocket {
+    inner: IoSource<File>,
+}
+
+impl RawSocket {
+    fn new(socket: File) -> RawSocket {
+        RawSocket {
+            inner: IoSource::new(socket),
+        }
+    }
+
+    /// Creates a new raw IPv4 socket for `protocol`, e.g. `IPPROTO_ICMP`.
+    pub fn new_v4(protocol: i32) -> io::Result<RawSocket> {
+        sys::raw::new_v4(protocol).map(RawSocket::new)
+    }
+
+    /// Creates a new raw IPv6 socket for `protocol`, e.g. `IPPROTO_ICMPV6`.
+    pub fn new_v6(protocol: i32) -> io::Result<RawSocket> {
+        sys::raw::new_v6(protocol).map(RawSocket::new)
+    }
+
+    /// Creates a new ICMP echo socket for IPv4.
+    ///
+    /// Unlike a raw socket this doesn't require elevated privileges, but the
+    /// group of the process must be in the `net.ipv4.ping_group_range`
+    /// sysctl. Only ICMP echo requests can be sent and only echo replies are
+    /// received, without the IP header. The kernel sets the identifier of
+    /// the request to the (local) port of the socket, which can be selected
+    /// using [`bind`].
+    ///
+    /// [`bind`]: #method.bind
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn ping_v4() -> io::Result<RawSocket> {
+        sys::raw::new_ping_v4().map(RawSocket::new)
+    }
+
+    /// Creates a new ICMPv6 echo socket.
+    ///
+    /// See [`ping_v4`] for more information.
+    ///
+    /// [`ping_v4`]: #method.ping_v4
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn ping_v6() -> io::Result<RawSocket> {
+        sys::raw::new_ping_v6().map(RawSocket::new)
+    }
+
+    /// Binds the socket to the local address `addr`, limiting the datagrams
+    /// received to those sent to `addr`. The port is ignored for raw
+    /// sockets.
+    pub fn bind(&self, addr: SocketAddr) -> io::Result<()> {
+        sys::raw::bind(self.inner.as_raw_fd(), addr)
+    }
+
+    /// Connects the socket to the remote address `addr`, limiting the
+    /// datagrams received to those from `addr` and allowing [`send`] to be
+    /// used. The port is ignored for raw sockets.
+    ///
+    /// [`send`]: #method.send
+    pub fn connect(&self, addr: SocketAddr) -> io::Result<()> {
+        sys::raw::connect(self.inner.as_raw_fd(), addr)
+    }
+
+    /// Returns the socket address that this socket was bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        sys::raw::local_addr(self.inner.as_raw_fd())
+    }
+
+    /// Sends a datagram to the given `target`.
+    ///
+    /// On success, returns the number of bytes written.
+    pub fn send_to(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
+        self.inner
+            .do_io(|inner| sys::raw::send_to(inner.as_raw_fd(), buf, target))
+    }
+
+    /// Sends a datagram to the remote address the socket is connected to.
+    ///
+    /// On success, returns the number of bytes written.
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.inner
+            .do_io(|inner| sys::raw::send(inner.as_raw_fd(), buf))
+    }
+
+    /// Receives a single datagram.
+    ///
+    /// On success, returns the number of bytes read and the address it came
+    /// from. If `buf` is too small to hold the datagram the remainder is
+    /// discarded.
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.inner
+            .do_io(|inner| sys::raw::recv_from(inner.as_raw_fd(), buf))
+    }
+
+    /// Receives a single datagram from the remote address the socket is
+    /// connected to.
+    ///
+    /// On success, returns the number of bytes read.
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner
+            .do_io(|inner| sys::raw::recv(inner.as_raw_fd(), buf))
+    }
+
+    /// Sets the value of the `IP_HDRINCL` option on this socket.
+    ///
+    /// If enabled the datagrams passed to [`send_to`] must start with the
+    /// IPv4 header, rather than the kernel generating it. Only supported on
+    /// raw IPv4 sockets.
+    ///
+    /// [`send_to`]: #method.send_to
+    pub fn set_header_included(&self, included: bool) -> io::Result<()> {
+        sys::raw::set_header_included(self.inner.as_raw_fd(), included)
+    }
+
+    /// Gets the value of the `IP_HDRINCL` option on this socket.
+    ///
+    /// For more information about this option, see [`set_header_included`].
+    ///
+    /// [`set_header_included`]: #method.set_header_included
+    pub fn header_included(&self) -> io::Result<bool> {
+        sys::raw::header_included(self.inner.as_raw_fd())
+    }
+
+    /// Returns the value of the `SO_ERROR` option.
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        sys::raw::take_error(self.inner.as_raw_fd())
+    }
+}
+
+impl event::Source for RawSocket {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        self.inner.deregister(registry)
+    }
+}
+
+impl fmt::Debug for RawSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut builder = f.debug_struct("RawSocket");
+        builder.field("fd", &self.inner.as_raw_fd());
+        if let Ok(addr) = self.local_addr() {
+            builder.field("local", &addr);
+        }
+        builder.finish()
+    }
+}
+
+impl IntoRawFd for RawSocket {
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_inner().into_raw_fd()
+    }
+}
+
+impl AsRawFd for RawSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl FromRawFd for RawSocket {
+    /// Converts a `RawFd` to a `RawSocket`.
+    ///
+    /// # Notes
+    ///
+    /// The caller is responsible for ensuring that the socket is in
+    /// non-blocking mode.
+    unsafe fn from_raw_fd(fd: RawFd) -> RawSocket {
+        RawSocket::new(File::from_raw_fd(fd))
+    }
+}
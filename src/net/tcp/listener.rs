@@ -5,7 +5,7 @@ use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 use std::os::windows::io::{AsRawSocket, FromRawSocket, IntoRawSocket, RawSocket};
 use std::{fmt, io};
 
-use super::TcpStream;
+use super::{TcpSocket, TcpStream};
 use crate::io_source::IoSource;
 use crate::{event, sys, Interest, Registry, Token};
 
@@ -47,9 +47,22 @@ impl TcpListener {
     /// 1. Create a new TCP socket.
     /// 2. Set the `SO_REUSEADDR` option on the socket on Unix.
     /// 3. Bind the socket to the specified address.
-    /// 4. Calls `listen` on the socket to prepare it to receive new connections.
+    /// 4. Calls `listen` on the socket, with a backlog of 1024, to prepare it
+    ///    to receive new connections.
+    ///
+    /// Use [`TcpSocket`] to set other options, or a different backlog, before
+    /// listening.
+    ///
+    /// [`TcpSocket`]: struct.TcpSocket.html
     pub fn bind(addr: SocketAddr) -> io::Result<TcpListener> {
-        sys::tcp::bind(addr).map(TcpListener::from_std)
+        let socket = TcpSocket::new_for_addr(addr)?;
+        // On platforms with Berkeley-derived sockets, this allows to quickly
+        // rebind a socket, without needing to wait for the OS to clean up the
+        // previous one.
+        #[cfg(not(windows))]
+        socket.set_reuseaddr(true)?;
+        socket.bind(addr)?;
+        socket.listen(1024)
     }
 
     /// Creates a new `TcpListener` from a standard `net::TcpListener`.
@@ -100,6 +113,44 @@ impl TcpListener {
         self.inner.ttl()
     }
 
+    /// Sets the value for the `IP_TOS` option on this socket.
+    ///
+    /// This value sets the type-of-service field, i.e. the DSCP and ECN
+    /// codepoints, that is used in every IPv4 packet sent from this socket.
+    #[cfg(unix)]
+    pub fn set_tos_v4(&self, tos: u32) -> io::Result<()> {
+        sys::tcp::set_tos_v4(self.inner.as_raw_fd(), tos)
+    }
+
+    /// Gets the value of the `IP_TOS` option for this socket.
+    ///
+    /// For more information about this option, see [`set_tos_v4`][link].
+    ///
+    /// [link]: #method.set_tos_v4
+    #[cfg(unix)]
+    pub fn tos_v4(&self) -> io::Result<u32> {
+        sys::tcp::tos_v4(self.inner.as_raw_fd())
+    }
+
+    /// Sets the value for the `IPV6_TCLASS` option on this socket.
+    ///
+    /// This value sets the traffic class field, i.e. the DSCP and ECN
+    /// codepoints, that is used in every IPv6 packet sent from this socket.
+    #[cfg(unix)]
+    pub fn set_tclass_v6(&self, tclass: u32) -> io::Result<()> {
+        sys::tcp::set_tclass_v6(self.inner.as_raw_fd(), tclass)
+    }
+
+    /// Gets the value of the `IPV6_TCLASS` option for this socket.
+    ///
+    /// For more information about this option, see [`set_tclass_v6`][link].
+    ///
+    /// [link]: #method.set_tclass_v6
+    #[cfg(unix)]
+    pub fn tclass_v6(&self) -> io::Result<u32> {
+        sys::tcp::tclass_v6(self.inner.as_raw_fd())
+    }
+
     /// Get the value of the `SO_ERROR` option on this socket.
     ///
     /// This will retrieve the stored error in the underlying socket, clearing
@@ -1,5 +1,8 @@
 mod listener;
 pub use self::listener::TcpListener;
 
+mod socket;
+pub use self::socket::TcpSocket;
+
 mod stream;
 pub use self::stream::TcpStream;
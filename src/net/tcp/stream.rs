@@ -5,7 +5,13 @@ use std::net::{self, Shutdown, SocketAddr};
 use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 #[cfg(windows)]
 use std::os::windows::io::{AsRawSocket, FromRawSocket, IntoRawSocket, RawSocket};
+use std::time::Duration;
 
+use super::TcpSocket;
+#[cfg(unix)]
+use crate::net::MsgFlags;
+#[cfg(target_os = "linux")]
+use crate::net::TcpInfo;
 use crate::io_source::IoSource;
 use crate::{event, sys, Interest, Registry, Token};
 
@@ -47,8 +53,17 @@ pub struct TcpStream {
 impl TcpStream {
     /// Create a new TCP stream and issue a non-blocking connect to the
     /// specified address.
+    ///
+    /// Use [`TcpSocket`] to set options on the socket before connecting.
+    ///
+    /// [`TcpSocket`]: struct.TcpSocket.html
     pub fn connect(addr: SocketAddr) -> io::Result<TcpStream> {
-        sys::tcp::connect(addr).map(TcpStream::from_std)
+        let socket = TcpSocket::new_for_addr(addr)?;
+        // Required for a future `connect_overlapped` operation to be executed
+        // successfully.
+        #[cfg(windows)]
+        socket.bind(sys::tcp::inaddr_any(addr))?;
+        socket.connect(addr)
     }
 
     /// Creates a new `TcpStream` from a standard `net::TcpStream`.
@@ -149,6 +164,346 @@ impl TcpStream {
         self.inner.ttl()
     }
 
+    /// Sets the value for the `IP_TOS` option on this socket.
+    ///
+    /// This value sets the type-of-service field, i.e. the DSCP and ECN
+    /// codepoints, that is used in every IPv4 packet sent from this socket.
+    #[cfg(unix)]
+    pub fn set_tos_v4(&self, tos: u32) -> io::Result<()> {
+        sys::tcp::set_tos_v4(self.inner.as_raw_fd(), tos)
+    }
+
+    /// Gets the value of the `IP_TOS` option for this socket.
+    ///
+    /// For more information about this option, see [`set_tos_v4`][link].
+    ///
+    /// [link]: #method.set_tos_v4
+    #[cfg(unix)]
+    pub fn tos_v4(&self) -> io::Result<u32> {
+        sys::tcp::tos_v4(self.inner.as_raw_fd())
+    }
+
+    /// Sets the value for the `IPV6_TCLASS` option on this socket.
+    ///
+    /// This value sets the traffic class field, i.e. the DSCP and ECN
+    /// codepoints, that is used in every IPv6 packet sent from this socket.
+    #[cfg(unix)]
+    pub fn set_tclass_v6(&self, tclass: u32) -> io::Result<()> {
+        sys::tcp::set_tclass_v6(self.inner.as_raw_fd(), tclass)
+    }
+
+    /// Gets the value of the `IPV6_TCLASS` option for this socket.
+    ///
+    /// For more information about this option, see [`set_tclass_v6`][link].
+    ///
+    /// [link]: #method.set_tclass_v6
+    #[cfg(unix)]
+    pub fn tclass_v6(&self) -> io::Result<u32> {
+        sys::tcp::tclass_v6(self.inner.as_raw_fd())
+    }
+
+    /// Sets the value of the `SO_KEEPALIVE` option on this socket.
+    ///
+    /// If enabled keepalive probes are sent once the connection has been idle
+    /// for some time, see [`set_keepalive_idle`].
+    ///
+    /// [`set_keepalive_idle`]: #method.set_keepalive_idle
+    pub fn set_keepalive(&self, keepalive: bool) -> io::Result<()> {
+        sys::tcp::set_keepalive(self.socket(), keepalive)
+    }
+
+    /// Gets the value of the `SO_KEEPALIVE` option on this socket.
+    ///
+    /// For more information about this option, see [`set_keepalive`][link].
+    ///
+    /// [link]: #method.set_keepalive
+    pub fn keepalive(&self) -> io::Result<bool> {
+        sys::tcp::keepalive(self.socket())
+    }
+
+    /// Sets the amount of time the connection must be idle before the first
+    /// keepalive probe is sent, in whole seconds.
+    ///
+    /// This sets the `TCP_KEEPIDLE` option, or `TCP_KEEPALIVE` on iOS and
+    /// macOS.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd"
+    ))]
+    pub fn set_keepalive_idle(&self, idle: Duration) -> io::Result<()> {
+        sys::tcp::set_keepalive_idle(self.socket(), idle)
+    }
+
+    /// Gets the amount of time the connection must be idle before the first
+    /// keepalive probe is sent.
+    ///
+    /// For more information about this option, see
+    /// [`set_keepalive_idle`][link].
+    ///
+    /// [link]: #method.set_keepalive_idle
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd"
+    ))]
+    pub fn keepalive_idle(&self) -> io::Result<Duration> {
+        sys::tcp::keepalive_idle(self.socket())
+    }
+
+    /// Sets the value of the `TCP_KEEPINTVL` option on this socket.
+    ///
+    /// This is the time between two keepalive probes, in whole seconds.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd"
+    ))]
+    pub fn set_keepalive_interval(&self, interval: Duration) -> io::Result<()> {
+        sys::tcp::set_keepalive_interval(self.socket(), interval)
+    }
+
+    /// Gets the value of the `TCP_KEEPINTVL` option on this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_keepalive_interval`][link].
+    ///
+    /// [link]: #method.set_keepalive_interval
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd"
+    ))]
+    pub fn keepalive_interval(&self) -> io::Result<Duration> {
+        sys::tcp::keepalive_interval(self.socket())
+    }
+
+    /// Sets the value of the `TCP_KEEPCNT` option on this socket.
+    ///
+    /// This is the number of unanswered keepalive probes after which the
+    /// connection is dropped.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd"
+    ))]
+    pub fn set_keepalive_count(&self, count: u32) -> io::Result<()> {
+        sys::tcp::set_keepalive_count(self.socket(), count)
+    }
+
+    /// Gets the value of the `TCP_KEEPCNT` option on this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_keepalive_count`][link].
+    ///
+    /// [link]: #method.set_keepalive_count
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd"
+    ))]
+    pub fn keepalive_count(&self) -> io::Result<u32> {
+        sys::tcp::keepalive_count(self.socket())
+    }
+
+    /// Sets the value of the `SO_LINGER` option on this socket.
+    ///
+    /// If `dur` is `Some` the socket will linger for at most the given
+    /// duration (in whole seconds) when closed, while unsent data remains.
+    /// `None` disables lingering.
+    pub fn set_linger(&self, dur: Option<Duration>) -> io::Result<()> {
+        sys::tcp::set_linger(self.socket(), dur)
+    }
+
+    /// Gets the value of the `SO_LINGER` option on this socket.
+    ///
+    /// For more information about this option, see [`set_linger`][link].
+    ///
+    /// [link]: #method.set_linger
+    pub fn linger(&self) -> io::Result<Option<Duration>> {
+        sys::tcp::linger(self.socket())
+    }
+
+    /// Sets the value of the `SO_SNDBUF` option on this socket.
+    ///
+    /// The operating system may adjust (e.g. double on Linux) the requested
+    /// size, use [`send_buffer_size`] to retrieve the actual value.
+    ///
+    /// [`send_buffer_size`]: #method.send_buffer_size
+    pub fn set_send_buffer_size(&self, size: u32) -> io::Result<()> {
+        sys::tcp::set_send_buffer_size(self.socket(), size)
+    }
+
+    /// Gets the value of the `SO_SNDBUF` option on this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_send_buffer_size`][link].
+    ///
+    /// [link]: #method.set_send_buffer_size
+    pub fn send_buffer_size(&self) -> io::Result<u32> {
+        sys::tcp::send_buffer_size(self.socket())
+    }
+
+    /// Sets the value of the `SO_RCVBUF` option on this socket.
+    ///
+    /// The operating system may adjust (e.g. double on Linux) the requested
+    /// size, use [`recv_buffer_size`] to retrieve the actual value.
+    ///
+    /// [`recv_buffer_size`]: #method.recv_buffer_size
+    pub fn set_recv_buffer_size(&self, size: u32) -> io::Result<()> {
+        sys::tcp::set_recv_buffer_size(self.socket(), size)
+    }
+
+    /// Gets the value of the `SO_RCVBUF` option on this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_recv_buffer_size`][link].
+    ///
+    /// [link]: #method.set_recv_buffer_size
+    pub fn recv_buffer_size(&self) -> io::Result<u32> {
+        sys::tcp::recv_buffer_size(self.socket())
+    }
+
+    /// Sets the value of the `TCP_USER_TIMEOUT` option on this socket.
+    ///
+    /// This is the maximum amount of time transmitted data may remain
+    /// unacknowledged before the connection is forcibly closed. `None` uses
+    /// the system default.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn set_user_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        sys::tcp::set_user_timeout(self.socket(), timeout)
+    }
+
+    /// Gets the value of the `TCP_USER_TIMEOUT` option on this socket.
+    ///
+    /// For more information about this option, see [`set_user_timeout`][link].
+    ///
+    /// [link]: #method.set_user_timeout
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn user_timeout(&self) -> io::Result<Option<Duration>> {
+        sys::tcp::user_timeout(self.socket())
+    }
+
+    /// Sets the value of the `TCP_QUICKACK` option on this socket.
+    ///
+    /// If set acknowledgements are sent immediately, rather than delayed. This
+    /// option isn't permanent, the kernel may switch back to delayed
+    /// acknowledgements at any point.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn set_quickack(&self, quickack: bool) -> io::Result<()> {
+        sys::tcp::set_quickack(self.socket(), quickack)
+    }
+
+    /// Gets the value of the `TCP_QUICKACK` option on this socket.
+    ///
+    /// For more information about this option, see [`set_quickack`][link].
+    ///
+    /// [link]: #method.set_quickack
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn quickack(&self) -> io::Result<bool> {
+        sys::tcp::quickack(self.socket())
+    }
+
+    /// Sets the value of the `TCP_CORK` option on this socket.
+    ///
+    /// If set partial frames are not sent until the option is cleared again,
+    /// or a full frame can be sent (with a 200 millisecond ceiling).
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn set_cork(&self, cork: bool) -> io::Result<()> {
+        sys::tcp::set_cork(self.socket(), cork)
+    }
+
+    /// Gets the value of the `TCP_CORK` option on this socket.
+    ///
+    /// For more information about this option, see [`set_cork`][link].
+    ///
+    /// [link]: #method.set_cork
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn cork(&self) -> io::Result<bool> {
+        sys::tcp::cork(self.socket())
+    }
+
+    /// Sets the value of the `TCP_NOTSENT_LOWAT` option on this socket.
+    ///
+    /// This limits the amount of unsent data in the socket's send buffer; the
+    /// stream is only reported as writable if less than `lowat` bytes are
+    /// waiting to be sent.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn set_notsent_lowat(&self, lowat: u32) -> io::Result<()> {
+        sys::tcp::set_notsent_lowat(self.socket(), lowat)
+    }
+
+    /// Gets the value of the `TCP_NOTSENT_LOWAT` option on this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_notsent_lowat`][link].
+    ///
+    /// [link]: #method.set_notsent_lowat
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn notsent_lowat(&self) -> io::Result<u32> {
+        sys::tcp::notsent_lowat(self.socket())
+    }
+
+    /// Sets the congestion control algorithm used by this socket, using the
+    /// `TCP_CONGESTION` option, e.g. `"cubic"` or `"reno"`.
+    ///
+    /// The algorithms available to unprivileged processes are listed in
+    /// `/proc/sys/net/ipv4/tcp_allowed_congestion_control`.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn set_congestion(&self, algorithm: &str) -> io::Result<()> {
+        sys::tcp::set_congestion(self.socket(), algorithm)
+    }
+
+    /// Gets the congestion control algorithm used by this socket.
+    ///
+    /// For more information about this option, see [`set_congestion`][link].
+    ///
+    /// [link]: #method.set_congestion
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn congestion(&self) -> io::Result<String> {
+        sys::tcp::congestion(self.socket())
+    }
+
+    /// Returns statistics about the connection, using the `TCP_INFO` option.
+    #[cfg(target_os = "linux")]
+    pub fn tcp_info(&self) -> io::Result<TcpInfo> {
+        sys::tcp::tcp_info(self.socket())
+    }
+
+    #[cfg(unix)]
+    fn socket(&self) -> sys::tcp::TcpSocket {
+        self.inner.as_raw_fd()
+    }
+
+    #[cfg(windows)]
+    fn socket(&self) -> sys::tcp::TcpSocket {
+        self.inner.as_raw_socket() as _
+    }
+
     /// Get the value of the `SO_ERROR` option on this socket.
     ///
     /// This will retrieve the stored error in the underlying socket, clearing
@@ -165,7 +520,41 @@ impl TcpStream {
     /// Successive calls return the same data. This is accomplished by passing
     /// `MSG_PEEK` as a flag to the underlying recv system call.
     pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
-        self.inner.peek(buf)
+        self.inner.do_io(|inner| inner.peek(buf))
+    }
+
+    /// Receives data on the socket from the remote peer, passing `flags` to
+    /// the underlying `recv(2)` system call. On success, returns the number
+    /// of bytes read.
+    ///
+    /// # Notes
+    ///
+    /// The socket is in non-blocking mode, so [`MsgFlags::WAITALL`] doesn't
+    /// block; it may still return fewer bytes than requested, or an error
+    /// with kind `WouldBlock`, if not enough data is available yet.
+    ///
+    /// [`MsgFlags::WAITALL`]: struct.MsgFlags.html#associatedconstant.WAITALL
+    #[cfg(unix)]
+    pub fn recv_with_flags(&self, buf: &mut [u8], flags: MsgFlags) -> io::Result<usize> {
+        self.inner
+            .do_io(|inner| sys::tcp::recv_with_flags(inner.as_raw_fd(), buf, flags))
+    }
+
+    /// Sends data on the socket to the remote peer, passing `flags` to the
+    /// underlying `send(2)` system call. On success, returns the number of
+    /// bytes written.
+    ///
+    /// For example [`MsgFlags::NOSIGNAL`] returns an `EPIPE` error, rather
+    /// than raising `SIGPIPE`, if the peer closed the connection and
+    /// [`MsgFlags::MORE`] signals more data will follow, delaying the
+    /// transmission of partial frames.
+    ///
+    /// [`MsgFlags::NOSIGNAL`]: struct.MsgFlags.html#associatedconstant.NOSIGNAL
+    /// [`MsgFlags::MORE`]: struct.MsgFlags.html#associatedconstant.MORE
+    #[cfg(unix)]
+    pub fn send_with_flags(&self, buf: &[u8], flags: MsgFlags) -> io::Result<usize> {
+        self.inner
+            .do_io(|inner| sys::tcp::send_with_flags(inner.as_raw_fd(), buf, flags))
     }
 }
 
@@ -8,10 +8,13 @@
 //! [portability guidelines]: ../struct.Poll.html#portability
 
 use crate::io_source::IoSource;
+#[cfg(unix)]
+use crate::net::{MsgFlags, RecvMsg};
 use crate::{event, sys, Interest, Registry, Token};
 
 use std::fmt;
-use std::io;
+use std::io::{self, IoSlice, IoSliceMut};
+use std::mem;
 use std::net;
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
 #[cfg(unix)]
@@ -78,7 +81,7 @@ use std::os::windows::io::{AsRawSocket, FromRawSocket, IntoRawSocket, RawSocket}
 ///                 let num_recv = echoer_socket.recv(&mut buffer)?;
 ///                 println!("echo {:?} -> {:?}", buffer, num_recv);
 ///                 buffer = [0; 9];
-///                 # drop(buffer); // Silence unused assignment warning.
+///                 # let _ = buffer; // Silence unused assignment warning.
 ///                 # return Ok(());
 ///             }
 ///             _ => unreachable!()
@@ -116,8 +119,12 @@ impl UdpSocket {
     /// #    Ok(())
     /// # }
     /// ```
+    ///
+    /// Use [`UdpSocketBuilder`] to set options on the socket before binding.
+    ///
+    /// [`UdpSocketBuilder`]: struct.UdpSocketBuilder.html
     pub fn bind(addr: SocketAddr) -> io::Result<UdpSocket> {
-        sys::udp::bind(addr).map(UdpSocket::from_std)
+        UdpSocketBuilder::new_for_addr(addr)?.bind(addr)
     }
 
     /// Creates a new `UdpSocket` from a standard `net::UdpSocket`.
@@ -289,6 +296,327 @@ impl UdpSocket {
         self.inner.do_io(|inner| inner.peek(buf))
     }
 
+    /// Sends data from multiple buffers on the socket to the given address. On
+    /// success, returns the number of bytes written.
+    ///
+    /// The buffers are sent as a single datagram.
+    #[cfg(unix)]
+    pub fn send_vectored_to(&self, bufs: &[IoSlice<'_>], target: SocketAddr) -> io::Result<usize> {
+        self.send_msg(bufs, Some(target), &[], MsgFlags::empty())
+    }
+
+    /// Receives a single datagram into multiple buffers. On success, returns
+    /// the number of bytes read and the address from whence the data came.
+    ///
+    /// The buffers are filled in order. If the datagram is larger than the
+    /// buffers the excess data is lost, use [`recv_msg`] to detect this.
+    ///
+    /// Like [`recv_from`] this returns an error if the source address isn't
+    /// an IPv4 or IPv6 address, after the datagram is read. [`recv_msg`]
+    /// returns the data without an address in that case.
+    ///
+    /// [`recv_msg`]: #method.recv_msg
+    /// [`recv_from`]: #method.recv_from
+    #[cfg(unix)]
+    pub fn recv_vectored_from(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> io::Result<(usize, SocketAddr)> {
+        self.recv_msg(bufs, &mut [], MsgFlags::empty())
+            .and_then(|msg| match msg.addr() {
+                Some(addr) => Ok((msg.len(), addr)),
+                None => Err(io::ErrorKind::InvalidInput.into()),
+            })
+    }
+
+    /// Sends a datagram using `sendmsg(2)`. On success, returns the number of
+    /// bytes written.
+    ///
+    /// The datagram is made up of all `bufs`. If `target` is `None` the
+    /// datagram is sent to the address previously bound via [`connect`].
+    /// `control` holds the ancillary data (control messages) to send, see
+    /// [`ControlMessageEncoder`].
+    ///
+    /// [`connect`]: #method.connect
+    /// [`ControlMessageEncoder`]: struct.ControlMessageEncoder.html
+    #[cfg(unix)]
+    pub fn send_msg(
+        &self,
+        bufs: &[IoSlice<'_>],
+        target: Option<SocketAddr>,
+        control: &[u8],
+        flags: MsgFlags,
+    ) -> io::Result<usize> {
+        self.inner
+            .do_io(|inner| sys::udp::send_msg(inner, bufs, target, control, flags))
+    }
+
+    /// Receives a datagram using `recvmsg(2)`.
+    ///
+    /// The datagram is read into `bufs`, any ancillary data (control
+    /// messages) is written into `control`. The returned [`RecvMsg`] holds
+    /// the number of bytes read, the source address (if any), the length of
+    /// the ancillary data and the flags of the received message.
+    ///
+    /// [`RecvMsg`]: struct.RecvMsg.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::io::IoSliceMut;
+    ///
+    /// use mio::net::{ControlMessages, MsgFlags, UdpSocket};
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:0".parse()?)?;
+    /// socket.send_to(b"Hello world", socket.local_addr()?)?;
+    /// # std::thread::sleep(std::time::Duration::from_millis(50));
+    ///
+    /// // We must check if the socket is readable before calling recv_msg,
+    /// // or we could run into a WouldBlock error.
+    ///
+    /// let mut buf = [0; 5];
+    /// let mut control = [0; 64];
+    /// let msg = socket.recv_msg(&mut [IoSliceMut::new(&mut buf)], &mut control, MsgFlags::empty())?;
+    /// assert_eq!(&buf, b"Hello");
+    /// // The datagram didn't fit in our buffer.
+    /// assert!(msg.flags().contains(MsgFlags::TRUNC));
+    /// for cmsg in ControlMessages::new(&control[..msg.control_len()]) {
+    ///     println!("control message: {:?}", cmsg);
+    /// }
+    /// #
+    /// #    Ok(())
+    /// # }
+    /// ```
+    #[cfg(unix)]
+    pub fn recv_msg(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        control: &mut [u8],
+        flags: MsgFlags,
+    ) -> io::Result<RecvMsg> {
+        self.inner
+            .do_io(|inner| sys::udp::recv_msg(inner, bufs, control, flags))
+    }
+
+    /// Sends multiple datagrams on the socket, `bufs[i]` is sent to
+    /// `targets[i]`. On success, returns the number of datagrams sent.
+    ///
+    /// At most `min(bufs.len(), targets.len())` datagrams are sent, but fewer
+    /// may be sent if the socket's send buffer is full. The remaining
+    /// datagrams should be sent once the socket becomes writable again.
+    ///
+    /// On Linux and Android this uses a single `sendmmsg(2)` system call (for
+    /// up to 32 datagrams), on other platforms this calls `send_to` for each
+    /// datagram.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::io::IoSlice;
+    ///
+    /// use mio::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:0".parse()?)?;
+    /// let addr = socket.local_addr()?;
+    ///
+    /// // We must check if the socket is writable before calling
+    /// // send_to_batch, or we could run into a WouldBlock error.
+    ///
+    /// let bufs = [IoSlice::new(b"Hello"), IoSlice::new(b"world")];
+    /// let sent = socket.send_to_batch(&bufs, &[addr, addr])?;
+    /// assert_eq!(sent, 2);
+    /// #
+    /// #    Ok(())
+    /// # }
+    /// ```
+    pub fn send_to_batch(&self, bufs: &[IoSlice<'_>], targets: &[SocketAddr]) -> io::Result<usize> {
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        {
+            self.inner
+                .do_io(|inner| sys::udp::send_to_batch(inner, bufs, targets))
+        }
+
+        #[cfg(not(any(target_os = "android", target_os = "linux")))]
+        {
+            self.inner.do_io(|inner| {
+                batch(bufs.len().min(targets.len()), |i| {
+                    inner.send_to(&bufs[i], targets[i]).map(|_| ())
+                })
+            })
+        }
+    }
+
+    /// Receives multiple datagrams from the socket, datagram `i` is read into
+    /// `bufs[i]`. On success, returns the number of datagrams received. For
+    /// each received datagram the number of bytes read and the address from
+    /// whence the data came is written into `results[i]`. The address is
+    /// `None` if the kernel didn't return an IPv4 or IPv6 address.
+    ///
+    /// At most `min(bufs.len(), results.len())` datagrams are received. If
+    /// a datagram is larger than its buffer the excess data is lost.
+    ///
+    /// On Linux and Android this uses a single `recvmmsg(2)` system call (for
+    /// up to 32 datagrams), on other platforms this calls `recv_from` for
+    /// each datagram.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::io::IoSliceMut;
+    ///
+    /// use mio::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:0".parse()?)?;
+    /// let addr = socket.local_addr()?;
+    /// socket.send_to(b"Hello", addr)?;
+    /// socket.send_to(b"world", addr)?;
+    /// # std::thread::sleep(std::time::Duration::from_millis(50));
+    ///
+    /// // We must check if the socket is readable before calling
+    /// // recv_from_batch, or we could run into a WouldBlock error.
+    ///
+    /// let mut buf1 = [0; 16];
+    /// let mut buf2 = [0; 16];
+    /// let mut bufs = [IoSliceMut::new(&mut buf1), IoSliceMut::new(&mut buf2)];
+    /// let mut results = [(0, None); 2];
+    /// let received = socket.recv_from_batch(&mut bufs, &mut results)?;
+    /// for &(n, from) in &results[..received] {
+    ///     println!("received {} bytes from {:?}", n, from);
+    /// }
+    /// #
+    /// #    Ok(())
+    /// # }
+    /// ```
+    pub fn recv_from_batch(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        results: &mut [(usize, Option<SocketAddr>)],
+    ) -> io::Result<usize> {
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        {
+            self.inner
+                .do_io(|inner| sys::udp::recv_from_batch(inner, bufs, results))
+        }
+
+        #[cfg(not(any(target_os = "android", target_os = "linux")))]
+        {
+            self.inner.do_io(|inner| {
+                batch(bufs.len().min(results.len()), |i| {
+                    inner
+                        .recv_from(&mut bufs[i])
+                        .map(|(n, addr)| results[i] = (n, Some(addr)))
+                })
+            })
+        }
+    }
+
+    /// Sets the value of the `UDP_GRO` option for this socket.
+    ///
+    /// When enabled, the kernel may coalesce multiple received datagrams into
+    /// a single buffer. Use [`recv_msg`] to receive them and
+    /// [`SegmentSize::from_control_message`] to determine the size of the
+    /// datagrams.
+    ///
+    /// [`recv_msg`]: #method.recv_msg
+    /// [`SegmentSize::from_control_message`]: struct.SegmentSize.html#method.from_control_message
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn set_gro(&self, on: bool) -> io::Result<()> {
+        sys::udp::set_gro(&self.inner, on)
+    }
+
+    /// Gets the value of the `UDP_GRO` option for this socket.
+    ///
+    /// For more information about this option, see [`set_gro`][link].
+    ///
+    /// [link]: #method.set_gro
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn gro(&self) -> io::Result<bool> {
+        sys::udp::gro(&self.inner)
+    }
+
+    /// Sets the value of the `UDP_SEGMENT` option for this socket.
+    ///
+    /// When set to a non-zero value, all sends larger than `size` are split
+    /// into datagrams of `size` bytes by the kernel. To set the segment size
+    /// for a single send use [`send_to_gso`], or [`send_msg`] with a
+    /// [`SegmentSize`] control message.
+    ///
+    /// [`send_to_gso`]: #method.send_to_gso
+    /// [`send_msg`]: #method.send_msg
+    /// [`SegmentSize`]: struct.SegmentSize.html
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn set_gso_segment_size(&self, size: u16) -> io::Result<()> {
+        sys::udp::set_gso_segment_size(&self.inner, size)
+    }
+
+    /// Gets the value of the `UDP_SEGMENT` option for this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_gso_segment_size`][link].
+    ///
+    /// [link]: #method.set_gso_segment_size
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn gso_segment_size(&self) -> io::Result<u16> {
+        sys::udp::gso_segment_size(&self.inner)
+    }
+
+    /// Sends `buf` to `target` as multiple datagrams of `segment_size` bytes
+    /// using UDP generic segmentation offload (GSO). On success, returns the
+    /// number of bytes written.
+    ///
+    /// `buf` is passed to the kernel in a single call with a [`SegmentSize`]
+    /// control message, the kernel (or network device) splits it into
+    /// datagrams of `segment_size` bytes, the last datagram may be smaller.
+    ///
+    /// If the kernel doesn't support segmentation offload (before Linux 4.18)
+    /// or rejects it, e.g. because the network device doesn't support it or
+    /// `buf` holds more than 64 segments, this falls back to sending each
+    /// segment as a separate datagram. In that case fewer bytes may be written
+    /// than `buf.len()`, the remaining bytes should be sent once the socket
+    /// becomes writable again.
+    ///
+    /// [`SegmentSize`]: struct.SegmentSize.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use mio::net::UdpSocket;
+    ///
+    /// let socket = UdpSocket::bind("127.0.0.1:0".parse()?)?;
+    ///
+    /// // We must check if the socket is writable before calling send_to_gso,
+    /// // or we could run into a WouldBlock error.
+    ///
+    /// // Sends three datagrams: two of 1200 bytes and one of 600 bytes.
+    /// let bytes_sent = socket.send_to_gso(&[1; 3000], socket.local_addr()?, 1200)?;
+    /// assert_eq!(bytes_sent, 3000);
+    /// #
+    /// #    Ok(())
+    /// # }
+    /// ```
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn send_to_gso(
+        &self,
+        buf: &[u8],
+        target: SocketAddr,
+        segment_size: u16,
+    ) -> io::Result<usize> {
+        self.inner
+            .do_io(|inner| sys::udp::send_to_gso(inner, buf, target, segment_size))
+    }
+
     /// Connects the UDP socket setting the default destination for `send()`
     /// and limiting packets that are read via `recv` from the address specified
     /// in `addr`.
@@ -498,6 +826,160 @@ impl UdpSocket {
         self.inner.leave_multicast_v6(multiaddr, interface)
     }
 
+    /// Sets the value of the `IP_PKTINFO` option for this socket.
+    ///
+    /// When enabled, the destination address and interface index of received
+    /// IPv4 datagrams are available as control message when using
+    /// [`recv_msg`], see [`PacketInfo`].
+    ///
+    /// [`recv_msg`]: #method.recv_msg
+    /// [`PacketInfo`]: struct.PacketInfo.html
+    #[cfg(any(
+        target_os = "android",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos"
+    ))]
+    pub fn set_recv_pktinfo_v4(&self, on: bool) -> io::Result<()> {
+        sys::udp::set_recv_pktinfo_v4(&self.inner, on)
+    }
+
+    /// Gets the value of the `IP_PKTINFO` option for this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_recv_pktinfo_v4`][link].
+    ///
+    /// [link]: #method.set_recv_pktinfo_v4
+    #[cfg(any(
+        target_os = "android",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos"
+    ))]
+    pub fn recv_pktinfo_v4(&self) -> io::Result<bool> {
+        sys::udp::recv_pktinfo_v4(&self.inner)
+    }
+
+    /// Sets the value of the `IPV6_RECVPKTINFO` option for this socket.
+    ///
+    /// When enabled, the destination address and interface index of received
+    /// IPv6 datagrams are available as control message when using
+    /// [`recv_msg`], see [`PacketInfo`].
+    ///
+    /// [`recv_msg`]: #method.recv_msg
+    /// [`PacketInfo`]: struct.PacketInfo.html
+    #[cfg(unix)]
+    pub fn set_recv_pktinfo_v6(&self, on: bool) -> io::Result<()> {
+        sys::udp::set_recv_pktinfo_v6(&self.inner, on)
+    }
+
+    /// Gets the value of the `IPV6_RECVPKTINFO` option for this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_recv_pktinfo_v6`][link].
+    ///
+    /// [link]: #method.set_recv_pktinfo_v6
+    #[cfg(unix)]
+    pub fn recv_pktinfo_v6(&self) -> io::Result<bool> {
+        sys::udp::recv_pktinfo_v6(&self.inner)
+    }
+
+    /// Sets the value of the `IP_RECVTOS` option for this socket.
+    ///
+    /// When enabled, the type-of-service field of received IPv4 datagrams is
+    /// available as control message when using [`recv_msg`], see [`Tos`].
+    ///
+    /// [`recv_msg`]: #method.recv_msg
+    /// [`Tos`]: struct.Tos.html
+    #[cfg(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos"
+    ))]
+    pub fn set_recv_tos_v4(&self, on: bool) -> io::Result<()> {
+        sys::udp::set_recv_tos_v4(&self.inner, on)
+    }
+
+    /// Gets the value of the `IP_RECVTOS` option for this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_recv_tos_v4`][link].
+    ///
+    /// [link]: #method.set_recv_tos_v4
+    #[cfg(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos"
+    ))]
+    pub fn recv_tos_v4(&self) -> io::Result<bool> {
+        sys::udp::recv_tos_v4(&self.inner)
+    }
+
+    /// Sets the value of the `IPV6_RECVTCLASS` option for this socket.
+    ///
+    /// When enabled, the traffic class field of received IPv6 datagrams is
+    /// available as control message when using [`recv_msg`], see [`Tos`].
+    ///
+    /// [`recv_msg`]: #method.recv_msg
+    /// [`Tos`]: struct.Tos.html
+    #[cfg(unix)]
+    pub fn set_recv_tclass_v6(&self, on: bool) -> io::Result<()> {
+        sys::udp::set_recv_tclass_v6(&self.inner, on)
+    }
+
+    /// Gets the value of the `IPV6_RECVTCLASS` option for this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_recv_tclass_v6`][link].
+    ///
+    /// [link]: #method.set_recv_tclass_v6
+    #[cfg(unix)]
+    pub fn recv_tclass_v6(&self) -> io::Result<bool> {
+        sys::udp::recv_tclass_v6(&self.inner)
+    }
+
+    /// Sets the value for the `IP_TOS` option on this socket.
+    ///
+    /// This value sets the type-of-service field, i.e. the DSCP and ECN
+    /// codepoints, that is used in every IPv4 packet sent from this socket.
+    #[cfg(unix)]
+    pub fn set_tos_v4(&self, tos: u32) -> io::Result<()> {
+        sys::udp::set_tos_v4(self.inner.as_raw_fd(), tos)
+    }
+
+    /// Gets the value of the `IP_TOS` option for this socket.
+    ///
+    /// For more information about this option, see [`set_tos_v4`][link].
+    ///
+    /// [link]: #method.set_tos_v4
+    #[cfg(unix)]
+    pub fn tos_v4(&self) -> io::Result<u32> {
+        sys::udp::tos_v4(self.inner.as_raw_fd())
+    }
+
+    /// Sets the value for the `IPV6_TCLASS` option on this socket.
+    ///
+    /// This value sets the traffic class field, i.e. the DSCP and ECN
+    /// codepoints, that is used in every IPv6 packet sent from this socket.
+    #[cfg(unix)]
+    pub fn set_tclass_v6(&self, tclass: u32) -> io::Result<()> {
+        sys::udp::set_tclass_v6(self.inner.as_raw_fd(), tclass)
+    }
+
+    /// Gets the value of the `IPV6_TCLASS` option for this socket.
+    ///
+    /// For more information about this option, see [`set_tclass_v6`][link].
+    ///
+    /// [link]: #method.set_tclass_v6
+    #[cfg(unix)]
+    pub fn tclass_v6(&self) -> io::Result<u32> {
+        sys::udp::tclass_v6(self.inner.as_raw_fd())
+    }
+
     /// Get the value of the `SO_ERROR` option on this socket.
     ///
     /// This will retrieve the stored error in the underlying socket, clearing
@@ -508,6 +990,22 @@ impl UdpSocket {
     }
 }
 
+/// Calls `f` for `0..n`, stopping at the first error. Returns the number of
+/// successful calls, or the error if the first call failed.
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+fn batch<F>(n: usize, mut f: F) -> io::Result<usize>
+where
+    F: FnMut(usize) -> io::Result<()>,
+{
+    for i in 0..n {
+        if let Err(err) = f(i) {
+            // Report the error on the next call, if it persists.
+            return if i == 0 { Err(err) } else { Ok(i) };
+        }
+    }
+    Ok(n)
+}
+
 impl event::Source for UdpSocket {
     fn register(
         &mut self,
@@ -591,3 +1089,243 @@ impl FromRawSocket for UdpSocket {
         UdpSocket::from_std(FromRawSocket::from_raw_socket(socket))
     }
 }
+
+/// A non-blocking UDP socket used to configure a [`UdpSocket`] before it's
+/// bound.
+///
+/// This allows options such as `SO_REUSEPORT`, the buffer sizes or
+/// `IPV6_V6ONLY` to be set, which must be done before calling `bind(2)`.
+///
+/// The socket will be closed when the value is dropped.
+///
+/// [`UdpSocket`]: struct.UdpSocket.html
+///
+/// # Examples
+///
+/// Sharding a single UDP port across multiple sockets, e.g. one per thread
+/// each with its own `Poll` instance. The kernel distributes incoming
+/// datagrams across the sockets.
+///
+#[cfg_attr(all(unix, not(target_os = "solaris")), doc = "```")]
+#[cfg_attr(not(all(unix, not(target_os = "solaris"))), doc = "```ignore")]
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use mio::net::UdpSocketBuilder;
+///
+/// let builder = UdpSocketBuilder::new_v4()?;
+/// builder.set_reuseport(true)?;
+/// builder.set_recv_buffer_size(256 * 1024)?;
+/// let socket1 = builder.bind("127.0.0.1:0".parse()?)?;
+/// let addr = socket1.local_addr()?;
+///
+/// // Another socket bound to the same address.
+/// let builder = UdpSocketBuilder::new_v4()?;
+/// builder.set_reuseport(true)?;
+/// let socket2 = builder.bind(addr)?;
+/// #     drop((socket1, socket2));
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct UdpSocketBuilder {
+    sys: sys::udp::UdpSocketBuilder,
+}
+
+impl UdpSocketBuilder {
+    /// Create a new IPv4 UDP socket.
+    ///
+    /// This calls `socket(2)`.
+    pub fn new_v4() -> io::Result<UdpSocketBuilder> {
+        sys::udp::new_v4_socket().map(|sys| UdpSocketBuilder { sys })
+    }
+
+    /// Create a new IPv6 UDP socket.
+    ///
+    /// This calls `socket(2)`.
+    pub fn new_v6() -> io::Result<UdpSocketBuilder> {
+        sys::udp::new_v6_socket().map(|sys| UdpSocketBuilder { sys })
+    }
+
+    pub(crate) fn new_for_addr(addr: SocketAddr) -> io::Result<UdpSocketBuilder> {
+        match addr {
+            SocketAddr::V4(..) => UdpSocketBuilder::new_v4(),
+            SocketAddr::V6(..) => UdpSocketBuilder::new_v6(),
+        }
+    }
+
+    /// Bind the socket to `addr`, converting it into a `UdpSocket`.
+    pub fn bind(self, addr: SocketAddr) -> io::Result<UdpSocket> {
+        let socket = sys::udp::bind(self.sys, addr)?;
+        // Don't close the socket.
+        mem::forget(self);
+        Ok(UdpSocket::from_std(socket))
+    }
+
+    /// Sets the value of the `SO_REUSEADDR` option on this socket.
+    pub fn set_reuseaddr(&self, reuseaddr: bool) -> io::Result<()> {
+        sys::udp::set_reuseaddr(self.sys, reuseaddr)
+    }
+
+    /// Gets the value of the `SO_REUSEADDR` option on this socket.
+    ///
+    /// For more information about this option, see [`set_reuseaddr`][link].
+    ///
+    /// [link]: #method.set_reuseaddr
+    pub fn reuseaddr(&self) -> io::Result<bool> {
+        sys::udp::reuseaddr(self.sys)
+    }
+
+    /// Sets the value of the `SO_REUSEPORT` option on this socket.
+    ///
+    /// This allows multiple sockets to bind to the same address, if all of
+    /// them set this option. On Linux incoming datagrams are distributed
+    /// evenly across those sockets.
+    ///
+    /// Only available on Unix (except Solaris).
+    #[cfg(all(unix, not(target_os = "solaris")))]
+    pub fn set_reuseport(&self, reuseport: bool) -> io::Result<()> {
+        sys::udp::set_reuseport(self.sys, reuseport)
+    }
+
+    /// Gets the value of the `SO_REUSEPORT` option on this socket.
+    ///
+    /// For more information about this option, see [`set_reuseport`][link].
+    ///
+    /// [link]: #method.set_reuseport
+    #[cfg(all(unix, not(target_os = "solaris")))]
+    pub fn reuseport(&self) -> io::Result<bool> {
+        sys::udp::reuseport(self.sys)
+    }
+
+    /// Sets the value of the `SO_SNDBUF` option on this socket.
+    ///
+    /// The operating system may adjust (e.g. double on Linux) the requested
+    /// size, use [`send_buffer_size`] to retrieve the actual value.
+    ///
+    /// [`send_buffer_size`]: #method.send_buffer_size
+    pub fn set_send_buffer_size(&self, size: u32) -> io::Result<()> {
+        sys::udp::set_send_buffer_size(self.sys, size)
+    }
+
+    /// Gets the value of the `SO_SNDBUF` option on this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_send_buffer_size`][link].
+    ///
+    /// [link]: #method.set_send_buffer_size
+    pub fn send_buffer_size(&self) -> io::Result<u32> {
+        sys::udp::send_buffer_size(self.sys)
+    }
+
+    /// Sets the value of the `SO_RCVBUF` option on this socket.
+    ///
+    /// The operating system may adjust (e.g. double on Linux) the requested
+    /// size, use [`recv_buffer_size`] to retrieve the actual value.
+    ///
+    /// [`recv_buffer_size`]: #method.recv_buffer_size
+    pub fn set_recv_buffer_size(&self, size: u32) -> io::Result<()> {
+        sys::udp::set_recv_buffer_size(self.sys, size)
+    }
+
+    /// Gets the value of the `SO_RCVBUF` option on this socket.
+    ///
+    /// For more information about this option, see
+    /// [`set_recv_buffer_size`][link].
+    ///
+    /// [link]: #method.set_recv_buffer_size
+    pub fn recv_buffer_size(&self) -> io::Result<u32> {
+        sys::udp::recv_buffer_size(self.sys)
+    }
+
+    /// Sets the value for the `IPV6_V6ONLY` option on this socket.
+    ///
+    /// If this is set to `true` then the socket is restricted to sending and
+    /// receiving IPv6 packets only. In this case two IPv4 and IPv6
+    /// applications can bind the same port at the same time.
+    ///
+    /// If this is set to `false` then the socket can be used to send and
+    /// receive packets from an IPv4-mapped IPv6 address. The default depends
+    /// on the operating system, so set it explicitly for dual-stack sockets.
+    ///
+    /// This is only valid for sockets created with [`new_v6`].
+    ///
+    /// [`new_v6`]: #method.new_v6
+    pub fn set_only_v6(&self, only_v6: bool) -> io::Result<()> {
+        sys::udp::set_only_v6(self.sys, only_v6)
+    }
+
+    /// Gets the value of the `IPV6_V6ONLY` option for this socket.
+    ///
+    /// For more information about this option, see [`set_only_v6`][link].
+    ///
+    /// [link]: #method.set_only_v6
+    pub fn only_v6(&self) -> io::Result<bool> {
+        sys::udp::only_v6(self.sys)
+    }
+}
+
+impl Drop for UdpSocketBuilder {
+    fn drop(&mut self) {
+        sys::udp::close(self.sys);
+    }
+}
+
+#[cfg(unix)]
+impl IntoRawFd for UdpSocketBuilder {
+    fn into_raw_fd(self) -> RawFd {
+        let ret = self.sys;
+        // Avoid closing the socket.
+        mem::forget(self);
+        ret
+    }
+}
+
+#[cfg(unix)]
+impl AsRawFd for UdpSocketBuilder {
+    fn as_raw_fd(&self) -> RawFd {
+        self.sys
+    }
+}
+
+#[cfg(unix)]
+impl FromRawFd for UdpSocketBuilder {
+    /// Converts a `RawFd` to a `UdpSocketBuilder`.
+    ///
+    /// # Notes
+    ///
+    /// The caller is responsible for ensuring that the socket is in
+    /// non-blocking mode.
+    unsafe fn from_raw_fd(fd: RawFd) -> UdpSocketBuilder {
+        UdpSocketBuilder { sys: fd }
+    }
+}
+
+#[cfg(windows)]
+impl IntoRawSocket for UdpSocketBuilder {
+    fn into_raw_socket(self) -> RawSocket {
+        let ret = self.sys as RawSocket;
+        // Avoid closing the socket.
+        mem::forget(self);
+        ret
+    }
+}
+
+#[cfg(windows)]
+impl AsRawSocket for UdpSocketBuilder {
+    fn as_raw_socket(&self) -> RawSocket {
+        self.sys as RawSocket
+    }
+}
+
+#[cfg(windows)]
+impl FromRawSocket for UdpSocketBuilder {
+    /// Converts a `RawSocket` to a `UdpSocketBuilder`.
+    ///
+    /// # Notes
+    ///
+    /// The caller is responsible for ensuring that the socket is in
+    /// non-blocking mode.
+    unsafe fn from_raw_socket(socket: RawSocket) -> UdpSocketBuilder {
+        UdpSocketBuilder { sys: socket as _ }
+    }
+}
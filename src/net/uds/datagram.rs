@@ -1,4 +1,7 @@
 use crate::io_source::IoSource;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use crate::net::UCred;
+use crate::net::SocketAddr;
 use crate::{event, sys, Interest, Registry, Token};
 
 use std::net::Shutdown;
@@ -18,6 +21,17 @@ impl UnixDatagram {
         sys::uds::datagram::bind(path.as_ref()).map(UnixDatagram::from_std)
     }
 
+    /// Creates a Unix datagram socket bound to the given socket address.
+    ///
+    /// Unlike [`bind`] this supports addresses in the abstract namespace,
+    /// see [`SocketAddr::from_abstract_name`].
+    ///
+    /// [`bind`]: #method.bind
+    /// [`SocketAddr::from_abstract_name`]: struct.SocketAddr.html#method.from_abstract_name
+    pub fn bind_addr(address: &SocketAddr) -> io::Result<UnixDatagram> {
+        sys::uds::datagram::bind_addr(address).map(UnixDatagram::from_std)
+    }
+
     /// Creates a new `UnixDatagram` from a standard `net::UnixDatagram`.
     ///
     /// This function is intended to be used to wrap a Unix datagram from the
@@ -35,11 +49,28 @@ impl UnixDatagram {
         self.inner.connect(path)
     }
 
+    /// Connects the socket to the specified socket address.
+    pub fn connect_addr(&self, address: &SocketAddr) -> io::Result<()> {
+        sys::uds::datagram::connect_addr(&self.inner, address)
+    }
+
     /// Creates a Unix Datagram socket which is not bound to any address.
     pub fn unbound() -> io::Result<UnixDatagram> {
         sys::uds::datagram::unbound().map(UnixDatagram::from_std)
     }
 
+    /// Binds an unbound socket to an unique address in the abstract
+    /// namespace, picked by the kernel.
+    ///
+    /// The address can be retrieved using [`local_addr`], allowing peers to
+    /// reply to datagrams send from this socket.
+    ///
+    /// [`local_addr`]: #method.local_addr
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn autobind(&self) -> io::Result<()> {
+        sys::uds::datagram::autobind(&self.inner)
+    }
+
     /// Create an unnamed pair of connected sockets.
     pub fn pair() -> io::Result<(UnixDatagram, UnixDatagram)> {
         sys::uds::datagram::pair().map(|(socket1, socket2)| {
@@ -85,6 +116,14 @@ impl UnixDatagram {
         self.inner.do_io(|inner| inner.send_to(buf, path))
     }
 
+    /// Sends data on the socket to the specified socket address.
+    ///
+    /// On success, returns the number of bytes written.
+    pub fn send_to_addr(&self, buf: &[u8], address: &SocketAddr) -> io::Result<usize> {
+        self.inner
+            .do_io(|inner| sys::uds::datagram::send_to_addr(inner, buf, address))
+    }
+
     /// Sends data on the socket to the socket's peer.
     ///
     /// The peer address may be set by the `connect` method, and this method
@@ -95,6 +134,92 @@ impl UnixDatagram {
         self.inner.do_io(|inner| inner.send(buf))
     }
 
+    /// Sends data and the file descriptors in `fds` to the socket's peer,
+    /// using an `SCM_RIGHTS` control message.
+    ///
+    /// The descriptors are duplicated into the receiving process, the caller
+    /// keeps ownership of `fds`. At most 253 (`SCM_MAX_FD`) descriptors can be
+    /// sent at once.
+    ///
+    /// On success, returns the number of bytes written.
+    pub fn send_with_fds(&self, buf: &[u8], fds: &[RawFd]) -> io::Result<usize> {
+        self.inner
+            .do_io(|inner| sys::uds::datagram::send_with_fds(inner, buf, fds))
+    }
+
+    /// Receives data and file descriptors, sent using an `SCM_RIGHTS` control
+    /// message, from the socket.
+    ///
+    /// On success, returns the number of bytes read, the number of file
+    /// descriptors written to the start of `fds` and whether any descriptors
+    /// were truncated. The caller owns the received descriptors, which have
+    /// the close-on-exec flag set.
+    ///
+    /// If more descriptors were sent than fit in `fds` the excess descriptors
+    /// are closed and the returned flag is set, the data and the descriptors
+    /// that did fit are still returned.
+    pub fn recv_with_fds(
+        &self,
+        buf: &mut [u8],
+        fds: &mut [RawFd],
+    ) -> io::Result<(usize, usize, bool)> {
+        self.inner
+            .do_io(|inner| sys::uds::datagram::recv_with_fds(inner, buf, fds))
+    }
+
+    /// Sends data on the socket to the socket's peer, along with the
+    /// credentials in `creds` as `SCM_CREDENTIALS` ancillary data.
+    ///
+    /// If `creds.pid` is `None` the process ID of the calling process is used.
+    /// Unless the process is privileged the kernel only accepts credentials
+    /// that match those of the calling process.
+    ///
+    /// Note that the kernel attaches the credentials of the sender to all
+    /// datagrams if the receiving socket has [`set_passcred`] enabled, even if
+    /// they're not sent with this method.
+    ///
+    /// [`set_passcred`]: #method.set_passcred
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn send_with_creds(&self, buf: &[u8], creds: &UCred) -> io::Result<usize> {
+        self.inner
+            .do_io(|inner| sys::uds::datagram::send_with_creds(inner, buf, creds))
+    }
+
+    /// Receives data from the socket, along with the credentials of the
+    /// sender if sent as `SCM_CREDENTIALS` ancillary data.
+    ///
+    /// The credentials are only received if [`set_passcred`] is enabled on
+    /// this socket. On success, returns the number of bytes read and the
+    /// credentials, if any.
+    ///
+    /// [`set_passcred`]: #method.set_passcred
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn recv_with_creds(&self, buf: &mut [u8]) -> io::Result<(usize, Option<UCred>)> {
+        self.inner
+            .do_io(|inner| sys::uds::datagram::recv_with_creds(inner, buf))
+    }
+
+    /// Sets the value of the `SO_PASSCRED` option on this socket.
+    ///
+    /// If enabled the credentials of the sender are received with every
+    /// datagram, see [`recv_with_creds`].
+    ///
+    /// [`recv_with_creds`]: #method.recv_with_creds
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn set_passcred(&self, passcred: bool) -> io::Result<()> {
+        sys::uds::datagram::set_passcred(&self.inner, passcred)
+    }
+
+    /// Gets the value of the `SO_PASSCRED` option on this socket.
+    ///
+    /// For more information about this option, see [`set_passcred`].
+    ///
+    /// [`set_passcred`]: #method.set_passcred
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn passcred(&self) -> io::Result<bool> {
+        sys::uds::datagram::passcred(&self.inner)
+    }
+
     /// Returns the value of the `SO_ERROR` option.
     pub fn take_error(&self) -> io::Result<Option<io::Error>> {
         self.inner.take_error()
@@ -18,6 +18,17 @@ impl UnixListener {
         sys::uds::listener::bind(path.as_ref()).map(UnixListener::from_std)
     }
 
+    /// Creates a new `UnixListener` bound to the specified socket address.
+    ///
+    /// Unlike [`bind`] this supports addresses in the abstract namespace,
+    /// see [`SocketAddr::from_abstract_name`].
+    ///
+    /// [`bind`]: #method.bind
+    /// [`SocketAddr::from_abstract_name`]: struct.SocketAddr.html#method.from_abstract_name
+    pub fn bind_addr(address: &SocketAddr) -> io::Result<UnixListener> {
+        sys::uds::listener::bind_addr(address).map(UnixListener::from_std)
+    }
+
     /// Creates a new `UnixListener` from a standard `net::UnixListener`.
     ///
     /// This function is intended to be used to wrap a Unix listener from the
@@ -4,7 +4,23 @@ pub use self::datagram::UnixDatagram;
 mod listener;
 pub use self::listener::UnixListener;
 
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+mod seqpacket;
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+pub use self::seqpacket::{UnixSeqpacket, UnixSeqpacketListener};
+
 mod stream;
 pub use self::stream::UnixStream;
 
 pub use crate::sys::SocketAddr;
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+pub use crate::sys::UCred;
@@ -0,0 +1,262 @@
+use crate::io_source::IoSource;
+use crate::net::SocketAddr;
+use crate::{event, sys, Interest, Registry, Token};
+
+use std::fs::File;
+use std::net::Shutdown;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::path::Path;
+use std::{fmt, io};
+
+/// A non-blocking Unix sequenced-packet socket server.
+///
+/// Sequenced-packet (`SOCK_SEQPACKET`) sockets are connection oriented, like
+/// [`UnixStream`], but preserve message boundaries, like [`UnixDatagram`].
+///
+/// [`UnixStream`]: struct.UnixStream.html
+/// [`UnixDatagram`]: struct.UnixDatagram.html
+pub struct UnixSeqpacketListener {
+    inner: IoSource<File>,
+}
+
+impl UnixSeqpacketListener {
+    /// Creates a new `UnixSeqpacketListener` bound to the specified socket.
+    pub fn bind<P: AsRef<Path>>(path: P) -> io::Result<UnixSeqpacketListener> {
+        sys::uds::seqpacket::bind(path.as_ref()).map(|socket| UnixSeqpacketListener {
+            inner: IoSource::new(socket),
+        })
+    }
+
+    /// Creates a new `UnixSeqpacketListener` bound to the specified socket
+    /// address.
+    pub fn bind_addr(address: &SocketAddr) -> io::Result<UnixSeqpacketListener> {
+        sys::uds::seqpacket::bind_addr(address).map(|socket| UnixSeqpacketListener {
+            inner: IoSource::new(socket),
+        })
+    }
+
+    /// Accepts a new incoming connection to this listener.
+    ///
+    /// The call is responsible for ensuring that the listening socket is in
+    /// non-blocking mode.
+    pub fn accept(&self) -> io::Result<(UnixSeqpacket, SocketAddr)> {
+        sys::uds::seqpacket::accept(self.inner.as_raw_fd())
+            .map(|(socket, addr)| (UnixSeqpacket::new(socket), addr))
+    }
+
+    /// Returns the local socket address of this listener.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        sys::uds::seqpacket::local_addr(self.inner.as_raw_fd())
+    }
+
+    /// Returns the value of the `SO_ERROR` option.
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        sys::uds::seqpacket::take_error(self.inner.as_raw_fd())
+    }
+}
+
+impl event::Source for UnixSeqpacketListener {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        self.inner.deregister(registry)
+    }
+}
+
+impl fmt::Debug for UnixSeqpacketListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut builder = f.debug_struct("UnixSeqpacketListener");
+        builder.field("fd", &self.inner.as_raw_fd());
+        if let Ok(addr) = self.local_addr() {
+            builder.field("local", &addr);
+        }
+        builder.finish()
+    }
+}
+
+impl IntoRawFd for UnixSeqpacketListener {
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_inner().into_raw_fd()
+    }
+}
+
+impl AsRawFd for UnixSeqpacketListener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl FromRawFd for UnixSeqpacketListener {
+    /// Converts a `RawFd` to a `UnixSeqpacketListener`.
+    ///
+    /// # Notes
+    ///
+    /// The caller is responsible for ensuring that the socket is in
+    /// non-blocking mode.
+    unsafe fn from_raw_fd(fd: RawFd) -> UnixSeqpacketListener {
+        UnixSeqpacketListener {
+            inner: IoSource::new(File::from_raw_fd(fd)),
+        }
+    }
+}
+
+/// A non-blocking Unix sequenced-packet socket.
+///
+/// Each [`send`] call sends a single message, which is received by a single
+/// [`recv`] call.
+///
+/// [`send`]: #method.send
+/// [`recv`]: #method.recv
+pub struct UnixSeqpacket {
+    inner: IoSource<File>,
+}
+
+impl UnixSeqpacket {
+    fn new(socket: File) -> UnixSeqpacket {
+        UnixSeqpacket {
+            inner: IoSource::new(socket),
+        }
+    }
+
+    /// Connects to the socket named by `path`.
+    pub fn connect<P: AsRef<Path>>(path: P) -> io::Result<UnixSeqpacket> {
+        sys::uds::seqpacket::connect(path.as_ref()).map(UnixSeqpacket::new)
+    }
+
+    /// Connects to the socket named by `address`.
+    pub fn connect_addr(address: &SocketAddr) -> io::Result<UnixSeqpacket> {
+        sys::uds::seqpacket::connect_addr(address).map(UnixSeqpacket::new)
+    }
+
+    /// Creates an unnamed pair of connected sockets.
+    ///
+    /// Returns two `UnixSeqpacket`s which are connected to each other.
+    pub fn pair() -> io::Result<(UnixSeqpacket, UnixSeqpacket)> {
+        sys::uds::seqpacket::pair()
+            .map(|(socket1, socket2)| (UnixSeqpacket::new(socket1), UnixSeqpacket::new(socket2)))
+    }
+
+    /// Returns the socket address of the local half of this connection.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        sys::uds::seqpacket::local_addr(self.inner.as_raw_fd())
+    }
+
+    /// Returns the socket address of the remote half of this connection.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        sys::uds::seqpacket::peer_addr(self.inner.as_raw_fd())
+    }
+
+    /// Sends `buf` as a single message to the remote peer.
+    ///
+    /// On success, returns the number of bytes written, which is always the
+    /// full message. Messages larger than the socket's send buffer return an
+    /// error.
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.inner
+            .do_io(|inner| sys::uds::seqpacket::send(inner.as_raw_fd(), buf))
+    }
+
+    /// Receives a single message from the remote peer.
+    ///
+    /// On success, returns the number of bytes read and whether or not the
+    /// message was truncated (`MSG_TRUNC`). If the message is larger than
+    /// `buf` the remainder of the message is discarded.
+    ///
+    /// Returns `Ok((0, false))` once the remote peer closed the connection.
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<(usize, bool)> {
+        self.inner
+            .do_io(|inner| sys::uds::seqpacket::recv(inner.as_raw_fd(), buf))
+    }
+
+    /// Returns the value of the `SO_ERROR` option.
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        sys::uds::seqpacket::take_error(self.inner.as_raw_fd())
+    }
+
+    /// Shuts down the read, write, or both halves of this connection.
+    ///
+    /// This function will cause all pending and future I/O calls on the
+    /// specified portions to immediately return with an appropriate value
+    /// (see the documentation of `Shutdown`).
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        sys::uds::seqpacket::shutdown(self.inner.as_raw_fd(), how)
+    }
+}
+
+impl event::Source for UnixSeqpacket {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        self.inner.deregister(registry)
+    }
+}
+
+impl fmt::Debug for UnixSeqpacket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut builder = f.debug_struct("UnixSeqpacket");
+        builder.field("fd", &self.inner.as_raw_fd());
+        if let Ok(addr) = self.local_addr() {
+            builder.field("local", &addr);
+        }
+        if let Ok(addr) = self.peer_addr() {
+            builder.field("peer", &addr);
+        }
+        builder.finish()
+    }
+}
+
+impl IntoRawFd for UnixSeqpacket {
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_inner().into_raw_fd()
+    }
+}
+
+impl AsRawFd for UnixSeqpacket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl FromRawFd for UnixSeqpacket {
+    /// Converts a `RawFd` to a `UnixSeqpacket`.
+    ///
+    /// # Notes
+    ///
+    /// The caller is responsible for ensuring that the socket is in
+    /// non-blocking mode.
+    unsafe fn from_raw_fd(fd: RawFd) -> UnixSeqpacket {
+        UnixSeqpacket::new(File::from_raw_fd(fd))
+    }
+}
@@ -1,4 +1,16 @@
 use crate::io_source::IoSource;
+use crate::net::{MsgFlags, SocketAddr};
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+use crate::net::UCred;
 use crate::{event, sys, Interest, Registry, Token};
 
 use std::fmt;
@@ -19,6 +31,17 @@ impl UnixStream {
         sys::uds::stream::connect(path.as_ref()).map(UnixStream::from_std)
     }
 
+    /// Connects to the socket named by `address`.
+    ///
+    /// Unlike [`connect`] this supports addresses in the abstract namespace,
+    /// see [`SocketAddr::from_abstract_name`].
+    ///
+    /// [`connect`]: #method.connect
+    /// [`SocketAddr::from_abstract_name`]: struct.SocketAddr.html#method.from_abstract_name
+    pub fn connect_addr(address: &SocketAddr) -> io::Result<UnixStream> {
+        sys::uds::stream::connect_addr(address).map(UnixStream::from_std)
+    }
+
     /// Creates a new `UnixStream` from a standard `net::UnixStream`.
     ///
     /// This function is intended to be used to wrap a Unix stream from the
@@ -56,6 +79,26 @@ impl UnixStream {
         sys::uds::stream::peer_addr(&self.inner)
     }
 
+    /// Returns the credentials of the process that created the other end of
+    /// this socket, i.e. the process that called `connect` or created the
+    /// pair.
+    ///
+    /// On Linux and Android this uses the `SO_PEERCRED` socket option, on the
+    /// BSDs and macOS `getpeereid(3)`, which doesn't return the process ID.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    pub fn peer_cred(&self) -> io::Result<UCred> {
+        sys::uds::stream::peer_cred(&self.inner)
+    }
+
     /// Returns the value of the `SO_ERROR` option.
     pub fn take_error(&self) -> io::Result<Option<io::Error>> {
         self.inner.take_error()
@@ -69,6 +112,81 @@ impl UnixStream {
     pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
         self.inner.shutdown(how)
     }
+
+    /// Receives data on the socket from the remote peer, without removing
+    /// that data from the queue. On success, returns the number of bytes
+    /// peeked.
+    ///
+    /// Successive calls return the same data. This is accomplished by passing
+    /// `MSG_PEEK` as a flag to the underlying recv system call.
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.recv_with_flags(buf, MsgFlags::PEEK)
+    }
+
+    /// Sends data and the file descriptors in `fds` to the remote peer, using an
+    /// `SCM_RIGHTS` control message.
+    ///
+    /// The descriptors are duplicated into the receiving process, the caller
+    /// keeps ownership of `fds`. At most 253 (`SCM_MAX_FD`) descriptors can be
+    /// sent at once.
+    ///
+    /// On success, returns the number of bytes written.
+    pub fn send_with_fds(&self, buf: &[u8], fds: &[RawFd]) -> io::Result<usize> {
+        self.inner
+            .do_io(|inner| sys::uds::stream::send_with_fds(inner, buf, fds))
+    }
+
+    /// Receives data and file descriptors, sent using an `SCM_RIGHTS` control
+    /// message, from the socket.
+    ///
+    /// On success, returns the number of bytes read, the number of file
+    /// descriptors written to the start of `fds` and whether any descriptors
+    /// were truncated. The caller owns the received descriptors, which have
+    /// the close-on-exec flag set.
+    ///
+    /// If more descriptors were sent than fit in `fds` the excess descriptors
+    /// are closed and the returned flag is set, the data and the descriptors
+    /// that did fit are still returned.
+    pub fn recv_with_fds(
+        &self,
+        buf: &mut [u8],
+        fds: &mut [RawFd],
+    ) -> io::Result<(usize, usize, bool)> {
+        self.inner
+            .do_io(|inner| sys::uds::stream::recv_with_fds(inner, buf, fds))
+    }
+
+    /// Receives data on the socket from the remote peer, passing `flags` to
+    /// the underlying `recv(2)` system call. On success, returns the number
+    /// of bytes read.
+    ///
+    /// # Notes
+    ///
+    /// The socket is in non-blocking mode, so [`MsgFlags::WAITALL`] doesn't
+    /// block; it may still return fewer bytes than requested, or an error
+    /// with kind `WouldBlock`, if not enough data is available yet.
+    ///
+    /// [`MsgFlags::WAITALL`]: struct.MsgFlags.html#associatedconstant.WAITALL
+    pub fn recv_with_flags(&self, buf: &mut [u8], flags: MsgFlags) -> io::Result<usize> {
+        self.inner
+            .do_io(|inner| sys::uds::stream::recv_with_flags(inner.as_raw_fd(), buf, flags))
+    }
+
+    /// Sends data on the socket to the remote peer, passing `flags` to the
+    /// underlying `send(2)` system call. On success, returns the number of
+    /// bytes written.
+    ///
+    /// For example [`MsgFlags::NOSIGNAL`] returns an `EPIPE` error, rather
+    /// than raising `SIGPIPE`, if the peer closed the connection and
+    /// [`MsgFlags::MORE`] signals more data will follow, delaying the
+    /// transmission of partial frames.
+    ///
+    /// [`MsgFlags::NOSIGNAL`]: struct.MsgFlags.html#associatedconstant.NOSIGNAL
+    /// [`MsgFlags::MORE`]: struct.MsgFlags.html#associatedconstant.MORE
+    pub fn send_with_flags(&self, buf: &[u8], flags: MsgFlags) -> io::Result<usize> {
+        self.inner
+            .do_io(|inner| sys::uds::stream::send_with_flags(inner.as_raw_fd(), buf, flags))
+    }
 }
 
 impl Read for UnixStream {
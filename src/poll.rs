@@ -1,8 +1,12 @@
-use crate::{event, sys, Events, Interest, Token};
+#[cfg(feature = "time")]
+use crate::time::TimerWheel;
+use crate::{event, sys, Events, Interest, Mode, Token};
 use log::trace;
 #[cfg(unix)]
 use std::os::unix::io::{AsRawFd, RawFd};
 use std::time::Duration;
+#[cfg(feature = "time")]
+use std::time::Instant;
 use std::{fmt, io};
 
 /// Polls for readiness events on all registered values.
@@ -92,6 +96,11 @@ use std::{fmt, io};
 /// there is no guarantee that another readiness event will be delivered, even
 /// if further data is received for the event source.
 ///
+/// This applies to the default, edge-triggered, registrations. On platforms
+/// that support it a source can also be registered level-triggered or
+/// oneshot, see [`Mode`].
+///
+/// [`Mode`]: enum.Mode.html
 /// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
 ///
 /// ### Readiness operations
@@ -168,6 +177,14 @@ use std::{fmt, io};
 /// | iOS           | [kqueue]  |
 /// | macOS         | [kqueue]  |
 ///
+/// On Linux the `io-uring` feature can be enabled to use [io_uring] instead
+/// of epoll, if supported by the kernel (Linux 5.13 or later), falling back to
+/// epoll otherwise. Registrations are batched and submitted as part of the
+/// next call to [`Poll::poll`], reducing the number of system calls. Unlike
+/// epoll, io_uring keeps a reference to registered files, so file descriptors
+/// registered using [`SourceFd`] must be deregistered before closing them.
+/// Mio's own types remove their registration when dropped.
+///
 /// On all supported platforms, socket operations are handled by using the
 /// system selector. Platform specific extensions (e.g. [`SourceFd`]) allow
 /// accessing other features provided by individual system selectors. For
@@ -184,12 +201,15 @@ use std::{fmt, io};
 ///
 /// [epoll]: http://man7.org/linux/man-pages/man7/epoll.7.html
 /// [kqueue]: https://www.freebsd.org/cgi/man.cgi?query=kqueue&sektion=2
+/// [io_uring]: https://man7.org/linux/man-pages/man7/io_uring.7.html
 /// [IOCP]: https://msdn.microsoft.com/en-us/library/windows/desktop/aa365198(v=vs.85).aspx
 /// [`signalfd`]: http://man7.org/linux/man-pages/man2/signalfd.2.html
 /// [`SourceFd`]: unix/struct.SourceFd.html
 /// [`Poll::poll`]: struct.Poll.html#method.poll
 pub struct Poll {
     registry: Registry,
+    #[cfg(feature = "time")]
+    timers: TimerWheel,
 }
 
 /// Registers I/O resources.
@@ -304,7 +324,57 @@ impl Poll {
     ///
     /// [struct]: #
     pub fn poll(&mut self, events: &mut Events, timeout: Option<Duration>) -> io::Result<()> {
-        self.registry.selector.select(events.sys(), timeout)
+        #[cfg(not(feature = "time"))]
+        {
+            self.registry.selector.select(events.sys(), timeout)
+        }
+
+        #[cfg(feature = "time")]
+        {
+            let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+            loop {
+                let timeout = deadline.map(|deadline| {
+                    let now = Instant::now();
+                    if deadline > now {
+                        deadline - now
+                    } else {
+                        Duration::from_millis(0)
+                    }
+                });
+                let selector_timeout = self.timers.poll_timeout(timeout);
+                self.registry
+                    .selector
+                    .select(events.sys(), selector_timeout)?;
+                self.timers.expire(events);
+
+                // The timer wheel can shorten the timeout passed to the
+                // selector, causing it to return before the timeout provided
+                // by the caller expired without any timer actually expiring.
+                // Only in that case we poll again, in all other cases we
+                // return to the caller like we do without the timer wheel.
+                let shortened = selector_timeout != timeout;
+                match deadline {
+                    _ if !events.is_empty() || !shortened => return Ok(()),
+                    Some(deadline) if Instant::now() >= deadline => return Ok(()),
+                    _ => continue,
+                }
+            }
+        }
+    }
+}
+
+cfg_time! {
+    impl Poll {
+        /// Returns the [`TimerWheel`] of this `Poll` instance.
+        ///
+        /// Timeouts added to the wheel are returned by [`poll`] once they
+        /// expire, see [`TimerWheel`] for more information.
+        ///
+        /// [`TimerWheel`]: time/struct.TimerWheel.html
+        /// [`poll`]: #method.poll
+        pub fn timers(&mut self) -> &mut TimerWheel {
+            &mut self.timers
+        }
     }
 }
 
@@ -346,6 +416,8 @@ cfg_os_poll! {
         pub fn new() -> io::Result<Poll> {
             sys::Selector::new().map(|selector| Poll {
                 registry: Registry { selector },
+                #[cfg(feature = "time")]
+                timers: TimerWheel::new(),
             })
         }
     }
@@ -397,6 +469,10 @@ impl Registry {
     /// The readiness interest for an `event::Source` can be changed at any time
     /// by calling [`reregister`].
     ///
+    /// `interests` also determine the registration [`Mode`], registrations are
+    /// edge-triggered unless another mode is set using
+    /// [`Interest::with_mode`].
+    ///
     /// # Notes
     ///
     /// Callers must ensure that if a source being registered with a `Poll`
@@ -415,6 +491,8 @@ impl Registry {
     /// [`reregister`]: struct.Registry.html#method.reregister
     /// [`deregister`]: struct.Registry.html#method.deregister
     /// [`Token`]: struct.Token.html
+    /// [`Mode`]: enum.Mode.html
+    /// [`Interest::with_mode`]: struct.Interest.html#method.with_mode
     ///
     /// # Examples
     ///
@@ -475,6 +553,98 @@ impl Registry {
         source.register(self, token, interests)
     }
 
+    /// Register an [`event::Source`] with the `Poll` instance as an exclusive
+    /// registration.
+    ///
+    /// This is useful when the same source, e.g. a listening socket, is
+    /// registered with multiple `Poll` instances that are polled by different
+    /// threads. Normally all threads blocked in [`poll`] are woken up once
+    /// the source becomes ready, but only one of them can act on it, e.g.
+    /// accept the connection. If the source is registered exclusively with
+    /// every `Poll` instance only one (or a few) of the threads blocked in
+    /// [`poll`] are woken up, avoiding the "thundering herd" problem.
+    ///
+    /// See [`register`] for a description of the arguments. Oneshot
+    /// registrations can't be exclusive.
+    ///
+    /// # Notes
+    ///
+    /// Exclusive registrations can't be reregistered, [`reregister`] returns
+    /// an [`InvalidInput`] error for them. Deregister the source and register
+    /// it again instead. They also don't report [`read_closed`] readiness, a
+    /// closed connection is only reported as readable.
+    ///
+    /// This is only supported on Linux (4.5 or later) and Android, using
+    /// `EPOLLEXCLUSIVE`, on other platforms, or when using the io_uring based
+    /// selector, this returns an error.
+    ///
+    /// [`event::Source`]: ./event/trait.Source.html
+    /// [`poll`]: struct.Poll.html#method.poll
+    /// [`register`]: struct.Registry.html#method.register
+    /// [`read_closed`]: ./event/struct.Event.html#method.is_read_closed
+    /// [`reregister`]: struct.Registry.html#method.reregister
+    /// [`InvalidInput`]: std::io::ErrorKind::InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Events, Interest, Poll, Token};
+    /// use mio::net::TcpListener;
+    /// use std::net;
+    /// use std::thread;
+    ///
+    /// let listener = net::TcpListener::bind("127.0.0.1:0")?;
+    /// listener.set_nonblocking(true)?;
+    ///
+    /// let mut handles = Vec::new();
+    /// for _ in 0..4 {
+    ///     let mut listener = TcpListener::from_std(listener.try_clone()?);
+    ///     handles.push(thread::spawn(move || -> std::io::Result<()> {
+    ///         let mut poll = Poll::new()?;
+    ///         let mut events = Events::with_capacity(128);
+    ///         // Only one of the threads is woken up for a new connection.
+    ///         poll.registry().register_exclusive(
+    ///             &mut listener,
+    ///             Token(0),
+    ///             Interest::READABLE)?;
+    ///
+    ///         loop {
+    ///             poll.poll(&mut events, None)?;
+    ///             while let Ok((connection, address)) = listener.accept() {
+    ///                 // Handle the connection.
+    /// #               drop((connection, address));
+    ///             }
+    ///         }
+    ///     }));
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn register_exclusive<S>(
+        &self,
+        source: &mut S,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()>
+    where
+        S: event::Source + ?Sized,
+    {
+        if interests.mode() == Mode::Oneshot {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "oneshot registrations can't be exclusive",
+            ));
+        }
+        trace!(
+            "registering event source with poller exclusively: token={:?}, interests={:?}",
+            token,
+            interests
+        );
+        source.register(self, token, interests.exclusive())
+    }
+
     /// Re-register an [`event::Source`] with the `Poll` instance.
     ///
     /// Re-registering an event source allows changing the details of the
@@ -484,7 +654,13 @@ impl Registry {
     /// The `reregister` arguments fully override the previous values. In other
     /// words, if a socket is registered with [`readable`] interest and the call
     /// to `reregister` specifies [`writable`], then read interest is no longer
-    /// requested for the handle.
+    /// requested for the handle. This includes the registration mode, which
+    /// also means that `reregister` must be used to re-enable a [oneshot]
+    /// registration after an event was returned for it.
+    ///
+    /// Sources registered using [`register_exclusive`] can't be reregistered,
+    /// Mio's types return an [`InvalidInput`] error for them. Deregister the
+    /// source and register it again instead.
     ///
     /// The event source must have previously been registered with this instance
     /// of `Poll`, otherwise the behavior is undefined.
@@ -530,6 +706,9 @@ impl Registry {
     /// [`register`]: struct.Registry.html#method.register
     /// [`readable`]: ./event/struct.Event.html#is_readable
     /// [`writable`]: ./event/struct.Event.html#is_writable
+    /// [oneshot]: enum.Mode.html#variant.Oneshot
+    /// [`register_exclusive`]: struct.Registry.html#method.register_exclusive
+    /// [`InvalidInput`]: std::io::ErrorKind::InvalidInput
     pub fn reregister<S>(&self, source: &mut S, token: Token, interests: Interest) -> io::Result<()>
     where
         S: event::Source + ?Sized,
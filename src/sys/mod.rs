@@ -54,25 +54,60 @@ cfg_os_poll! {
 #[cfg(unix)]
 cfg_os_poll! {
     mod unix;
-    pub use self::unix::SourceFd;
+    cfg_any_os_util! {
+        pub use self::unix::SourceFd;
+    }
 
     pub(crate) use self::unix::{event, Event, Events, Selector, Waker};
 
+    cfg_os_util! {
+        #[cfg(target_os = "linux")]
+        pub(crate) use self::unix::inotify;
+        #[cfg(target_os = "linux")]
+        pub(crate) use self::unix::netlink;
+        #[cfg(target_os = "linux")]
+        pub(crate) use self::unix::packet;
+        pub(crate) use self::unix::pipe;
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        pub(crate) use self::unix::process;
+        pub use self::unix::{SignalInfo, Signals};
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    cfg_time! {
+        pub(crate) use self::unix::{timer, Timer};
+    }
+
     cfg_tcp! {
         pub(crate) use self::unix::tcp;
     }
 
     cfg_udp! {
+        pub(crate) use self::unix::raw;
         pub(crate) use self::unix::udp;
+        pub use self::unix::{PacketInfo, RecvMsg, Tos};
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        pub use self::unix::SegmentSize;
     }
 
     cfg_uds! {
         pub use self::unix::SocketAddr;
+        #[cfg(any(
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "ios",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        pub use self::unix::UCred;
 
         pub(crate) use self::unix::uds;
     }
 
-    cfg_net! {
+    cfg_io_source! {
         pub(crate) use self::unix::IoSourceState;
     }
 }
@@ -96,5 +131,29 @@ cfg_not_os_poll! {
     #[cfg(unix)]
     cfg_uds! {
         pub use self::unix::SocketAddr;
+        #[cfg(any(
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "ios",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        pub use self::unix::UCred;
+    }
+
+    #[cfg(unix)]
+    cfg_udp! {
+        pub use self::unix::{PacketInfo, RecvMsg, Tos};
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        pub use self::unix::SegmentSize;
     }
 }
+
+#[cfg(all(unix, any(feature = "tcp", feature = "udp", feature = "uds")))]
+pub use self::unix::{ControlMessage, ControlMessageEncoder, ControlMessages, MsgFlags};
+
+#[cfg(all(target_os = "linux", feature = "tcp"))]
+pub use self::unix::TcpInfo;
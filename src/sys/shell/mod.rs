@@ -15,6 +15,8 @@ cfg_tcp! {
 }
 
 cfg_udp! {
+    #[cfg(unix)]
+    pub(crate) mod raw;
     pub(crate) mod udp;
 }
 
@@ -28,6 +30,11 @@ cfg_net! {
     #[cfg(windows)]
     use std::os::windows::io::RawSocket;
 
+    #[cfg(unix)]
+    use std::os::unix::io::RawFd;
+
+    #[cfg(unix)]
+    use crate::Interest;
     #[cfg(windows)]
     use crate::{Registry, Token, Interest};
 
@@ -48,6 +55,17 @@ cfg_net! {
         }
     }
 
+    #[cfg(unix)]
+    impl IoSourceState {
+        pub fn registered(&mut self, _: &Selector, _: RawFd, _: Interest) {}
+
+        pub fn deregistered(&mut self) {}
+
+        pub fn is_exclusive(&self) -> bool {
+            false
+        }
+    }
+
     #[cfg(windows)]
     impl IoSourceState {
          pub fn register(
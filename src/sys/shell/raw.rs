@@ -0,0 +1,62 @@
+use std::fs::File;
+use std::io;
+use std::net::SocketAddr;
+use std::os::unix::io::RawFd;
+
+pub(crate) fn new_v4(_: i32) -> io::Result<File> {
+    os_required!()
+}
+
+pub(crate) fn new_v6(_: i32) -> io::Result<File> {
+    os_required!()
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn new_ping_v4() -> io::Result<File> {
+    os_required!()
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn new_ping_v6() -> io::Result<File> {
+    os_required!()
+}
+
+pub(crate) fn bind(_: RawFd, _: SocketAddr) -> io::Result<()> {
+    os_required!()
+}
+
+pub(crate) fn connect(_: RawFd, _: SocketAddr) -> io::Result<()> {
+    os_required!()
+}
+
+pub(crate) fn local_addr(_: RawFd) -> io::Result<SocketAddr> {
+    os_required!()
+}
+
+pub(crate) fn send_to(_: RawFd, _: &[u8], _: SocketAddr) -> io::Result<usize> {
+    os_required!()
+}
+
+pub(crate) fn send(_: RawFd, _: &[u8]) -> io::Result<usize> {
+    os_required!()
+}
+
+pub(crate) fn recv_from(_: RawFd, _: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+    os_required!()
+}
+
+pub(crate) fn recv(_: RawFd, _: &mut [u8]) -> io::Result<usize> {
+    os_required!()
+}
+
+pub(crate) fn set_header_included(_: RawFd, _: bool) -> io::Result<()> {
+    os_required!()
+}
+
+pub(crate) fn header_included(_: RawFd) -> io::Result<bool> {
+    os_required!()
+}
+
+pub(crate) fn take_error(_: RawFd) -> io::Result<Option<io::Error>> {
+    os_required!()
+}
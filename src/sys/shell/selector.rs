@@ -96,6 +96,12 @@ pub mod event {
         os_required!();
     }
 
+    cfg_time! {
+        pub fn timeout(_: Token) -> Event {
+            os_required!();
+        }
+    }
+
     pub fn debug_details(_: &mut fmt::Formatter<'_>, _: &Event) -> fmt::Result {
         os_required!();
     }
@@ -1,14 +1,263 @@
 use std::io;
 use std::net::{self, SocketAddr};
+use std::time::Duration;
 
-pub fn connect(_: SocketAddr) -> io::Result<net::TcpStream> {
+pub(crate) type TcpSocket = i32;
+
+pub(crate) fn new_v4_socket() -> io::Result<TcpSocket> {
+    os_required!();
+}
+
+pub(crate) fn new_v6_socket() -> io::Result<TcpSocket> {
+    os_required!();
+}
+
+pub(crate) fn bind(_: TcpSocket, _: SocketAddr) -> io::Result<()> {
+    os_required!();
+}
+
+pub(crate) fn connect(_: TcpSocket, _: SocketAddr) -> io::Result<net::TcpStream> {
+    os_required!();
+}
+
+pub(crate) fn listen(_: TcpSocket, _: u32) -> io::Result<net::TcpListener> {
+    os_required!();
+}
+
+pub(crate) fn close(_: TcpSocket) {
+    os_required!();
+}
+
+pub(crate) fn set_reuseaddr(_: TcpSocket, _: bool) -> io::Result<()> {
+    os_required!();
+}
+
+pub(crate) fn reuseaddr(_: TcpSocket) -> io::Result<bool> {
+    os_required!();
+}
+
+#[cfg(all(unix, not(target_os = "solaris")))]
+pub(crate) fn set_reuseport(_: TcpSocket, _: bool) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(all(unix, not(target_os = "solaris")))]
+pub(crate) fn reuseport(_: TcpSocket) -> io::Result<bool> {
+    os_required!();
+}
+
+pub(crate) fn set_linger(_: TcpSocket, _: Option<Duration>) -> io::Result<()> {
+    os_required!();
+}
+
+pub(crate) fn linger(_: TcpSocket) -> io::Result<Option<Duration>> {
+    os_required!();
+}
+
+pub(crate) fn set_send_buffer_size(_: TcpSocket, _: u32) -> io::Result<()> {
+    os_required!();
+}
+
+pub(crate) fn send_buffer_size(_: TcpSocket) -> io::Result<u32> {
+    os_required!();
+}
+
+pub(crate) fn set_recv_buffer_size(_: TcpSocket, _: u32) -> io::Result<()> {
+    os_required!();
+}
+
+pub(crate) fn recv_buffer_size(_: TcpSocket) -> io::Result<u32> {
+    os_required!();
+}
+
+pub(crate) fn set_keepalive(_: TcpSocket, _: bool) -> io::Result<()> {
+    os_required!();
+}
+
+pub(crate) fn keepalive(_: TcpSocket) -> io::Result<bool> {
+    os_required!();
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd"
+))]
+pub(crate) fn set_keepalive_idle(_: TcpSocket, _: Duration) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd"
+))]
+pub(crate) fn keepalive_idle(_: TcpSocket) -> io::Result<Duration> {
+    os_required!();
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd"
+))]
+pub(crate) fn set_keepalive_interval(_: TcpSocket, _: Duration) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd"
+))]
+pub(crate) fn keepalive_interval(_: TcpSocket) -> io::Result<Duration> {
+    os_required!();
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd"
+))]
+pub(crate) fn set_keepalive_count(_: TcpSocket, _: u32) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd"
+))]
+pub(crate) fn keepalive_count(_: TcpSocket) -> io::Result<u32> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn set_user_timeout(_: TcpSocket, _: Option<Duration>) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn user_timeout(_: TcpSocket) -> io::Result<Option<Duration>> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn set_quickack(_: TcpSocket, _: bool) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn quickack(_: TcpSocket) -> io::Result<bool> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn set_cork(_: TcpSocket, _: bool) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn cork(_: TcpSocket) -> io::Result<bool> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn set_notsent_lowat(_: TcpSocket, _: u32) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn notsent_lowat(_: TcpSocket) -> io::Result<u32> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn set_congestion(_: TcpSocket, _: &str) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn congestion(_: TcpSocket) -> io::Result<String> {
+    os_required!();
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn tcp_info(_: TcpSocket) -> io::Result<crate::sys::TcpInfo> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn bind_device(_: TcpSocket, _: Option<&[u8]>) -> io::Result<()> {
     os_required!();
 }
 
-pub fn bind(_: SocketAddr) -> io::Result<net::TcpListener> {
+pub(crate) fn local_addr(_: TcpSocket) -> io::Result<SocketAddr> {
     os_required!();
 }
 
 pub fn accept(_: &net::TcpListener) -> io::Result<(net::TcpStream, SocketAddr)> {
     os_required!();
 }
+
+#[cfg(unix)]
+pub(crate) fn recv_with_flags(
+    _: std::os::unix::io::RawFd,
+    _: &mut [u8],
+    _: crate::sys::MsgFlags,
+) -> io::Result<usize> {
+    os_required!();
+}
+
+#[cfg(unix)]
+pub(crate) fn send_with_flags(
+    _: std::os::unix::io::RawFd,
+    _: &[u8],
+    _: crate::sys::MsgFlags,
+) -> io::Result<usize> {
+    os_required!();
+}
+
+#[cfg(unix)]
+pub(crate) fn set_tos_v4(_: std::os::unix::io::RawFd, _: u32) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(unix)]
+pub(crate) fn tos_v4(_: std::os::unix::io::RawFd) -> io::Result<u32> {
+    os_required!();
+}
+
+#[cfg(unix)]
+pub(crate) fn set_tclass_v6(_: std::os::unix::io::RawFd, _: u32) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(unix)]
+pub(crate) fn tclass_v6(_: std::os::unix::io::RawFd) -> io::Result<u32> {
+    os_required!();
+}
@@ -1,6 +1,208 @@
 use std::io;
 use std::net::{self, SocketAddr};
 
-pub fn bind(_: SocketAddr) -> io::Result<net::UdpSocket> {
+pub(crate) type UdpSocketBuilder = i32;
+
+pub(crate) fn new_v4_socket() -> io::Result<UdpSocketBuilder> {
+    os_required!()
+}
+
+pub(crate) fn new_v6_socket() -> io::Result<UdpSocketBuilder> {
+    os_required!()
+}
+
+pub(crate) fn bind(_: UdpSocketBuilder, _: SocketAddr) -> io::Result<net::UdpSocket> {
+    os_required!()
+}
+
+pub(crate) fn close(_: UdpSocketBuilder) {
+    os_required!()
+}
+
+pub(crate) fn set_reuseaddr(_: UdpSocketBuilder, _: bool) -> io::Result<()> {
+    os_required!()
+}
+
+pub(crate) fn reuseaddr(_: UdpSocketBuilder) -> io::Result<bool> {
+    os_required!()
+}
+
+#[cfg(all(unix, not(target_os = "solaris")))]
+pub(crate) fn set_reuseport(_: UdpSocketBuilder, _: bool) -> io::Result<()> {
+    os_required!()
+}
+
+#[cfg(all(unix, not(target_os = "solaris")))]
+pub(crate) fn reuseport(_: UdpSocketBuilder) -> io::Result<bool> {
+    os_required!()
+}
+
+pub(crate) fn set_send_buffer_size(_: UdpSocketBuilder, _: u32) -> io::Result<()> {
+    os_required!()
+}
+
+pub(crate) fn send_buffer_size(_: UdpSocketBuilder) -> io::Result<u32> {
+    os_required!()
+}
+
+pub(crate) fn set_recv_buffer_size(_: UdpSocketBuilder, _: u32) -> io::Result<()> {
+    os_required!()
+}
+
+pub(crate) fn recv_buffer_size(_: UdpSocketBuilder) -> io::Result<u32> {
+    os_required!()
+}
+
+pub(crate) fn set_only_v6(_: UdpSocketBuilder, _: bool) -> io::Result<()> {
+    os_required!()
+}
+
+pub(crate) fn only_v6(_: UdpSocketBuilder) -> io::Result<bool> {
+    os_required!()
+}
+
+#[cfg(unix)]
+pub fn send_msg(
+    _: &net::UdpSocket,
+    _: &[io::IoSlice<'_>],
+    _: Option<SocketAddr>,
+    _: &[u8],
+    _: crate::sys::MsgFlags,
+) -> io::Result<usize> {
+    os_required!()
+}
+
+#[cfg(unix)]
+pub fn recv_msg(
+    _: &net::UdpSocket,
+    _: &mut [io::IoSliceMut<'_>],
+    _: &mut [u8],
+    _: crate::sys::MsgFlags,
+) -> io::Result<crate::sys::RecvMsg> {
+    os_required!()
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn send_to_batch(
+    _: &net::UdpSocket,
+    _: &[io::IoSlice<'_>],
+    _: &[SocketAddr],
+) -> io::Result<usize> {
+    os_required!()
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn recv_from_batch(
+    _: &net::UdpSocket,
+    _: &mut [io::IoSliceMut<'_>],
+    _: &mut [(usize, Option<SocketAddr>)],
+) -> io::Result<usize> {
+    os_required!()
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn set_gro(_: &net::UdpSocket, _: bool) -> io::Result<()> {
+    os_required!()
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn gro(_: &net::UdpSocket) -> io::Result<bool> {
+    os_required!()
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn set_gso_segment_size(_: &net::UdpSocket, _: u16) -> io::Result<()> {
+    os_required!()
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn gso_segment_size(_: &net::UdpSocket) -> io::Result<u16> {
+    os_required!()
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn send_to_gso(_: &net::UdpSocket, _: &[u8], _: SocketAddr, _: u16) -> io::Result<usize> {
+    os_required!()
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos"
+))]
+pub fn set_recv_pktinfo_v4(_: &net::UdpSocket, _: bool) -> io::Result<()> {
+    os_required!()
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos"
+))]
+pub fn recv_pktinfo_v4(_: &net::UdpSocket) -> io::Result<bool> {
+    os_required!()
+}
+
+#[cfg(unix)]
+pub fn set_recv_pktinfo_v6(_: &net::UdpSocket, _: bool) -> io::Result<()> {
+    os_required!()
+}
+
+#[cfg(unix)]
+pub fn recv_pktinfo_v6(_: &net::UdpSocket) -> io::Result<bool> {
+    os_required!()
+}
+
+#[cfg(unix)]
+pub(crate) fn set_tos_v4(_: std::os::unix::io::RawFd, _: u32) -> io::Result<()> {
+    os_required!()
+}
+
+#[cfg(unix)]
+pub(crate) fn tos_v4(_: std::os::unix::io::RawFd) -> io::Result<u32> {
+    os_required!()
+}
+
+#[cfg(unix)]
+pub(crate) fn set_tclass_v6(_: std::os::unix::io::RawFd, _: u32) -> io::Result<()> {
+    os_required!()
+}
+
+#[cfg(unix)]
+pub(crate) fn tclass_v6(_: std::os::unix::io::RawFd) -> io::Result<u32> {
+    os_required!()
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos"
+))]
+pub fn set_recv_tos_v4(_: &net::UdpSocket, _: bool) -> io::Result<()> {
+    os_required!()
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos"
+))]
+pub fn recv_tos_v4(_: &net::UdpSocket) -> io::Result<bool> {
+    os_required!()
+}
+
+#[cfg(unix)]
+pub fn set_recv_tclass_v6(_: &net::UdpSocket, _: bool) -> io::Result<()> {
+    os_required!()
+}
+
+#[cfg(unix)]
+pub fn recv_tclass_v6(_: &net::UdpSocket) -> io::Result<bool> {
     os_required!()
 }
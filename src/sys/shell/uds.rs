@@ -8,6 +8,27 @@ pub(crate) mod datagram {
         os_required!()
     }
 
+    pub(crate) fn bind_addr(_: &SocketAddr) -> io::Result<net::UnixDatagram> {
+        os_required!()
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub(crate) fn autobind(_: &net::UnixDatagram) -> io::Result<()> {
+        os_required!()
+    }
+
+    pub(crate) fn connect_addr(_: &net::UnixDatagram, _: &SocketAddr) -> io::Result<()> {
+        os_required!()
+    }
+
+    pub(crate) fn send_to_addr(
+        _: &net::UnixDatagram,
+        _: &[u8],
+        _: &SocketAddr,
+    ) -> io::Result<usize> {
+        os_required!()
+    }
+
     pub(crate) fn unbound() -> io::Result<net::UnixDatagram> {
         os_required!()
     }
@@ -24,6 +45,49 @@ pub(crate) mod datagram {
         os_required!()
     }
 
+    pub(crate) fn send_with_fds(
+        _: &net::UnixDatagram,
+        _: &[u8],
+        _: &[std::os::unix::io::RawFd],
+    ) -> io::Result<usize> {
+        os_required!()
+    }
+
+    pub(crate) fn recv_with_fds(
+        _: &net::UnixDatagram,
+        _: &mut [u8],
+        _: &mut [std::os::unix::io::RawFd],
+    ) -> io::Result<(usize, usize, bool)> {
+        os_required!()
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub(crate) fn send_with_creds(
+        _: &net::UnixDatagram,
+        _: &[u8],
+        _: &crate::net::UCred,
+    ) -> io::Result<usize> {
+        os_required!()
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub(crate) fn recv_with_creds(
+        _: &net::UnixDatagram,
+        _: &mut [u8],
+    ) -> io::Result<(usize, Option<crate::net::UCred>)> {
+        os_required!()
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub(crate) fn set_passcred(_: &net::UnixDatagram, _: bool) -> io::Result<()> {
+        os_required!()
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub(crate) fn passcred(_: &net::UnixDatagram) -> io::Result<bool> {
+        os_required!()
+    }
+
     pub(crate) fn recv_from(
         _: &net::UnixDatagram,
         _: &mut [u8],
@@ -42,6 +106,10 @@ pub(crate) mod listener {
         os_required!()
     }
 
+    pub(crate) fn bind_addr(_: &SocketAddr) -> io::Result<net::UnixListener> {
+        os_required!()
+    }
+
     pub(crate) fn accept(_: &net::UnixListener) -> io::Result<(UnixStream, SocketAddr)> {
         os_required!()
     }
@@ -61,6 +129,10 @@ pub(crate) mod stream {
         os_required!()
     }
 
+    pub(crate) fn connect_addr(_: &SocketAddr) -> io::Result<net::UnixStream> {
+        os_required!()
+    }
+
     pub(crate) fn pair() -> io::Result<(net::UnixStream, net::UnixStream)> {
         os_required!()
     }
@@ -72,4 +144,108 @@ pub(crate) mod stream {
     pub(crate) fn peer_addr(_: &net::UnixStream) -> io::Result<SocketAddr> {
         os_required!()
     }
+
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    pub(crate) fn peer_cred(_: &net::UnixStream) -> io::Result<crate::net::UCred> {
+        os_required!()
+    }
+
+    pub(crate) fn send_with_fds(
+        _: &net::UnixStream,
+        _: &[u8],
+        _: &[std::os::unix::io::RawFd],
+    ) -> io::Result<usize> {
+        os_required!()
+    }
+
+    pub(crate) fn recv_with_fds(
+        _: &net::UnixStream,
+        _: &mut [u8],
+        _: &mut [std::os::unix::io::RawFd],
+    ) -> io::Result<(usize, usize, bool)> {
+        os_required!()
+    }
+
+    pub(crate) fn recv_with_flags(
+        _: std::os::unix::io::RawFd,
+        _: &mut [u8],
+        _: crate::sys::MsgFlags,
+    ) -> io::Result<usize> {
+        os_required!()
+    }
+
+    pub(crate) fn send_with_flags(
+        _: std::os::unix::io::RawFd,
+        _: &[u8],
+        _: crate::sys::MsgFlags,
+    ) -> io::Result<usize> {
+        os_required!()
+    }
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+pub(crate) mod seqpacket {
+    use crate::net::SocketAddr;
+    use std::fs::File;
+    use std::io;
+    use std::net::Shutdown;
+    use std::os::unix::io::RawFd;
+    use std::path::Path;
+
+    pub(crate) fn bind(_: &Path) -> io::Result<File> {
+        os_required!()
+    }
+
+    pub(crate) fn bind_addr(_: &SocketAddr) -> io::Result<File> {
+        os_required!()
+    }
+
+    pub(crate) fn accept(_: RawFd) -> io::Result<(File, SocketAddr)> {
+        os_required!()
+    }
+
+    pub(crate) fn connect(_: &Path) -> io::Result<File> {
+        os_required!()
+    }
+
+    pub(crate) fn connect_addr(_: &SocketAddr) -> io::Result<File> {
+        os_required!()
+    }
+
+    pub(crate) fn pair() -> io::Result<(File, File)> {
+        os_required!()
+    }
+
+    pub(crate) fn local_addr(_: RawFd) -> io::Result<SocketAddr> {
+        os_required!()
+    }
+
+    pub(crate) fn peer_addr(_: RawFd) -> io::Result<SocketAddr> {
+        os_required!()
+    }
+
+    pub(crate) fn send(_: RawFd, _: &[u8]) -> io::Result<usize> {
+        os_required!()
+    }
+
+    pub(crate) fn recv(_: RawFd, _: &mut [u8]) -> io::Result<(usize, bool)> {
+        os_required!()
+    }
+
+    pub(crate) fn take_error(_: RawFd) -> io::Result<Option<io::Error>> {
+        os_required!()
+    }
+
+    pub(crate) fn shutdown(_: RawFd, _: Shutdown) -> io::Result<()> {
+        os_required!()
+    }
 }
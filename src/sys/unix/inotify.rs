@@ -0,0 +1,28 @@
+use std::ffi::CString;
+use std::fs::File;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::{FromRawFd, RawFd};
+use std::path::Path;
+
+/// Create a new non-blocking inotify instance.
+pub(crate) fn new() -> io::Result<File> {
+    // `IN_NONBLOCK` and `IN_CLOEXEC` are defined as the `O_*` equivalents.
+    syscall!(inotify_init1(libc::O_NONBLOCK | libc::O_CLOEXEC))
+        .map(|fd| unsafe { File::from_raw_fd(fd) })
+}
+
+/// Returns the watch descriptor of the (new) watch for `path`.
+pub(crate) fn add_watch(fd: RawFd, path: &Path, mask: u32) -> io::Result<i32> {
+    let path = CString::new(path.as_os_str().as_bytes()).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "path must not contain interior null bytes",
+        )
+    })?;
+    syscall!(inotify_add_watch(fd, path.as_ptr(), mask))
+}
+
+pub(crate) fn remove_watch(fd: RawFd, wd: i32) -> io::Result<()> {
+    syscall!(inotify_rm_watch(fd, wd)).map(|_| ())
+}
@@ -13,40 +13,101 @@ macro_rules! syscall {
     }};
 }
 
+#[cfg(any(feature = "tcp", feature = "udp", feature = "uds"))]
+mod msg;
+#[cfg(any(feature = "tcp", feature = "udp", feature = "uds"))]
+pub use self::msg::{ControlMessage, ControlMessageEncoder, ControlMessages, MsgFlags};
+#[cfg(feature = "udp")]
+pub use self::msg::{PacketInfo, RecvMsg, Tos};
+#[cfg(all(feature = "udp", any(target_os = "android", target_os = "linux")))]
+pub use self::msg::SegmentSize;
+
+#[cfg(all(feature = "tcp", target_os = "linux"))]
+mod tcp_info;
+#[cfg(all(feature = "tcp", target_os = "linux"))]
+pub use self::tcp_info::TcpInfo;
+
 cfg_os_poll! {
     mod net;
 
     mod selector;
     pub(crate) use self::selector::{event, Event, Events, Selector};
 
-    mod sourcefd;
-    pub use self::sourcefd::SourceFd;
+    cfg_any_os_util! {
+        mod sourcefd;
+        pub use self::sourcefd::SourceFd;
+    }
 
     mod waker;
     pub(crate) use self::waker::Waker;
 
+    cfg_os_util! {
+        #[cfg(target_os = "linux")]
+        pub(crate) mod inotify;
+        #[cfg(target_os = "linux")]
+        pub(crate) mod netlink;
+        #[cfg(target_os = "linux")]
+        pub(crate) mod packet;
+        pub(crate) mod pipe;
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        pub(crate) mod process;
+
+        mod signals;
+        pub use self::signals::{SignalInfo, Signals};
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    cfg_time! {
+        pub(crate) mod timer;
+        pub(crate) use self::timer::Timer;
+    }
+
     cfg_tcp! {
         pub(crate) mod tcp;
     }
 
     cfg_udp! {
+        pub(crate) mod raw;
         pub(crate) mod udp;
     }
 
     cfg_uds! {
         pub(crate) mod uds;
         pub use self::uds::SocketAddr;
+        #[cfg(any(
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "ios",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        pub use self::uds::UCred;
     }
 
-    cfg_net! {
+    #[cfg(all(target_os = "linux", feature = "io-uring"))]
+    cfg_io_source! {
+        pub(crate) use self::selector::IoSourceState;
+    }
+
+    #[cfg(not(all(target_os = "linux", feature = "io-uring")))]
+    cfg_io_source! {
         use std::io;
+        use std::os::unix::io::RawFd;
 
-        // Both `kqueue` and `epoll` don't need to hold any user space state.
-        pub(crate) struct IoSourceState;
+        use crate::Interest;
+
+        // Both `kqueue` and `epoll` don't need to hold any user space state,
+        // other than whether the source is registered exclusively.
+        pub(crate) struct IoSourceState {
+            exclusive: bool,
+        }
 
         impl IoSourceState {
             pub fn new() -> IoSourceState {
-                IoSourceState
+                IoSourceState { exclusive: false }
             }
 
             pub fn do_io<T, F, R>(&self, f: F, io: &T) -> io::Result<R>
@@ -57,6 +118,21 @@ cfg_os_poll! {
                 // return.
                 f(io)
             }
+
+            /// Called after `fd` is registered with `selector`.
+            pub fn registered(&mut self, _: &Selector, _: RawFd, interests: Interest) {
+                self.exclusive = interests.is_exclusive();
+            }
+
+            /// Called after the source is deregistered.
+            pub fn deregistered(&mut self) {
+                self.exclusive = false;
+            }
+
+            /// Returns true if the source is registered exclusively.
+            pub fn is_exclusive(&self) -> bool {
+                self.exclusive
+            }
         }
     }
 }
@@ -65,6 +141,17 @@ cfg_not_os_poll! {
     cfg_uds! {
         mod uds;
         pub use self::uds::SocketAddr;
+        #[cfg(any(
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "ios",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        pub use self::uds::UCred;
     }
 
     cfg_any_os_util! {
@@ -0,0 +1,727 @@
+use std::ops::{BitOr, BitOrAssign};
+use std::{fmt, io, mem, ptr};
+
+/// Flags passed to, or returned by, `sendmsg(2)` and `recvmsg(2)`.
+///
+/// Flags can be combined using the `|` operator.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct MsgFlags(libc::c_int);
+
+impl MsgFlags {
+    /// Peek at the incoming data without removing it from the queue
+    /// (`MSG_PEEK`).
+    pub const PEEK: MsgFlags = MsgFlags(libc::MSG_PEEK);
+    /// Make this single operation non-blocking (`MSG_DONTWAIT`).
+    pub const DONTWAIT: MsgFlags = MsgFlags(libc::MSG_DONTWAIT);
+    /// Wait until the full request is satisfied (`MSG_WAITALL`).
+    pub const WAITALL: MsgFlags = MsgFlags(libc::MSG_WAITALL);
+    /// Send or receive out-of-band data (`MSG_OOB`).
+    pub const OOB: MsgFlags = MsgFlags(libc::MSG_OOB);
+    /// Returned when the datagram was larger than the supplied buffers and
+    /// was truncated (`MSG_TRUNC`).
+    pub const TRUNC: MsgFlags = MsgFlags(libc::MSG_TRUNC);
+    /// Returned when the control messages were larger than the supplied
+    /// control buffer and were truncated (`MSG_CTRUNC`).
+    pub const CTRUNC: MsgFlags = MsgFlags(libc::MSG_CTRUNC);
+    /// End of record (`MSG_EOR`).
+    pub const EOR: MsgFlags = MsgFlags(libc::MSG_EOR);
+    /// Don't generate `SIGPIPE` if the peer closed the connection
+    /// (`MSG_NOSIGNAL`).
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "linux",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    pub const NOSIGNAL: MsgFlags = MsgFlags(libc::MSG_NOSIGNAL);
+    /// More data will be sent (`MSG_MORE`).
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub const MORE: MsgFlags = MsgFlags(libc::MSG_MORE);
+
+    /// Returns an empty set of flags.
+    pub const fn empty() -> MsgFlags {
+        MsgFlags(0)
+    }
+
+    /// Create flags from the raw `MSG_*` bits.
+    pub const fn from_bits(bits: libc::c_int) -> MsgFlags {
+        MsgFlags(bits)
+    }
+
+    /// Returns the raw `MSG_*` bits.
+    pub const fn bits(self) -> libc::c_int {
+        self.0
+    }
+
+    /// Returns true if all flags in `other` are set.
+    pub const fn contains(self, other: MsgFlags) -> bool {
+        (self.0 & other.0) == other.0
+    }
+
+    /// Returns true if no flags are set.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for MsgFlags {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        MsgFlags(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for MsgFlags {
+    #[inline]
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+impl fmt::Debug for MsgFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flags = [
+            (MsgFlags::PEEK, "PEEK"),
+            (MsgFlags::DONTWAIT, "DONTWAIT"),
+            (MsgFlags::WAITALL, "WAITALL"),
+            (MsgFlags::OOB, "OOB"),
+            (MsgFlags::TRUNC, "TRUNC"),
+            (MsgFlags::CTRUNC, "CTRUNC"),
+            (MsgFlags::EOR, "EOR"),
+            #[cfg(any(
+                target_os = "android",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "linux",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            ))]
+            (MsgFlags::NOSIGNAL, "NOSIGNAL"),
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            (MsgFlags::MORE, "MORE"),
+        ];
+
+        let mut one = false;
+        for &(flag, name) in flags.iter() {
+            if self.contains(flag) {
+                if one {
+                    write!(f, " | ")?
+                }
+                write!(f, "{}", name)?;
+                one = true
+            }
+        }
+        if !one {
+            write!(f, "(empty)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Size of the control message header, including padding.
+fn header_len() -> usize {
+    // This is safe because `CMSG_LEN` only does arithmetic.
+    unsafe { libc::CMSG_LEN(0) as usize }
+}
+
+/// A single control message (ancillary data), see [`ControlMessages`].
+///
+/// [`ControlMessages`]: struct.ControlMessages.html
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ControlMessage<'a> {
+    level: libc::c_int,
+    kind: libc::c_int,
+    data: &'a [u8],
+}
+
+impl<'a> ControlMessage<'a> {
+    /// The originating protocol, `cmsg_level`, e.g. `libc::IPPROTO_IP`.
+    pub fn level(&self) -> libc::c_int {
+        self.level
+    }
+
+    /// The protocol specific type, `cmsg_type`, e.g. `libc::IP_TOS`.
+    pub fn kind(&self) -> libc::c_int {
+        self.kind
+    }
+
+    /// The data of the message, without the header and trailing padding.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+/// Iterator over the control messages (ancillary data) in a buffer filled by
+/// `recv_msg`.
+///
+/// # Examples
+///
+/// ```
+/// use mio::net::{ControlMessageEncoder, ControlMessages};
+///
+/// let mut buf = [0; 64];
+/// let mut encoder = ControlMessageEncoder::new(&mut buf);
+/// encoder.push(libc::IPPROTO_IP, libc::IP_TOS, &[1])?;
+/// let len = encoder.len();
+///
+/// let mut messages = ControlMessages::new(&buf[..len]);
+/// let msg = messages.next().unwrap();
+/// assert_eq!(msg.level(), libc::IPPROTO_IP);
+/// assert_eq!(msg.kind(), libc::IP_TOS);
+/// assert_eq!(msg.data(), &[1]);
+/// assert!(messages.next().is_none());
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct ControlMessages<'a> {
+    buf: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> ControlMessages<'a> {
+    /// Create a new iterator over the control messages in `buf`.
+    ///
+    /// `buf` should only contain the bytes actually written by the kernel,
+    /// i.e. it must be truncated to the control length returned by
+    /// `recv_msg`.
+    pub fn new(buf: &'a [u8]) -> ControlMessages<'a> {
+        ControlMessages { buf, offset: 0 }
+    }
+}
+
+impl<'a> Iterator for ControlMessages<'a> {
+    type Item = ControlMessage<'a>;
+
+    fn next(&mut self) -> Option<ControlMessage<'a>> {
+        let remaining = &self.buf[self.offset..];
+        if remaining.len() < mem::size_of::<libc::cmsghdr>() {
+            return None;
+        }
+
+        // This is safe because we checked above that the buffer is large
+        // enough to hold the header. The buffer doesn't have to be aligned.
+        let header: libc::cmsghdr = unsafe { ptr::read_unaligned(remaining.as_ptr() as *const _) };
+        let len = header.cmsg_len as usize;
+        if len < header_len() || len > remaining.len() {
+            return None;
+        }
+
+        let data = &remaining[header_len()..len];
+        // This is safe because `CMSG_SPACE` only does arithmetic.
+        let space = unsafe { libc::CMSG_SPACE(data.len() as _) as usize };
+        self.offset = (self.offset + space).min(self.buf.len());
+        Some(ControlMessage {
+            level: header.cmsg_level,
+            kind: header.cmsg_type,
+            data,
+        })
+    }
+}
+
+/// Encoder of control messages (ancillary data) to use with `send_msg`.
+///
+/// See [`ControlMessages`] for an example.
+///
+/// [`ControlMessages`]: struct.ControlMessages.html
+#[derive(Debug)]
+pub struct ControlMessageEncoder<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> ControlMessageEncoder<'a> {
+    /// Create a new encoder writing into `buf`.
+    pub fn new(buf: &'a mut [u8]) -> ControlMessageEncoder<'a> {
+        ControlMessageEncoder { buf, len: 0 }
+    }
+
+    /// Returns the number of bytes a control message with `data_len` bytes of
+    /// data takes up in the buffer, including the header and padding.
+    pub fn space(data_len: usize) -> usize {
+        // This is safe because `CMSG_SPACE` only does arithmetic.
+        unsafe { libc::CMSG_SPACE(data_len as _) as usize }
+    }
+
+    /// Add a control message.
+    ///
+    /// Returns an error of kind `InvalidInput` if the buffer is too small.
+    pub fn push(&mut self, level: libc::c_int, kind: libc::c_int, data: &[u8]) -> io::Result<()> {
+        let space = ControlMessageEncoder::space(data.len());
+        if self.buf.len() - self.len < space {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "control message buffer too small",
+            ));
+        }
+
+        let buf = &mut self.buf[self.len..self.len + space];
+        for byte in buf.iter_mut() {
+            *byte = 0;
+        }
+
+        // Using `zeroed` so we don't have to deal with the platform specific
+        // padding fields.
+        let mut header: libc::cmsghdr = unsafe { mem::zeroed() };
+        // This is safe because `CMSG_LEN` only does arithmetic.
+        header.cmsg_len = unsafe { libc::CMSG_LEN(data.len() as _) } as _;
+        header.cmsg_level = level;
+        header.cmsg_type = kind;
+        // This is safe because `space` is always larger than the header and
+        // the buffer doesn't have to be aligned.
+        unsafe { ptr::write_unaligned(buf.as_mut_ptr() as *mut libc::cmsghdr, header) };
+        buf[header_len()..header_len() + data.len()].copy_from_slice(data);
+
+        self.len += space;
+        Ok(())
+    }
+
+    /// Returns the number of bytes used by the encoded control messages.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no control messages have been added.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the encoded control messages, to pass to `send_msg`.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+/// Result of `UdpSocket::recv_msg`.
+#[cfg(feature = "udp")]
+#[derive(Copy, Clone, Debug)]
+pub struct RecvMsg {
+    pub(crate) len: usize,
+    pub(crate) addr: Option<std::net::SocketAddr>,
+    pub(crate) control_len: usize,
+    pub(crate) flags: MsgFlags,
+}
+
+#[cfg(feature = "udp")]
+impl RecvMsg {
+    /// Number of bytes read into the buffers.
+    ///
+    /// If [`MsgFlags::TRUNC`] is set in [`flags`] the datagram was larger
+    /// than the buffers.
+    ///
+    /// [`MsgFlags::TRUNC`]: struct.MsgFlags.html#associatedconstant.TRUNC
+    /// [`flags`]: #method.flags
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if an empty datagram was received.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The address the datagram was sent from.
+    ///
+    /// Returns `None` if the kernel didn't return an IPv4 or IPv6 address.
+    /// The datagram is still read into the buffers in that case.
+    pub fn addr(&self) -> Option<std::net::SocketAddr> {
+        self.addr
+    }
+
+    /// Number of bytes written into the control buffer, use
+    /// [`ControlMessages`] to parse them.
+    ///
+    /// [`ControlMessages`]: struct.ControlMessages.html
+    pub fn control_len(&self) -> usize {
+        self.control_len
+    }
+
+    /// Flags set on the received message, e.g. [`MsgFlags::TRUNC`].
+    ///
+    /// [`MsgFlags::TRUNC`]: struct.MsgFlags.html#associatedconstant.TRUNC
+    pub fn flags(&self) -> MsgFlags {
+        self.flags
+    }
+}
+
+/// Packet information of a received datagram, or the source of a datagram to
+/// send, using `IP_PKTINFO` (IPv4) or `IPV6_PKTINFO` (IPv6) control messages.
+///
+/// Receiving packet information must be enabled using
+/// [`UdpSocket::set_recv_pktinfo_v4`] or [`UdpSocket::set_recv_pktinfo_v6`],
+/// for IPv4 and IPv6 datagrams respectively.
+///
+/// [`UdpSocket::set_recv_pktinfo_v4`]: struct.UdpSocket.html#method.set_recv_pktinfo_v4
+/// [`UdpSocket::set_recv_pktinfo_v6`]: struct.UdpSocket.html#method.set_recv_pktinfo_v6
+///
+/// # Examples
+///
+/// Replying from the address a datagram was received on.
+///
+/// ```
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::io::{IoSlice, IoSliceMut};
+///
+/// use mio::net::{ControlMessageEncoder, ControlMessages, MsgFlags, PacketInfo, UdpSocket};
+///
+/// let socket = UdpSocket::bind("127.0.0.1:0".parse()?)?;
+/// socket.set_recv_pktinfo_v4(true)?;
+/// socket.send_to(b"ping", socket.local_addr()?)?;
+/// # std::thread::sleep(std::time::Duration::from_millis(50));
+///
+/// // We must check if the socket is readable before calling recv_msg, or we
+/// // could run into a WouldBlock error.
+///
+/// let mut buf = [0; 16];
+/// let mut control = [0; 64];
+/// let msg = socket.recv_msg(&mut [IoSliceMut::new(&mut buf)], &mut control, MsgFlags::empty())?;
+/// assert_eq!(&buf[..msg.len()], b"ping");
+/// let info = ControlMessages::new(&control[..msg.control_len()])
+///     .filter_map(|cmsg| PacketInfo::from_control_message(&cmsg))
+///     .next()
+///     .expect("missing packet information");
+/// assert_eq!(info.addr(), socket.local_addr()?.ip());
+///
+/// let mut control = [0; 64];
+/// let mut encoder = ControlMessageEncoder::new(&mut control);
+/// info.encode(&mut encoder)?;
+/// let bufs = [IoSlice::new(b"pong")];
+/// socket.send_msg(&bufs, msg.addr(), encoder.as_bytes(), MsgFlags::empty())?;
+/// #
+/// #    Ok(())
+/// # }
+/// ```
+#[cfg(feature = "udp")]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PacketInfo {
+    addr: std::net::IpAddr,
+    ifindex: u32,
+}
+
+#[cfg(feature = "udp")]
+impl PacketInfo {
+    /// Create new packet information.
+    ///
+    /// When sending, `addr` is the source address to use and `ifindex` the
+    /// index of the interface to send the datagram on, either may be
+    /// unspecified (`0.0.0.0`/`::` and `0` respectively) to let the kernel
+    /// choose.
+    pub fn new(addr: std::net::IpAddr, ifindex: u32) -> PacketInfo {
+        PacketInfo { addr, ifindex }
+    }
+
+    /// The destination address of a received datagram, or the source address
+    /// of a datagram to send.
+    pub fn addr(&self) -> std::net::IpAddr {
+        self.addr
+    }
+
+    /// The index of the interface the datagram was received on, or should be
+    /// send on.
+    pub fn ifindex(&self) -> u32 {
+        self.ifindex
+    }
+
+    /// Parse packet information from a `IP_PKTINFO` or `IPV6_PKTINFO` control
+    /// message. Returns `None` for all other control messages.
+    pub fn from_control_message(cmsg: &ControlMessage<'_>) -> Option<PacketInfo> {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+        match (cmsg.level(), cmsg.kind()) {
+            #[cfg(any(
+                target_os = "android",
+                target_os = "ios",
+                target_os = "linux",
+                target_os = "macos"
+            ))]
+            (libc::IPPROTO_IP, libc::IP_PKTINFO) => {
+                let info: libc::in_pktinfo = read_data(cmsg.data())?;
+                Some(PacketInfo {
+                    addr: IpAddr::V4(Ipv4Addr::from(info.ipi_addr.s_addr.to_ne_bytes())),
+                    ifindex: info.ipi_ifindex as u32,
+                })
+            }
+            (libc::IPPROTO_IPV6, libc::IPV6_PKTINFO) => {
+                let info: libc::in6_pktinfo = read_data(cmsg.data())?;
+                Some(PacketInfo {
+                    addr: IpAddr::V6(Ipv6Addr::from(info.ipi6_addr.s6_addr)),
+                    ifindex: info.ipi6_ifindex,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Add the packet information as control message to `encoder`, to set
+    /// the source of a datagram send with `send_msg`.
+    ///
+    /// Returns an error of kind `InvalidInput` if the encoder's buffer is too
+    /// small, or if IPv4 packet information is not supported on this platform.
+    pub fn encode(&self, encoder: &mut ControlMessageEncoder<'_>) -> io::Result<()> {
+        match self.addr {
+            #[cfg(any(
+                target_os = "android",
+                target_os = "ios",
+                target_os = "linux",
+                target_os = "macos"
+            ))]
+            std::net::IpAddr::V4(addr) => {
+                // Using `zeroed` so we don't have to deal with the platform
+                // specific fields.
+                let mut info: libc::in_pktinfo = unsafe { mem::zeroed() };
+                info.ipi_spec_dst.s_addr = u32::from_ne_bytes(addr.octets());
+                info.ipi_ifindex = self.ifindex as _;
+                encoder.push(libc::IPPROTO_IP, libc::IP_PKTINFO, as_bytes(&info))
+            }
+            #[cfg(not(any(
+                target_os = "android",
+                target_os = "ios",
+                target_os = "linux",
+                target_os = "macos"
+            )))]
+            std::net::IpAddr::V4(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "IPv4 packet information not supported",
+            )),
+            std::net::IpAddr::V6(addr) => {
+                let mut info: libc::in6_pktinfo = unsafe { mem::zeroed() };
+                info.ipi6_addr.s6_addr = addr.octets();
+                info.ipi6_ifindex = self.ifindex as _;
+                encoder.push(libc::IPPROTO_IPV6, libc::IPV6_PKTINFO, as_bytes(&info))
+            }
+        }
+    }
+}
+
+/// Type-of-service (IPv4) or traffic class (IPv6) field of a received
+/// datagram, or of a datagram to send, using `IP_TOS` or `IPV6_TCLASS` control
+/// messages.
+///
+/// The field holds the DSCP codepoint in the upper six bits and the ECN
+/// codepoint in the lower two bits. Receiving it must be enabled using
+/// [`UdpSocket::set_recv_tos_v4`] or [`UdpSocket::set_recv_tclass_v6`], for
+/// IPv4 and IPv6 datagrams respectively. To set it for all datagrams send on
+/// a socket see [`UdpSocket::set_tos_v4`] and [`UdpSocket::set_tclass_v6`].
+///
+/// [`UdpSocket::set_recv_tos_v4`]: struct.UdpSocket.html#method.set_recv_tos_v4
+/// [`UdpSocket::set_recv_tclass_v6`]: struct.UdpSocket.html#method.set_recv_tclass_v6
+/// [`UdpSocket::set_tos_v4`]: struct.UdpSocket.html#method.set_tos_v4
+/// [`UdpSocket::set_tclass_v6`]: struct.UdpSocket.html#method.set_tclass_v6
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::io::{IoSlice, IoSliceMut};
+///
+/// use mio::net::{ControlMessageEncoder, ControlMessages, MsgFlags, Tos, UdpSocket};
+///
+/// // ECN Capable Transport (0) codepoint.
+/// const ECT_0: u8 = 0b10;
+///
+/// let socket = UdpSocket::bind("127.0.0.1:0".parse()?)?;
+/// socket.set_recv_tos_v4(true)?;
+///
+/// let mut control = [0; 32];
+/// let mut encoder = ControlMessageEncoder::new(&mut control);
+/// Tos::new(ECT_0).encode_v4(&mut encoder)?;
+/// let bufs = [IoSlice::new(b"Hello world")];
+/// let target = Some(socket.local_addr()?);
+/// socket.send_msg(&bufs, target, encoder.as_bytes(), MsgFlags::empty())?;
+/// # std::thread::sleep(std::time::Duration::from_millis(50));
+///
+/// // We must check if the socket is readable before calling recv_msg, or we
+/// // could run into a WouldBlock error.
+///
+/// let mut buf = [0; 16];
+/// let mut control = [0; 32];
+/// let msg = socket.recv_msg(&mut [IoSliceMut::new(&mut buf)], &mut control, MsgFlags::empty())?;
+/// let tos = ControlMessages::new(&control[..msg.control_len()])
+///     .filter_map(|cmsg| Tos::from_control_message(&cmsg))
+///     .next();
+/// assert_eq!(tos.map(|tos| tos.ecn()), Some(ECT_0));
+/// #
+/// #    Ok(())
+/// # }
+/// ```
+#[cfg(feature = "udp")]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Tos(u8);
+
+#[cfg(feature = "udp")]
+impl Tos {
+    /// Create a new type-of-service/traffic class.
+    pub fn new(value: u8) -> Tos {
+        Tos(value)
+    }
+
+    /// Returns the type-of-service/traffic class.
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+
+    /// Returns the ECN codepoint, the lower two bits.
+    pub fn ecn(&self) -> u8 {
+        self.0 & 0b11
+    }
+
+    /// Parse the type-of-service or traffic class from a `IP_TOS` (or
+    /// `IP_RECVTOS` on the BSDs) or `IPV6_TCLASS` control message. Returns
+    /// `None` for all other control messages.
+    pub fn from_control_message(cmsg: &ControlMessage<'_>) -> Option<Tos> {
+        match (cmsg.level(), cmsg.kind()) {
+            // Linux uses `IP_TOS`, the BSDs `IP_RECVTOS` and both use a
+            // single byte.
+            (libc::IPPROTO_IP, libc::IP_TOS) => cmsg.data().first().copied().map(Tos),
+            #[cfg(any(target_os = "freebsd", target_os = "ios", target_os = "macos"))]
+            (libc::IPPROTO_IP, libc::IP_RECVTOS) => cmsg.data().first().copied().map(Tos),
+            // The traffic class is an integer.
+            (libc::IPPROTO_IPV6, libc::IPV6_TCLASS) => {
+                let tclass: libc::c_int = read_data(cmsg.data())?;
+                Some(Tos(tclass as u8))
+            }
+            _ => None,
+        }
+    }
+
+    /// Add the type-of-service as `IP_TOS` control message to `encoder`, to
+    /// set it for a single IPv4 datagram send with `send_msg`.
+    ///
+    /// Returns an error of kind `InvalidInput` if the encoder's buffer is too
+    /// small.
+    pub fn encode_v4(&self, encoder: &mut ControlMessageEncoder<'_>) -> io::Result<()> {
+        let tos = libc::c_int::from(self.0);
+        encoder.push(libc::IPPROTO_IP, libc::IP_TOS, as_bytes(&tos))
+    }
+
+    /// Add the traffic class as `IPV6_TCLASS` control message to `encoder`,
+    /// to set it for a single IPv6 datagram send with `send_msg`.
+    ///
+    /// Returns an error of kind `InvalidInput` if the encoder's buffer is too
+    /// small.
+    pub fn encode_v6(&self, encoder: &mut ControlMessageEncoder<'_>) -> io::Result<()> {
+        let tclass = libc::c_int::from(self.0);
+        encoder.push(libc::IPPROTO_IPV6, libc::IPV6_TCLASS, as_bytes(&tclass))
+    }
+}
+
+/// Segment size of UDP generic segmentation offload (GSO) and generic receive
+/// offload (GRO), using `UDP_SEGMENT` and `UDP_GRO` control messages.
+///
+/// # Notes
+///
+/// Kernels before Linux 4.18 ignore the `UDP_SEGMENT` control message and
+/// send a single large datagram instead. `send_msg` returns an `EIO` error if
+/// the network device doesn't support checksum offloading, which is required
+/// for segmentation offload, and `EINVAL` for more than 64 segments.
+/// [`UdpSocket::send_to_gso`] handles all of these by falling back to sending
+/// each segment as a separate datagram.
+///
+/// [`UdpSocket::send_to_gso`]: struct.UdpSocket.html#method.send_to_gso
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::io::IoSlice;
+///
+/// use mio::net::{ControlMessageEncoder, MsgFlags, SegmentSize, UdpSocket};
+///
+/// let socket = UdpSocket::bind("127.0.0.1:0".parse()?)?;
+/// if socket.gso_segment_size().is_err() {
+///     // Segmentation offload isn't supported by the kernel.
+///     return Ok(());
+/// }
+///
+/// // We must check if the socket is writable before calling send_msg, or we
+/// // could run into a WouldBlock error.
+///
+/// // Sends three datagrams: two of 1200 bytes and one of 600 bytes.
+/// let mut control = [0; 32];
+/// let mut encoder = ControlMessageEncoder::new(&mut control);
+/// SegmentSize::new(1200).encode(&mut encoder)?;
+/// let bufs = [IoSlice::new(&[1; 3000])];
+/// let target = Some(socket.local_addr()?);
+/// let bytes_sent = socket.send_msg(&bufs, target, encoder.as_bytes(), MsgFlags::empty())?;
+/// assert_eq!(bytes_sent, 3000);
+/// #
+/// #    Ok(())
+/// # }
+/// ```
+#[cfg(all(feature = "udp", any(target_os = "android", target_os = "linux")))]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SegmentSize(u16);
+
+#[cfg(all(feature = "udp", any(target_os = "android", target_os = "linux")))]
+impl SegmentSize {
+    /// Create a new segment size.
+    pub fn new(size: u16) -> SegmentSize {
+        SegmentSize(size)
+    }
+
+    /// Returns the segment size in bytes.
+    pub fn get(&self) -> u16 {
+        self.0
+    }
+
+    /// Parse the segment size of coalesced datagrams from a `UDP_GRO` control
+    /// message. Returns `None` for all other control messages.
+    ///
+    /// With generic receive offload enabled, see [`UdpSocket::set_gro`], the
+    /// kernel may coalesce multiple datagrams from the same source into a
+    /// single buffer. Each datagram is `get()` bytes, except for the last
+    /// which may be smaller. If the control message is missing the data
+    /// wasn't coalesced.
+    ///
+    /// [`UdpSocket::set_gro`]: struct.UdpSocket.html#method.set_gro
+    pub fn from_control_message(cmsg: &ControlMessage<'_>) -> Option<SegmentSize> {
+        match (cmsg.level(), cmsg.kind()) {
+            (libc::SOL_UDP, libc::UDP_GRO) => {
+                let size: libc::c_int = read_data(cmsg.data())?;
+                Some(SegmentSize(size as u16))
+            }
+            _ => None,
+        }
+    }
+
+    /// Add the segment size as `UDP_SEGMENT` control message to `encoder`, to
+    /// split the data send with `send_msg` into datagrams of this size. The
+    /// kernel limits the number of segments to 64 per call.
+    ///
+    /// Returns an error of kind `InvalidInput` if the encoder's buffer is too
+    /// small.
+    pub fn encode(&self, encoder: &mut ControlMessageEncoder<'_>) -> io::Result<()> {
+        encoder.push(libc::SOL_UDP, libc::UDP_SEGMENT, &self.0.to_ne_bytes())
+    }
+}
+
+/// Read a `T` from the data of a control message, returns `None` if `data` is
+/// too small.
+#[cfg(feature = "udp")]
+fn read_data<T: Copy>(data: &[u8]) -> Option<T> {
+    if data.len() < mem::size_of::<T>() {
+        None
+    } else {
+        // This is safe because we checked the size above, the data doesn't
+        // have to be aligned and it's only used for C types for which all
+        // bit patterns are valid.
+        Some(unsafe { ptr::read_unaligned(data.as_ptr() as *const T) })
+    }
+}
+
+/// Returns the bytes of a C type.
+#[cfg(feature = "udp")]
+fn as_bytes<T: Copy>(value: &T) -> &[u8] {
+    // This is safe because `value` is valid for `size_of::<T>()` bytes and
+    // only used for C types without padding.
+    unsafe { std::slice::from_raw_parts(value as *const T as *const u8, mem::size_of::<T>()) }
+}
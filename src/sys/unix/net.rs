@@ -1,27 +1,33 @@
-#[cfg(all(feature = "os-poll", any(feature = "tcp", feature = "udp")))]
+#[cfg(any(feature = "tcp", feature = "udp"))]
 use std::net::SocketAddr;
 
-#[cfg(all(feature = "os-poll", any(feature = "tcp", feature = "udp")))]
-pub(crate) fn new_ip_socket(
-    addr: SocketAddr,
+/// Create a new non-blocking socket.
+#[cfg(all(
+    feature = "os-poll",
+    any(feature = "tcp", feature = "udp", feature = "uds")
+))]
+pub(crate) fn new_socket(
+    domain: libc::c_int,
     socket_type: libc::c_int,
 ) -> std::io::Result<libc::c_int> {
-    let domain = match addr {
-        SocketAddr::V4(..) => libc::AF_INET,
-        SocketAddr::V6(..) => libc::AF_INET6,
-    };
-
-    new_socket(domain, socket_type)
+    new_socket_with_protocol(domain, socket_type, 0)
 }
 
-/// Create a new non-blocking socket.
+/// Create a new non-blocking socket using `protocol`, rather than the default
+/// protocol for `socket_type`.
 #[cfg(all(
     feature = "os-poll",
-    any(feature = "tcp", feature = "udp", feature = "uds")
+    any(
+        feature = "tcp",
+        feature = "udp",
+        feature = "uds",
+        all(feature = "os-util", target_os = "linux")
+    )
 ))]
-pub(crate) fn new_socket(
+pub(crate) fn new_socket_with_protocol(
     domain: libc::c_int,
     socket_type: libc::c_int,
+    protocol: libc::c_int,
 ) -> std::io::Result<libc::c_int> {
     #[cfg(any(
         target_os = "android",
@@ -35,7 +41,7 @@ pub(crate) fn new_socket(
 
     // Gives a warning for platforms without SOCK_NONBLOCK.
     #[allow(clippy::let_and_return)]
-    let socket = syscall!(socket(domain, socket_type, 0));
+    let socket = syscall!(socket(domain, socket_type, protocol));
 
     // Darwin doesn't have SOCK_NONBLOCK or SOCK_CLOEXEC. Not sure about
     // Solaris, couldn't find anything online.
@@ -56,34 +62,261 @@ pub(crate) fn new_socket(
     socket
 }
 
-#[cfg(all(feature = "os-poll", any(feature = "tcp", feature = "udp")))]
-pub(crate) fn socket_addr(addr: &SocketAddr) -> (*const libc::sockaddr, libc::socklen_t) {
-    use std::mem::size_of_val;
+/// A type with the same memory layout as `libc::sockaddr`. Used in converting
+/// Rust level `SocketAddr` types into their system representation.
+#[cfg(any(feature = "tcp", feature = "udp"))]
+#[repr(C)]
+pub(crate) union SocketAddrCRepr {
+    v4: libc::sockaddr_in,
+    v6: libc::sockaddr_in6,
+}
+
+#[cfg(any(feature = "tcp", feature = "udp"))]
+impl SocketAddrCRepr {
+    pub(crate) fn as_ptr(&self) -> *const libc::sockaddr {
+        self as *const _ as *const libc::sockaddr
+    }
+}
+
+/// Converts a Rust `SocketAddr` into the system representation.
+#[cfg(any(feature = "tcp", feature = "udp"))]
+pub(crate) fn socket_addr(addr: &SocketAddr) -> (SocketAddrCRepr, libc::socklen_t) {
+    use std::mem::{size_of, zeroed};
 
     match addr {
-        SocketAddr::V4(ref addr) => (
-            addr as *const _ as *const libc::sockaddr,
-            size_of_val(addr) as libc::socklen_t,
-        ),
-        SocketAddr::V6(ref addr) => (
-            addr as *const _ as *const libc::sockaddr,
-            size_of_val(addr) as libc::socklen_t,
-        ),
+        SocketAddr::V4(ref addr) => {
+            // Using `zeroed` so we don't have to deal with the platform
+            // specific fields, such as `sin_len` on the BSDs.
+            let mut sockaddr: libc::sockaddr_in = unsafe { zeroed() };
+            sockaddr.sin_family = libc::AF_INET as libc::sa_family_t;
+            sockaddr.sin_port = addr.port().to_be();
+            // `s_addr` is stored as big endian on all machines and the octets
+            // are in big endian order, so a native endian conversion is used
+            // to never swap the bytes.
+            sockaddr.sin_addr = libc::in_addr {
+                s_addr: u32::from_ne_bytes(addr.ip().octets()),
+            };
+            (
+                SocketAddrCRepr { v4: sockaddr },
+                size_of::<libc::sockaddr_in>() as libc::socklen_t,
+            )
+        }
+        SocketAddr::V6(ref addr) => {
+            let mut sockaddr: libc::sockaddr_in6 = unsafe { zeroed() };
+            sockaddr.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+            sockaddr.sin6_port = addr.port().to_be();
+            sockaddr.sin6_addr.s6_addr = addr.ip().octets();
+            sockaddr.sin6_flowinfo = addr.flowinfo();
+            sockaddr.sin6_scope_id = addr.scope_id();
+            (
+                SocketAddrCRepr { v6: sockaddr },
+                size_of::<libc::sockaddr_in6>() as libc::socklen_t,
+            )
+        }
     }
 }
 
+/// Converts the system representation in `storage` into a Rust `SocketAddr`.
+///
 /// `storage` must be initialised to `sockaddr_in` or `sockaddr_in6`.
-#[cfg(all(feature = "os-poll", feature = "tcp"))]
+#[cfg(any(feature = "tcp", feature = "udp"))]
 pub(crate) unsafe fn to_socket_addr(
     storage: *const libc::sockaddr_storage,
 ) -> std::io::Result<SocketAddr> {
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+
     match (*storage).ss_family as libc::c_int {
-        libc::AF_INET => Ok(SocketAddr::V4(
-            *(storage as *const libc::sockaddr_in as *const _),
-        )),
-        libc::AF_INET6 => Ok(SocketAddr::V6(
-            *(storage as *const libc::sockaddr_in6 as *const _),
-        )),
+        libc::AF_INET => {
+            let addr = &*(storage as *const libc::sockaddr_in);
+            let ip = Ipv4Addr::from(addr.sin_addr.s_addr.to_ne_bytes());
+            let port = u16::from_be(addr.sin_port);
+            Ok(SocketAddr::V4(SocketAddrV4::new(ip, port)))
+        }
+        libc::AF_INET6 => {
+            let addr = &*(storage as *const libc::sockaddr_in6);
+            let ip = Ipv6Addr::from(addr.sin6_addr.s6_addr);
+            let port = u16::from_be(addr.sin6_port);
+            Ok(SocketAddr::V6(SocketAddrV6::new(
+                ip,
+                port,
+                addr.sin6_flowinfo,
+                addr.sin6_scope_id,
+            )))
+        }
         _ => Err(std::io::ErrorKind::InvalidInput.into()),
     }
 }
+
+/// Sets the socket option `name` at `level` to `value`.
+#[cfg(any(feature = "tcp", feature = "udp", feature = "uds"))]
+pub(crate) fn setsockopt<T>(
+    fd: std::os::unix::io::RawFd,
+    level: libc::c_int,
+    name: libc::c_int,
+    value: T,
+) -> std::io::Result<()> {
+    syscall!(setsockopt(
+        fd,
+        level,
+        name,
+        &value as *const T as *const libc::c_void,
+        std::mem::size_of::<T>() as libc::socklen_t,
+    ))
+    .map(|_| ())
+}
+
+/// Gets the value of the socket option `name` at `level`.
+#[cfg(any(feature = "tcp", feature = "udp", feature = "uds"))]
+pub(crate) fn getsockopt<T: Copy>(
+    fd: std::os::unix::io::RawFd,
+    level: libc::c_int,
+    name: libc::c_int,
+) -> std::io::Result<T> {
+    let mut value: T = unsafe { std::mem::zeroed() };
+    let mut len = std::mem::size_of::<T>() as libc::socklen_t;
+    syscall!(getsockopt(
+        fd,
+        level,
+        name,
+        &mut value as *mut T as *mut libc::c_void,
+        &mut len,
+    ))
+    .map(|_| value)
+}
+
+/// Returns and clears the pending error on the socket (`SO_ERROR`).
+#[cfg(any(feature = "udp", feature = "uds"))]
+pub(crate) fn take_error(fd: std::os::unix::io::RawFd) -> std::io::Result<Option<std::io::Error>> {
+    match getsockopt::<libc::c_int>(fd, libc::SOL_SOCKET, libc::SO_ERROR)? {
+        0 => Ok(None),
+        errno => Ok(Some(std::io::Error::from_raw_os_error(errno))),
+    }
+}
+
+#[cfg(any(feature = "tcp", feature = "udp"))]
+pub(crate) fn set_tos_v4(fd: std::os::unix::io::RawFd, tos: u32) -> std::io::Result<()> {
+    setsockopt(fd, libc::IPPROTO_IP, libc::IP_TOS, tos as libc::c_int)
+}
+
+#[cfg(any(feature = "tcp", feature = "udp"))]
+pub(crate) fn tos_v4(fd: std::os::unix::io::RawFd) -> std::io::Result<u32> {
+    // Some platforms use a single byte for the option, so we can't use
+    // `getsockopt` with an integer directly.
+    let mut tos = [0u8; std::mem::size_of::<libc::c_int>()];
+    let mut len = tos.len() as libc::socklen_t;
+    syscall!(getsockopt(
+        fd,
+        libc::IPPROTO_IP,
+        libc::IP_TOS,
+        tos.as_mut_ptr() as *mut libc::c_void,
+        &mut len,
+    ))?;
+    Ok(if len as usize == 1 {
+        u32::from(tos[0])
+    } else {
+        libc::c_int::from_ne_bytes(tos) as u32
+    })
+}
+
+#[cfg(any(feature = "tcp", feature = "udp"))]
+pub(crate) fn set_tclass_v6(fd: std::os::unix::io::RawFd, tclass: u32) -> std::io::Result<()> {
+    setsockopt(
+        fd,
+        libc::IPPROTO_IPV6,
+        libc::IPV6_TCLASS,
+        tclass as libc::c_int,
+    )
+}
+
+#[cfg(any(feature = "tcp", feature = "udp"))]
+pub(crate) fn tclass_v6(fd: std::os::unix::io::RawFd) -> std::io::Result<u32> {
+    getsockopt::<libc::c_int>(fd, libc::IPPROTO_IPV6, libc::IPV6_TCLASS).map(|tclass| tclass as u32)
+}
+
+#[cfg(any(feature = "tcp", feature = "udp"))]
+pub(crate) fn set_reuseaddr(fd: std::os::unix::io::RawFd, reuseaddr: bool) -> std::io::Result<()> {
+    setsockopt(
+        fd,
+        libc::SOL_SOCKET,
+        libc::SO_REUSEADDR,
+        reuseaddr as libc::c_int,
+    )
+}
+
+#[cfg(any(feature = "tcp", feature = "udp"))]
+pub(crate) fn reuseaddr(fd: std::os::unix::io::RawFd) -> std::io::Result<bool> {
+    getsockopt::<libc::c_int>(fd, libc::SOL_SOCKET, libc::SO_REUSEADDR).map(|on| on != 0)
+}
+
+#[cfg(all(any(feature = "tcp", feature = "udp"), not(target_os = "solaris")))]
+pub(crate) fn set_reuseport(fd: std::os::unix::io::RawFd, reuseport: bool) -> std::io::Result<()> {
+    setsockopt(
+        fd,
+        libc::SOL_SOCKET,
+        libc::SO_REUSEPORT,
+        reuseport as libc::c_int,
+    )
+}
+
+#[cfg(all(any(feature = "tcp", feature = "udp"), not(target_os = "solaris")))]
+pub(crate) fn reuseport(fd: std::os::unix::io::RawFd) -> std::io::Result<bool> {
+    getsockopt::<libc::c_int>(fd, libc::SOL_SOCKET, libc::SO_REUSEPORT).map(|on| on != 0)
+}
+
+#[cfg(any(feature = "tcp", feature = "udp"))]
+pub(crate) fn set_send_buffer_size(fd: std::os::unix::io::RawFd, size: u32) -> std::io::Result<()> {
+    use std::convert::TryInto;
+
+    let size = size.try_into().unwrap_or(libc::c_int::MAX);
+    setsockopt(fd, libc::SOL_SOCKET, libc::SO_SNDBUF, size)
+}
+
+#[cfg(any(feature = "tcp", feature = "udp"))]
+pub(crate) fn send_buffer_size(fd: std::os::unix::io::RawFd) -> std::io::Result<u32> {
+    getsockopt::<libc::c_int>(fd, libc::SOL_SOCKET, libc::SO_SNDBUF).map(|size| size as u32)
+}
+
+#[cfg(any(feature = "tcp", feature = "udp"))]
+pub(crate) fn set_recv_buffer_size(fd: std::os::unix::io::RawFd, size: u32) -> std::io::Result<()> {
+    use std::convert::TryInto;
+
+    let size = size.try_into().unwrap_or(libc::c_int::MAX);
+    setsockopt(fd, libc::SOL_SOCKET, libc::SO_RCVBUF, size)
+}
+
+#[cfg(any(feature = "tcp", feature = "udp"))]
+pub(crate) fn recv_buffer_size(fd: std::os::unix::io::RawFd) -> std::io::Result<u32> {
+    getsockopt::<libc::c_int>(fd, libc::SOL_SOCKET, libc::SO_RCVBUF).map(|size| size as u32)
+}
+
+/// Receive data from a connected socket, using `recv(2)` with `flags`.
+#[cfg(any(feature = "tcp", feature = "uds"))]
+pub(crate) fn recv_with_flags(
+    fd: std::os::unix::io::RawFd,
+    buf: &mut [u8],
+    flags: crate::sys::MsgFlags,
+) -> std::io::Result<usize> {
+    syscall!(recv(
+        fd,
+        buf.as_mut_ptr() as *mut libc::c_void,
+        buf.len(),
+        flags.bits(),
+    ))
+    .map(|n| n as usize)
+}
+
+/// Send data on a connected socket, using `send(2)` with `flags`.
+#[cfg(any(feature = "tcp", feature = "uds"))]
+pub(crate) fn send_with_flags(
+    fd: std::os::unix::io::RawFd,
+    buf: &[u8],
+    flags: crate::sys::MsgFlags,
+) -> std::io::Result<usize> {
+    syscall!(send(
+        fd,
+        buf.as_ptr() as *const libc::c_void,
+        buf.len(),
+        flags.bits(),
+    ))
+    .map(|n| n as usize)
+}
@@ -0,0 +1,90 @@
+use crate::sys::unix::net::new_socket_with_protocol;
+use std::fs::File;
+use std::io;
+use std::mem;
+use std::os::unix::io::{FromRawFd, RawFd};
+
+/// Create a new non-blocking `AF_NETLINK` socket for `protocol`.
+pub(crate) fn new(protocol: libc::c_int) -> io::Result<File> {
+    new_socket_with_protocol(libc::AF_NETLINK, libc::SOCK_RAW, protocol)
+        .map(|socket| unsafe { File::from_raw_fd(socket) })
+}
+
+fn socket_addr(port_id: u32, groups: u32) -> libc::sockaddr_nl {
+    // `sockaddr_nl` has private padding, so it can't be created directly.
+    let mut addr: libc::sockaddr_nl = unsafe { mem::zeroed() };
+    addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+    addr.nl_pid = port_id;
+    addr.nl_groups = groups;
+    addr
+}
+
+pub(crate) fn bind(socket: RawFd, port_id: u32, groups: u32) -> io::Result<()> {
+    let addr = socket_addr(port_id, groups);
+    syscall!(bind(
+        socket,
+        &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+        mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+    ))
+    .map(|_| ())
+}
+
+/// Returns the port id and multicast groups (bitmask) the socket is bound to.
+pub(crate) fn local_addr(socket: RawFd) -> io::Result<(u32, u32)> {
+    let mut addr: libc::sockaddr_nl = unsafe { mem::zeroed() };
+    let mut length = mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t;
+    syscall!(getsockname(
+        socket,
+        &mut addr as *mut libc::sockaddr_nl as *mut libc::sockaddr,
+        &mut length,
+    ))?;
+    Ok((addr.nl_pid, addr.nl_groups))
+}
+
+fn set_membership(socket: RawFd, option: libc::c_int, group: u32) -> io::Result<()> {
+    syscall!(setsockopt(
+        socket,
+        libc::SOL_NETLINK,
+        option,
+        &group as *const u32 as *const libc::c_void,
+        mem::size_of::<u32>() as libc::socklen_t,
+    ))
+    .map(|_| ())
+}
+
+pub(crate) fn join_group(socket: RawFd, group: u32) -> io::Result<()> {
+    set_membership(socket, libc::NETLINK_ADD_MEMBERSHIP, group)
+}
+
+pub(crate) fn leave_group(socket: RawFd, group: u32) -> io::Result<()> {
+    set_membership(socket, libc::NETLINK_DROP_MEMBERSHIP, group)
+}
+
+pub(crate) fn send_to(socket: RawFd, buf: &[u8], port_id: u32, groups: u32) -> io::Result<usize> {
+    let addr = socket_addr(port_id, groups);
+    syscall!(sendto(
+        socket,
+        buf.as_ptr() as *const libc::c_void,
+        buf.len(),
+        0,
+        &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+        mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+    ))
+    .map(|n| n as usize)
+}
+
+/// Returns the number of bytes read, the port id and multicast groups
+/// (bitmask) of the sender.
+pub(crate) fn recv_from(socket: RawFd, buf: &mut [u8]) -> io::Result<(usize, u32, u32)> {
+    let mut addr: libc::sockaddr_nl = unsafe { mem::zeroed() };
+    let mut length = mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t;
+    let n = syscall!(recvfrom(
+        socket,
+        buf.as_mut_ptr() as *mut libc::c_void,
+        buf.len(),
+        0,
+        &mut addr as *mut libc::sockaddr_nl as *mut libc::sockaddr,
+        &mut length,
+    ))?;
+    Ok((n as usize, addr.nl_pid, addr.nl_groups))
+}
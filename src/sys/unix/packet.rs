@@ -0,0 +1,108 @@
+use crate::sys::unix::net::new_socket_with_protocol;
+use std::fs::File;
+use std::io;
+use std::mem;
+use std::os::unix::io::{FromRawFd, RawFd};
+
+/// Create a new non-blocking `AF_PACKET` socket of `socket_type`, receiving
+/// frames of `protocol` (in network byte order).
+pub(crate) fn new(socket_type: libc::c_int, protocol: u16) -> io::Result<File> {
+    new_socket_with_protocol(libc::AF_PACKET, socket_type, protocol as libc::c_int)
+        .map(|socket| unsafe { File::from_raw_fd(socket) })
+}
+
+pub(crate) fn bind(socket: RawFd, addr: &libc::sockaddr_ll) -> io::Result<()> {
+    syscall!(bind(
+        socket,
+        addr as *const libc::sockaddr_ll as *const libc::sockaddr,
+        mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+    ))
+    .map(|_| ())
+}
+
+pub(crate) fn send_to(socket: RawFd, buf: &[u8], target: &libc::sockaddr_ll) -> io::Result<usize> {
+    syscall!(sendto(
+        socket,
+        buf.as_ptr() as *const libc::c_void,
+        buf.len(),
+        0,
+        target as *const libc::sockaddr_ll as *const libc::sockaddr,
+        mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+    ))
+    .map(|n| n as usize)
+}
+
+pub(crate) fn recv_from(socket: RawFd, buf: &mut [u8]) -> io::Result<(usize, libc::sockaddr_ll)> {
+    let mut addr: libc::sockaddr_ll = unsafe { mem::zeroed() };
+    let mut length = mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t;
+    let n = syscall!(recvfrom(
+        socket,
+        buf.as_mut_ptr() as *mut libc::c_void,
+        buf.len(),
+        0,
+        &mut addr as *mut libc::sockaddr_ll as *mut libc::sockaddr,
+        &mut length,
+    ))?;
+    Ok((n as usize, addr))
+}
+
+/// Attach the classic BPF program `filter` to the socket.
+pub(crate) fn attach_filter(socket: RawFd, filter: &[SockFilter]) -> io::Result<()> {
+    if filter.len() > u16::MAX as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "filter program too long",
+        ));
+    }
+    let program = libc::sock_fprog {
+        len: filter.len() as libc::c_ushort,
+        filter: filter.as_ptr() as *mut libc::sock_filter,
+    };
+    syscall!(setsockopt(
+        socket,
+        libc::SOL_SOCKET,
+        libc::SO_ATTACH_FILTER,
+        &program as *const libc::sock_fprog as *const libc::c_void,
+        mem::size_of::<libc::sock_fprog>() as libc::socklen_t,
+    ))
+    .map(|_| ())
+}
+
+pub(crate) fn detach_filter(socket: RawFd) -> io::Result<()> {
+    // The value is ignored, but must be present.
+    let value: libc::c_int = 0;
+    syscall!(setsockopt(
+        socket,
+        libc::SOL_SOCKET,
+        libc::SO_DETACH_FILTER,
+        &value as *const libc::c_int as *const libc::c_void,
+        mem::size_of::<libc::c_int>() as libc::socklen_t,
+    ))
+    .map(|_| ())
+}
+
+/// A single classic BPF instruction, see [`PacketSocket::attach_filter`].
+///
+/// This has the same layout as `struct sock_filter` in `<linux/filter.h>`, so
+/// programs generated by e.g. `tcpdump -dd` can be used as is.
+///
+/// [`PacketSocket::attach_filter`]: struct.PacketSocket.html#method.attach_filter
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SockFilter {
+    /// The operation code.
+    pub code: u16,
+    /// Jump offset if the condition is true.
+    pub jt: u8,
+    /// Jump offset if the condition is false.
+    pub jf: u8,
+    /// Generic field, e.g. a constant or offset.
+    pub k: u32,
+}
+
+impl SockFilter {
+    /// Creates a new BPF instruction.
+    pub const fn new(code: u16, jt: u8, jf: u8, k: u32) -> SockFilter {
+        SockFilter { code, jt, jf, k }
+    }
+}
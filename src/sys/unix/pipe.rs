@@ -0,0 +1,45 @@
+use std::io;
+use std::os::unix::io::RawFd;
+
+/// Create a new non-blocking unix pipe, with the close-on-exec flag set.
+///
+/// Returns the receiving and sending end, in that order.
+pub fn new_raw() -> io::Result<[RawFd; 2]> {
+    let mut fds: [RawFd; 2] = [-1, -1];
+
+    #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+    syscall!(pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC | libc::O_NONBLOCK))?;
+
+    // `pipe2` is not available on iOS and macOS, so we need to set the flags
+    // manually.
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    {
+        syscall!(pipe(fds.as_mut_ptr()))?;
+        for &fd in &fds {
+            let res = syscall!(fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC))
+                .and_then(|_| set_nonblocking(fd, true));
+            if let Err(err) = res {
+                let _ = syscall!(close(fds[0]));
+                let _ = syscall!(close(fds[1]));
+                return Err(err);
+            }
+        }
+    }
+
+    Ok(fds)
+}
+
+/// Set or clear the non-blocking flag of `fd`, keeping the other file status
+/// flags.
+pub fn set_nonblocking(fd: RawFd, nonblocking: bool) -> io::Result<()> {
+    let flags = syscall!(fcntl(fd, libc::F_GETFL))?;
+    let new_flags = if nonblocking {
+        flags | libc::O_NONBLOCK
+    } else {
+        flags & !libc::O_NONBLOCK
+    };
+    if flags != new_flags {
+        syscall!(fcntl(fd, libc::F_SETFL, new_flags))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use super::signals::Signals;
+
+use std::fs::File;
+use std::io;
+use std::mem;
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+
+/// Notification of a child process exiting.
+///
+/// This uses a pidfd, which becomes readable once the process exits. On
+/// kernels without `pidfd_open` (before Linux 5.3) this falls back to a
+/// `signalfd` receiving `SIGCHLD`. A `SIGCHLD` is only received by a single
+/// `signalfd`, so with multiple notifiers only one of them becomes readable
+/// once *any* child process exits.
+#[derive(Debug)]
+pub enum ExitNotifier {
+    PidFd(File),
+    SigChld(Signals),
+}
+
+impl ExitNotifier {
+    pub fn new(pid: u32) -> io::Result<ExitNotifier> {
+        match pidfd_open(pid) {
+            Ok(fd) => Ok(ExitNotifier::PidFd(fd)),
+            Err(ref err) if err.raw_os_error() == Some(libc::ENOSYS) => {
+                sigchld(pid).map(ExitNotifier::SigChld)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Reset the readiness, must be called *before* checking whether the
+    /// process exited to not miss any notifications.
+    pub fn reset(&self) {
+        match self {
+            // A pidfd stays readable once the process exited.
+            ExitNotifier::PidFd(_) => {}
+            ExitNotifier::SigChld(signals) => while signals.receive().is_ok() {},
+        }
+    }
+}
+
+impl AsRawFd for ExitNotifier {
+    fn as_raw_fd(&self) -> RawFd {
+        match self {
+            ExitNotifier::PidFd(fd) => fd.as_raw_fd(),
+            ExitNotifier::SigChld(signals) => signals.as_raw_fd(),
+        }
+    }
+}
+
+fn pidfd_open(pid: u32) -> io::Result<File> {
+    // The returned file descriptor has the close-on-exec flag set.
+    syscall!(syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0))
+        .map(|fd| unsafe { File::from_raw_fd(fd as RawFd) })
+}
+
+/// Handle `SIGCHLD` using `Signals`, which blocks the signal for the calling
+/// thread and unblocks it again once the last `Signals` handling it is
+/// dropped.
+fn sigchld(pid: u32) -> io::Result<Signals> {
+    let signals = Signals::new(&[libc::SIGCHLD])?;
+
+    // The process could have exited before `SIGCHLD` was blocked, in which
+    // case we'll never receive the signal. So check if the process already
+    // exited (without reaping it) and if so send the signal ourselves.
+    let mut info: libc::siginfo_t = unsafe { mem::zeroed() };
+    syscall!(waitid(
+        libc::P_PID,
+        pid as libc::id_t,
+        &mut info,
+        libc::WEXITED | libc::WNOHANG | libc::WNOWAIT
+    ))?;
+    if unsafe { info.si_pid() } != 0 {
+        syscall!(kill(libc::getpid(), libc::SIGCHLD))?;
+    }
+    Ok(signals)
+}
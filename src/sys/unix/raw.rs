@@ -0,0 +1,115 @@
+use crate::sys::unix::net::{
+    getsockopt, new_socket_with_protocol, setsockopt, socket_addr, to_socket_addr,
+};
+
+pub(crate) use crate::sys::unix::net::take_error;
+
+use std::fs::File;
+use std::io;
+use std::mem;
+use std::net::SocketAddr;
+use std::os::unix::io::{FromRawFd, RawFd};
+
+pub(crate) fn new_v4(protocol: i32) -> io::Result<File> {
+    new(libc::AF_INET, libc::SOCK_RAW, protocol)
+}
+
+pub(crate) fn new_v6(protocol: i32) -> io::Result<File> {
+    new(libc::AF_INET6, libc::SOCK_RAW, protocol)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn new_ping_v4() -> io::Result<File> {
+    new(libc::AF_INET, libc::SOCK_DGRAM, libc::IPPROTO_ICMP)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn new_ping_v6() -> io::Result<File> {
+    new(libc::AF_INET6, libc::SOCK_DGRAM, libc::IPPROTO_ICMPV6)
+}
+
+fn new(domain: libc::c_int, socket_type: libc::c_int, protocol: i32) -> io::Result<File> {
+    new_socket_with_protocol(domain, socket_type, protocol)
+        .map(|socket| unsafe { File::from_raw_fd(socket) })
+}
+
+pub(crate) fn bind(socket: RawFd, addr: SocketAddr) -> io::Result<()> {
+    let (raw_addr, raw_addr_length) = socket_addr(&addr);
+    syscall!(bind(socket, raw_addr.as_ptr(), raw_addr_length)).map(|_| ())
+}
+
+pub(crate) fn connect(socket: RawFd, addr: SocketAddr) -> io::Result<()> {
+    let (raw_addr, raw_addr_length) = socket_addr(&addr);
+    syscall!(connect(socket, raw_addr.as_ptr(), raw_addr_length)).map(|_| ())
+}
+
+pub(crate) fn local_addr(socket: RawFd) -> io::Result<SocketAddr> {
+    let mut storage: libc::sockaddr_storage = unsafe { mem::zeroed() };
+    let mut length = mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+    syscall!(getsockname(
+        socket,
+        &mut storage as *mut _ as *mut libc::sockaddr,
+        &mut length
+    ))?;
+    unsafe { to_socket_addr(&storage) }
+}
+
+pub(crate) fn send_to(socket: RawFd, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
+    let (raw_addr, raw_addr_length) = socket_addr(&target);
+    syscall!(sendto(
+        socket,
+        buf.as_ptr() as *const libc::c_void,
+        buf.len(),
+        0,
+        raw_addr.as_ptr(),
+        raw_addr_length
+    ))
+    .map(|n| n as usize)
+}
+
+pub(crate) fn send(socket: RawFd, buf: &[u8]) -> io::Result<usize> {
+    syscall!(send(
+        socket,
+        buf.as_ptr() as *const libc::c_void,
+        buf.len(),
+        0
+    ))
+    .map(|n| n as usize)
+}
+
+pub(crate) fn recv_from(socket: RawFd, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+    let mut storage: libc::sockaddr_storage = unsafe { mem::zeroed() };
+    let mut length = mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+    let n = syscall!(recvfrom(
+        socket,
+        buf.as_mut_ptr() as *mut libc::c_void,
+        buf.len(),
+        0,
+        &mut storage as *mut _ as *mut libc::sockaddr,
+        &mut length
+    ))?;
+    unsafe { to_socket_addr(&storage) }.map(|addr| (n as usize, addr))
+}
+
+pub(crate) fn recv(socket: RawFd, buf: &mut [u8]) -> io::Result<usize> {
+    syscall!(recv(
+        socket,
+        buf.as_mut_ptr() as *mut libc::c_void,
+        buf.len(),
+        0
+    ))
+    .map(|n| n as usize)
+}
+
+pub(crate) fn set_header_included(socket: RawFd, included: bool) -> io::Result<()> {
+    setsockopt(
+        socket,
+        libc::IPPROTO_IP,
+        libc::IP_HDRINCL,
+        included as libc::c_int,
+    )
+}
+
+pub(crate) fn header_included(socket: RawFd) -> io::Result<bool> {
+    getsockopt::<libc::c_int>(socket, libc::IPPROTO_IP, libc::IP_HDRINCL).map(|v| v != 0)
+}
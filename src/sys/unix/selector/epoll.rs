@@ -1,12 +1,12 @@
-use crate::{Interest, Token};
+use crate::{Interest, Mode, Token};
 
-use libc::{EPOLLET, EPOLLIN, EPOLLOUT, EPOLLRDHUP};
+use libc::{EPOLLET, EPOLLIN, EPOLLONESHOT, EPOLLOUT, EPOLLRDHUP};
 use log::error;
 use std::os::unix::io::{AsRawFd, RawFd};
 #[cfg(debug_assertions)]
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
-use std::{cmp, i32, io, ptr};
+use std::{cmp, i32, io};
 
 /// Unique id for use as `SelectorId`.
 #[cfg(debug_assertions)]
@@ -60,6 +60,16 @@ impl Selector {
     }
 
     pub fn register(&self, fd: RawFd, token: Token, interests: Interest) -> io::Result<()> {
+        #[cfg(target_os = "solaris")]
+        {
+            if interests.is_exclusive() {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "exclusive registrations are not supported on this platform",
+                ));
+            }
+        }
+
         let mut event = libc::epoll_event {
             events: interests_to_epoll(interests),
             u64: usize::from(token) as u64,
@@ -67,7 +77,10 @@ impl Selector {
 
         syscall!(epoll_ctl(self.ep, libc::EPOLL_CTL_ADD, fd, &mut event)).map(|_| ())
     }
+}
 
+cfg_io_source! {
+    impl Selector {
     pub fn reregister(&self, fd: RawFd, token: Token, interests: Interest) -> io::Result<()> {
         let mut event = libc::epoll_event {
             events: interests_to_epoll(interests),
@@ -78,12 +91,9 @@ impl Selector {
     }
 
     pub fn deregister(&self, fd: RawFd) -> io::Result<()> {
-        syscall!(epoll_ctl(self.ep, libc::EPOLL_CTL_DEL, fd, ptr::null_mut())).map(|_| ())
+        syscall!(epoll_ctl(self.ep, libc::EPOLL_CTL_DEL, fd, std::ptr::null_mut())).map(|_| ())
     }
-}
 
-cfg_net! {
-    impl Selector {
         #[cfg(debug_assertions)]
         pub fn id(&self) -> usize {
             self.id
@@ -106,7 +116,11 @@ impl Drop for Selector {
 }
 
 fn interests_to_epoll(interests: Interest) -> u32 {
-    let mut kind = EPOLLET;
+    let mut kind = match interests.mode() {
+        Mode::Edge => EPOLLET,
+        Mode::Level => 0,
+        Mode::Oneshot => EPOLLONESHOT,
+    };
 
     if interests.is_readable() {
         kind = kind | EPOLLIN | EPOLLRDHUP;
@@ -116,6 +130,15 @@ fn interests_to_epoll(interests: Interest) -> u32 {
         kind |= EPOLLOUT;
     }
 
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    {
+        if interests.is_exclusive() {
+            // `EPOLLRDHUP` can't be combined with `EPOLLEXCLUSIVE`, closed
+            // connections are still reported as readable.
+            kind = (kind & !EPOLLRDHUP) | libc::EPOLLEXCLUSIVE;
+        }
+    }
+
     kind as u32
 }
 
@@ -175,6 +198,16 @@ pub mod event {
         false
     }
 
+    cfg_time! {
+        /// Create a readable event for an expired `TimerWheel` timeout.
+        pub fn timeout(token: Token) -> Event {
+            libc::epoll_event {
+                events: libc::EPOLLIN as u32,
+                u64: usize::from(token) as u64,
+            }
+        }
+    }
+
     pub fn debug_details(f: &mut fmt::Formatter<'_>, event: &Event) -> fmt::Result {
         #[allow(clippy::trivially_copy_pass_by_ref)]
         fn check_events(got: &u32, want: &libc::c_int) -> bool {
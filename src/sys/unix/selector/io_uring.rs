@@ -0,0 +1,956 @@
+//! Selector backed by io_uring, falling back to epoll if io_uring isn't
+//! supported by the kernel.
+//!
+//! Every edge-triggered registration is a multishot `IORING_OP_POLL_ADD`
+//! request, which posts a completion every time the file descriptor becomes
+//! ready. Level-triggered and oneshot registrations use single poll requests,
+//! which are re-added after every completion for level-triggered
+//! registrations.
+//!
+//! Registrations are queued in the submission queue and submitted in a single
+//! batch as part of the next call to `select`, or directly if a thread is
+//! currently blocked in `select`. Only deregistrations are always submitted
+//! directly, as the poll request holds a reference to the file, keeping it
+//! open until the request is removed.
+//!
+//! Requires Linux 5.13 for multishot poll requests.
+
+use crate::sys::unix::selector::epoll;
+use crate::{Interest, Mode, Token};
+
+use log::{debug, error};
+use std::collections::HashMap;
+use std::os::unix::io::{AsRawFd, RawFd};
+#[cfg(debug_assertions)]
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use std::{cmp, io, mem, ptr};
+
+pub use self::epoll::{event, Event, Events};
+
+/// Unique id for use as `SelectorId`.
+#[cfg(debug_assertions)]
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+#[derive(Debug)]
+pub struct Selector {
+    kind: Kind,
+}
+
+#[derive(Debug)]
+enum Kind {
+    Ring(Arc<Ring>),
+    Epoll(epoll::Selector),
+}
+
+impl Selector {
+    pub fn new() -> io::Result<Selector> {
+        Selector::with_ring(Ring::new())
+    }
+
+    /// Uses `ring`, or falls back to epoll if creating it failed.
+    fn with_ring(ring: io::Result<Ring>) -> io::Result<Selector> {
+        let kind = match ring {
+            Ok(ring) => Kind::Ring(Arc::new(ring)),
+            Err(err) => {
+                debug!("io_uring not supported, falling back to epoll: {}", err);
+                Kind::Epoll(epoll::Selector::new()?)
+            }
+        };
+        Ok(Selector { kind })
+    }
+
+    pub fn try_clone(&self) -> io::Result<Selector> {
+        let kind = match self.kind {
+            // It's the same selector, so we share the ring (and id).
+            Kind::Ring(ref ring) => Kind::Ring(ring.clone()),
+            Kind::Epoll(ref selector) => Kind::Epoll(selector.try_clone()?),
+        };
+        Ok(Selector { kind })
+    }
+
+    pub fn select(&self, events: &mut Events, timeout: Option<Duration>) -> io::Result<()> {
+        match self.kind {
+            Kind::Ring(ref ring) => ring.select(events, timeout),
+            Kind::Epoll(ref selector) => selector.select(events, timeout),
+        }
+    }
+
+    pub fn register(&self, fd: RawFd, token: Token, interests: Interest) -> io::Result<()> {
+        match self.kind {
+            Kind::Ring(ref ring) => ring.register(fd, token, interests),
+            Kind::Epoll(ref selector) => selector.register(fd, token, interests),
+        }
+    }
+}
+
+cfg_io_source! {
+    impl Selector {
+    pub fn reregister(&self, fd: RawFd, token: Token, interests: Interest) -> io::Result<()> {
+        match self.kind {
+            Kind::Ring(ref ring) => ring.reregister(fd, token, interests),
+            Kind::Epoll(ref selector) => selector.reregister(fd, token, interests),
+        }
+    }
+
+    pub fn deregister(&self, fd: RawFd) -> io::Result<()> {
+        match self.kind {
+            Kind::Ring(ref ring) => ring.deregister(fd),
+            Kind::Epoll(ref selector) => selector.deregister(fd),
+        }
+    }
+
+        #[cfg(debug_assertions)]
+        pub fn id(&self) -> usize {
+            match self.kind {
+                Kind::Ring(ref ring) => ring.id,
+                Kind::Epoll(ref selector) => selector.id(),
+            }
+        }
+    }
+
+    /// State of an `IoSource`, see `Registration`.
+    #[derive(Debug)]
+    pub(crate) struct IoSourceState {
+        registration: Option<Registration>,
+        exclusive: bool,
+    }
+
+    impl IoSourceState {
+        pub fn new() -> IoSourceState {
+            IoSourceState {
+                registration: None,
+                exclusive: false,
+            }
+        }
+
+        pub fn do_io<T, F, R>(&self, f: F, io: &T) -> io::Result<R>
+        where
+            F: FnOnce(&T) -> io::Result<R>,
+        {
+            f(io)
+        }
+
+        /// Called after `fd` is registered with `selector`.
+        pub fn registered(&mut self, selector: &Selector, fd: RawFd, interests: Interest) {
+            self.registration = Registration::new(selector, fd);
+            self.exclusive = interests.is_exclusive();
+        }
+
+        /// Called after the source is deregistered.
+        pub fn deregistered(&mut self) {
+            // Dropping the registration tries to remove it again, which
+            // fails as it's already removed.
+            self.registration = None;
+            self.exclusive = false;
+        }
+
+        /// Returns true if the source is registered exclusively, only
+        /// possible when falling back to epoll.
+        pub fn is_exclusive(&self) -> bool {
+            self.exclusive
+        }
+    }
+}
+
+/// Removes the registration of a file descriptor when dropped.
+///
+/// Poll requests hold a reference to the file, so unlike with epoll, closing a
+/// file descriptor doesn't remove its registration and the request keeps
+/// returning events for its token. Types registering a file descriptor they
+/// own, e.g. `IoSource` and `Waker`, keep this around to remove the
+/// registration when they're dropped.
+#[derive(Debug)]
+pub(crate) struct Registration {
+    ring: Arc<Ring>,
+    fd: RawFd,
+}
+
+impl Registration {
+    /// Returns `None` if `selector` isn't using io_uring.
+    pub fn new(selector: &Selector, fd: RawFd) -> Option<Registration> {
+        match selector.kind {
+            Kind::Ring(ref ring) => Some(Registration {
+                ring: ring.clone(),
+                fd,
+            }),
+            Kind::Epoll(..) => None,
+        }
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        // Not registered (anymore) is fine.
+        let _ = self.ring.deregister(self.fd);
+    }
+}
+
+impl AsRawFd for Selector {
+    fn as_raw_fd(&self) -> RawFd {
+        match self.kind {
+            Kind::Ring(ref ring) => ring.fd,
+            Kind::Epoll(ref selector) => selector.as_raw_fd(),
+        }
+    }
+}
+
+// Constants and types from `<linux/io_uring.h>`.
+
+const IORING_OFF_SQ_RING: libc::off_t = 0;
+const IORING_OFF_SQES: libc::off_t = 0x1000_0000;
+
+const IORING_SETUP_CQSIZE: u32 = 1 << 3;
+const IORING_SETUP_CLAMP: u32 = 1 << 4;
+
+const IORING_FEAT_SINGLE_MMAP: u32 = 1 << 0;
+const IORING_FEAT_NODROP: u32 = 1 << 1;
+const IORING_FEAT_EXT_ARG: u32 = 1 << 8;
+
+const IORING_ENTER_GETEVENTS: u32 = 1 << 0;
+const IORING_ENTER_EXT_ARG: u32 = 1 << 3;
+
+const IORING_OP_POLL_ADD: u8 = 6;
+const IORING_OP_ASYNC_CANCEL: u8 = 14;
+
+const IORING_POLL_ADD_MULTI: u32 = 1 << 0;
+
+const IORING_CQE_F_MORE: u32 = 1 << 1;
+
+#[repr(C)]
+#[derive(Default)]
+struct SqringOffsets {
+    head: u32,
+    tail: u32,
+    ring_mask: u32,
+    ring_entries: u32,
+    flags: u32,
+    dropped: u32,
+    array: u32,
+    resv1: u32,
+    user_addr: u64,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct CqringOffsets {
+    head: u32,
+    tail: u32,
+    ring_mask: u32,
+    ring_entries: u32,
+    overflow: u32,
+    cqes: u32,
+    flags: u32,
+    resv1: u32,
+    user_addr: u64,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct Params {
+    sq_entries: u32,
+    cq_entries: u32,
+    flags: u32,
+    sq_thread_cpu: u32,
+    sq_thread_idle: u32,
+    features: u32,
+    wq_fd: u32,
+    resv: [u32; 3],
+    sq_off: SqringOffsets,
+    cq_off: CqringOffsets,
+}
+
+/// Submission queue entry, `struct io_uring_sqe`.
+#[repr(C)]
+#[derive(Default)]
+struct Sqe {
+    opcode: u8,
+    flags: u8,
+    ioprio: u16,
+    fd: i32,
+    off: u64,
+    addr: u64,
+    len: u32,
+    poll32_events: u32,
+    user_data: u64,
+    buf_index: u16,
+    personality: u16,
+    splice_fd_in: i32,
+    addr3: u64,
+    pad: u64,
+}
+
+#[repr(C)]
+struct Cqe {
+    user_data: u64,
+    res: i32,
+    flags: u32,
+}
+
+/// `struct __kernel_timespec`, which always uses 64 bit fields.
+#[repr(C)]
+struct KernelTimespec {
+    tv_sec: i64,
+    tv_nsec: i64,
+}
+
+#[repr(C)]
+struct GeteventsArg {
+    sigmask: u64,
+    sigmask_sz: u32,
+    pad: u32,
+    ts: u64,
+}
+
+/// Number of submission queue entries.
+const SQ_ENTRIES: u32 = 256;
+/// Number of completion queue entries, each registration can have multiple
+/// completions pending.
+const CQ_ENTRIES: u32 = 4096;
+
+/// `user_data` of requests whose completion is ignored, i.e. poll removals.
+/// Registration ids start at 0 so this is never used for a registration.
+const IGNORED: u64 = u64::MAX;
+
+/// An io_uring instance.
+struct Ring {
+    #[cfg(debug_assertions)]
+    id: usize,
+    fd: RawFd,
+    /// Shared submission and completion queue rings.
+    rings: *mut libc::c_void,
+    rings_len: usize,
+    sqes: *mut Sqe,
+    sqes_len: usize,
+    sq_head: *const AtomicU32,
+    sq_tail: *const AtomicU32,
+    sq_mask: u32,
+    sq_entries: u32,
+    cq_head: *const AtomicU32,
+    cq_tail: *const AtomicU32,
+    cq_mask: u32,
+    cqes: *const Cqe,
+    state: Mutex<State>,
+}
+
+// The raw pointers point into the memory shared with the kernel, access to
+// the submission queue and writing the completion queue head is guarded by
+// the `state` mutex.
+unsafe impl Send for Ring {}
+unsafe impl Sync for Ring {}
+
+struct State {
+    /// Registrations by file descriptor: the registration id (`user_data` of
+    /// the poll request), token and interests.
+    registrations: HashMap<RawFd, (u64, Token, Interest)>,
+    /// File descriptor by registration id.
+    ids: HashMap<u64, RawFd>,
+    next_id: u64,
+    /// Whether or not a thread is blocked in `select`, if so submissions must
+    /// be submitted directly.
+    polling: bool,
+    /// Index into the events by registration id, used in `select` to merge
+    /// completions for the same registration into a single event.
+    merge: HashMap<u64, usize>,
+}
+
+impl Ring {
+    fn new() -> io::Result<Ring> {
+        let mut params = Params {
+            flags: IORING_SETUP_CQSIZE | IORING_SETUP_CLAMP,
+            cq_entries: CQ_ENTRIES,
+            ..Params::default()
+        };
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_io_uring_setup,
+                SQ_ENTRIES,
+                &mut params as *mut Params,
+            )
+        };
+        if fd == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        let fd = fd as RawFd;
+
+        // Extended arguments (Linux 5.11) are needed to wait with a timeout,
+        // all other features were added before that.
+        let required = IORING_FEAT_SINGLE_MMAP | IORING_FEAT_NODROP | IORING_FEAT_EXT_ARG;
+        if params.features & required != required {
+            let _ = syscall!(close(fd));
+            return Err(io::Error::other("io_uring doesn't support the required features"));
+        }
+
+        let rings_len = cmp::max(
+            params.sq_off.array as usize + params.sq_entries as usize * mem::size_of::<u32>(),
+            params.cq_off.cqes as usize + params.cq_entries as usize * mem::size_of::<Cqe>(),
+        );
+        let rings = match mmap(fd, rings_len, IORING_OFF_SQ_RING) {
+            Ok(rings) => rings,
+            Err(err) => {
+                let _ = syscall!(close(fd));
+                return Err(err);
+            }
+        };
+        let sqes_len = params.sq_entries as usize * mem::size_of::<Sqe>();
+        let sqes = match mmap(fd, sqes_len, IORING_OFF_SQES) {
+            Ok(sqes) => sqes as *mut Sqe,
+            Err(err) => {
+                unsafe { libc::munmap(rings, rings_len) };
+                let _ = syscall!(close(fd));
+                return Err(err);
+            }
+        };
+
+        let offset = |offset: u32| unsafe { (rings as *mut u8).add(offset as usize) };
+        let ring = unsafe {
+            Ring {
+                #[cfg(debug_assertions)]
+                id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+                fd,
+                rings,
+                rings_len,
+                sqes,
+                sqes_len,
+                sq_head: offset(params.sq_off.head) as *const AtomicU32,
+                sq_tail: offset(params.sq_off.tail) as *const AtomicU32,
+                sq_mask: *(offset(params.sq_off.ring_mask) as *const u32),
+                sq_entries: params.sq_entries,
+                cq_head: offset(params.cq_off.head) as *const AtomicU32,
+                cq_tail: offset(params.cq_off.tail) as *const AtomicU32,
+                cq_mask: *(offset(params.cq_off.ring_mask) as *const u32),
+                cqes: offset(params.cq_off.cqes) as *const Cqe,
+                state: Mutex::new(State {
+                    registrations: HashMap::new(),
+                    ids: HashMap::new(),
+                    next_id: 0,
+                    polling: false,
+                    merge: HashMap::new(),
+                }),
+            }
+        };
+
+        // We always use the submission queue entry with the same index as
+        // its position in the ring.
+        let array = offset(params.sq_off.array) as *mut u32;
+        for i in 0..params.sq_entries {
+            unsafe { *array.add(i as usize) = i };
+        }
+
+        ring.probe()?;
+        Ok(ring)
+    }
+
+    /// Checks if multishot poll requests are supported (Linux 5.13), by
+    /// polling an always writable eventfd.
+    fn probe(&self) -> io::Result<()> {
+        let efd = syscall!(eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK))?;
+        let mut state = self.lock();
+        let result = self
+            .push(
+                &mut state,
+                poll_add(efd, libc::EPOLLOUT as u32, true, IGNORED - 1),
+            )
+            .and_then(|()| self.enter(self.pending(), 1, IORING_ENTER_GETEVENTS, None))
+            .and_then(|()| {
+                let cqe = self.pop().expect("missing io_uring completion");
+                if cqe.res < 0 {
+                    Err(io::Error::from_raw_os_error(-cqe.res))
+                } else if cqe.flags & IORING_CQE_F_MORE == 0 {
+                    Err(io::Error::other("io_uring doesn't support multishot poll requests"))
+                } else {
+                    self.remove(&mut state, IGNORED - 1)
+                }
+            });
+        let _ = syscall!(close(efd));
+        result
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn enter(
+        &self,
+        to_submit: u32,
+        min_complete: u32,
+        flags: u32,
+        timeout: Option<&KernelTimespec>,
+    ) -> io::Result<()> {
+        let arg = GeteventsArg {
+            sigmask: 0,
+            sigmask_sz: 0,
+            pad: 0,
+            ts: timeout.map_or(0, |ts| ts as *const KernelTimespec as u64),
+        };
+        let res = unsafe {
+            libc::syscall(
+                libc::SYS_io_uring_enter,
+                self.fd,
+                to_submit,
+                min_complete,
+                flags | IORING_ENTER_EXT_ARG,
+                &arg as *const GeteventsArg,
+                mem::size_of::<GeteventsArg>(),
+            )
+        };
+        if res == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the number of entries in the submission queue not yet
+    /// consumed by the kernel.
+    fn pending(&self) -> u32 {
+        let tail = unsafe { (*self.sq_tail).load(Ordering::Relaxed) };
+        let head = unsafe { (*self.sq_head).load(Ordering::Acquire) };
+        tail.wrapping_sub(head)
+    }
+
+    /// Submits all pending submission queue entries.
+    ///
+    /// Like all methods that access the submission queue this requires the
+    /// `state` lock to be held.
+    fn submit(&self, _: &mut State) -> io::Result<()> {
+        match self.pending() {
+            0 => Ok(()),
+            pending => self.enter(pending, 0, 0, None),
+        }
+    }
+
+    /// Adds `sqe` to the submission queue, submitting the queue first if it's
+    /// full.
+    fn push(&self, state: &mut State, sqe: Sqe) -> io::Result<()> {
+        if self.pending() == self.sq_entries {
+            self.submit(state)?;
+            if self.pending() == self.sq_entries {
+                return Err(io::Error::from_raw_os_error(libc::EBUSY));
+            }
+        }
+        let tail = unsafe { (*self.sq_tail).load(Ordering::Relaxed) };
+        unsafe {
+            ptr::write(self.sqes.add((tail & self.sq_mask) as usize), sqe);
+            (*self.sq_tail).store(tail.wrapping_add(1), Ordering::Release);
+        }
+        Ok(())
+    }
+
+    /// Removes the next completion queue entry, if any.
+    fn pop(&self) -> Option<Cqe> {
+        let head = unsafe { (*self.cq_head).load(Ordering::Relaxed) };
+        let tail = unsafe { (*self.cq_tail).load(Ordering::Acquire) };
+        if head == tail {
+            return None;
+        }
+        unsafe {
+            let cqe = ptr::read(self.cqes.add((head & self.cq_mask) as usize));
+            (*self.cq_head).store(head.wrapping_add(1), Ordering::Release);
+            Some(cqe)
+        }
+    }
+
+    fn has_completions(&self) -> bool {
+        let head = unsafe { (*self.cq_head).load(Ordering::Relaxed) };
+        let tail = unsafe { (*self.cq_tail).load(Ordering::Acquire) };
+        head != tail
+    }
+
+    /// Submits the pending entries if needed, i.e. if another thread is
+    /// blocked in `select`.
+    fn maybe_submit(&self, state: &mut State) -> io::Result<()> {
+        if state.polling {
+            self.submit(state)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Queues the removal of the poll request with `user_data`.
+    ///
+    /// This uses `IORING_OP_ASYNC_CANCEL` rather than `IORING_OP_POLL_REMOVE`
+    /// as the latter fails with `EALREADY` if a completion for the request is
+    /// being posted, leaving the request armed.
+    fn remove(&self, state: &mut State, user_data: u64) -> io::Result<()> {
+        let sqe = Sqe {
+            opcode: IORING_OP_ASYNC_CANCEL,
+            fd: -1,
+            addr: user_data,
+            user_data: IGNORED,
+            ..Sqe::default()
+        };
+        self.push(state, sqe)
+    }
+
+    /// Queues a new poll request for `fd`.
+    fn add(
+        &self,
+        state: &mut State,
+        fd: RawFd,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        let id = state.next_id;
+        state.next_id += 1;
+        let multishot = interests.mode() == Mode::Edge;
+        let sqe = poll_add(fd, interests_to_poll(interests), multishot, id);
+        self.push(state, sqe)?;
+        state.registrations.insert(fd, (id, token, interests));
+        state.ids.insert(id, fd);
+        Ok(())
+    }
+
+    fn register(&self, fd: RawFd, token: Token, interests: Interest) -> io::Result<()> {
+        if interests.is_exclusive() {
+            return Err(io::Error::other(
+                "exclusive registrations are not supported by the io_uring selector",
+            ));
+        }
+
+        let mut state = self.lock();
+        // Unlike epoll the registration isn't removed when the file
+        // descriptor is closed, so the file descriptor could have been reused.
+        if let Some((id, _, _)) = state.registrations.remove(&fd) {
+            state.ids.remove(&id);
+            self.remove(&mut state, id)?;
+        }
+        self.add(&mut state, fd, token, interests)?;
+        self.maybe_submit(&mut state)
+    }
+
+    fn deregister(&self, fd: RawFd) -> io::Result<()> {
+        let mut state = self.lock();
+        let (id, _, _) = match state.registrations.remove(&fd) {
+            Some(registration) => registration,
+            None => return Err(io::Error::from_raw_os_error(libc::ENOENT)),
+        };
+        state.ids.remove(&id);
+        self.remove(&mut state, id)?;
+        // Submit directly to release the file.
+        self.submit(&mut state)
+    }
+
+    fn select(&self, events: &mut Events, timeout: Option<Duration>) -> io::Result<()> {
+        events.clear();
+
+        let deadline = timeout.and_then(|to| Instant::now().checked_add(to));
+        let mut timeout = timeout;
+        loop {
+            let mut state = self.lock();
+            let pending = self.pending();
+            let wait = timeout != Some(Duration::from_secs(0)) && !self.has_completions();
+            if wait {
+                state.polling = true;
+                drop(state);
+
+                let ts = timeout.map(|to| KernelTimespec {
+                    tv_sec: cmp::min(to.as_secs(), i64::MAX as u64) as i64,
+                    tv_nsec: i64::from(to.subsec_nanos()),
+                });
+                let res = self.enter(pending, 1, IORING_ENTER_GETEVENTS, ts.as_ref());
+
+                state = self.lock();
+                state.polling = false;
+                match res {
+                    Ok(()) => {}
+                    // Timeout expired, or the completion queue overflowed, in
+                    // which case we process the completions below.
+                    Err(ref err) if err.raw_os_error() == Some(libc::ETIME) => {}
+                    Err(ref err) if err.raw_os_error() == Some(libc::EBUSY) => {}
+                    Err(err) => return Err(err),
+                }
+            } else if pending != 0 {
+                self.enter(pending, 0, 0, None)?;
+            }
+
+            self.reap(&mut state, events)?;
+            if !events.is_empty() || timeout == Some(Duration::from_secs(0)) {
+                return Ok(());
+            }
+
+            // Only completions that don't produce events were posted, e.g.
+            // those of removed registrations, so wait for the remainder of
+            // the timeout (if any).
+            match (timeout, deadline) {
+                (None, _) | (Some(_), None) => {}
+                (Some(_), Some(deadline)) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Ok(());
+                    }
+                    timeout = Some(deadline - now);
+                }
+            }
+        }
+    }
+
+    /// Converts the completions into events.
+    fn reap(&self, state: &mut State, events: &mut Events) -> io::Result<()> {
+        let state = &mut *state;
+        state.merge.clear();
+        while events.len() < events.capacity() {
+            let cqe = match self.pop() {
+                Some(cqe) => cqe,
+                None => break,
+            };
+            let fd = match state.ids.get(&cqe.user_data) {
+                Some(fd) => *fd,
+                // Removed registration or ignored request.
+                None => continue,
+            };
+            let (id, token, interests) = state.registrations[&fd];
+            let ready = if cqe.res < 0 {
+                // Report errors, e.g. an invalid file descriptor, as an error
+                // event as the registration already succeeded.
+                error!(
+                    "io_uring poll request failed: {}",
+                    io::Error::from_raw_os_error(-cqe.res)
+                );
+                libc::EPOLLERR as u32
+            } else {
+                cqe.res as u32
+            };
+
+            if cqe.flags & IORING_CQE_F_MORE == 0 && interests.mode() != Mode::Oneshot {
+                // The poll request was terminated, e.g. because the
+                // completion queue overflowed, or it's a level-triggered
+                // registration. Level-triggered registrations are emulated
+                // by adding a new poll request after every completion, which
+                // completes directly if the source is still ready.
+                state.ids.remove(&id);
+                state.registrations.remove(&fd);
+                if cqe.res >= 0 {
+                    self.add(state, fd, token, interests)?;
+                }
+            }
+
+            match state.merge.get(&id) {
+                Some(&i) => events[i].events |= ready,
+                None => {
+                    state.merge.insert(id, events.len());
+                    events.push(libc::epoll_event {
+                        events: ready,
+                        u64: usize::from(token) as u64,
+                    });
+                }
+            }
+        }
+        self.maybe_submit(state)
+    }
+}
+
+cfg_io_source! {
+    impl Ring {
+        fn reregister(&self, fd: RawFd, token: Token, interests: Interest) -> io::Result<()> {
+            let mut state = self.lock();
+            let (id, _, _) = match state.registrations.remove(&fd) {
+                Some(registration) => registration,
+                None => return Err(io::Error::from_raw_os_error(libc::ENOENT)),
+            };
+            // Replace the poll request, a new id ensures completions for the
+            // old request are ignored.
+            state.ids.remove(&id);
+            self.remove(&mut state, id)?;
+            self.add(&mut state, fd, token, interests)?;
+            self.maybe_submit(&mut state)
+        }
+    }
+}
+
+impl std::fmt::Debug for Ring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("Ring");
+        #[cfg(debug_assertions)]
+        f.field("id", &self.id);
+        f.field("fd", &self.fd).finish()
+    }
+}
+
+impl Drop for Ring {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.sqes as *mut libc::c_void, self.sqes_len);
+            libc::munmap(self.rings, self.rings_len);
+        }
+        if let Err(err) = syscall!(close(self.fd)) {
+            error!("error closing io_uring: {}", err);
+        }
+    }
+}
+
+fn mmap(fd: RawFd, len: usize, offset: libc::off_t) -> io::Result<*mut libc::c_void> {
+    let ptr = unsafe {
+        libc::mmap(
+            ptr::null_mut(),
+            len,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED | libc::MAP_POPULATE,
+            fd,
+            offset,
+        )
+    };
+    if ptr == libc::MAP_FAILED {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(ptr)
+    }
+}
+
+/// Creates a poll request for `events` on `fd`, if `multishot` is false the
+/// request is removed after the first completion.
+fn poll_add(fd: RawFd, events: u32, multishot: bool, user_data: u64) -> Sqe {
+    // The kernel swaps the 16 bit halves on big endian.
+    #[cfg(target_endian = "big")]
+    let events = events.rotate_left(16);
+    Sqe {
+        opcode: IORING_OP_POLL_ADD,
+        fd,
+        len: if multishot { IORING_POLL_ADD_MULTI } else { 0 },
+        poll32_events: events,
+        user_data,
+        ..Sqe::default()
+    }
+}
+
+/// Multishot poll requests are edge-triggered, level-triggered and oneshot
+/// registrations use single poll requests, see `Ring::reap`.
+fn interests_to_poll(interests: Interest) -> u32 {
+    let mut kind = 0;
+
+    if interests.is_readable() {
+        kind |= libc::EPOLLIN | libc::EPOLLRDHUP;
+    }
+
+    if interests.is_writable() {
+        kind |= libc::EPOLLOUT;
+    }
+
+    kind as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{event, Kind, Registration, Ring, Selector};
+    use crate::{Interest, Mode, Token};
+    use std::fs::File;
+    use std::io::{self, Read, Write};
+    use std::os::unix::io::{AsRawFd, FromRawFd};
+    use std::time::Duration;
+
+    const TOKEN: Token = Token(1);
+
+    /// Returns the (non-blocking) receiving and sending end of a pipe.
+    fn pipe() -> (File, File) {
+        let mut fds = [-1; 2];
+        syscall!(pipe2(fds.as_mut_ptr(), libc::O_NONBLOCK | libc::O_CLOEXEC)).unwrap();
+        unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) }
+    }
+
+    fn expect_event(selector: &Selector, events: &mut Vec<libc::epoll_event>) {
+        selector
+            .select(events, Some(Duration::from_secs(1)))
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(event::token(&events[0]), TOKEN);
+        assert!(event::is_readable(&events[0]));
+    }
+
+    fn expect_no_events(selector: &Selector, events: &mut Vec<libc::epoll_event>) {
+        selector
+            .select(events, Some(Duration::from_millis(10)))
+            .unwrap();
+        assert!(events.is_empty(), "unexpected events: {:?}", events);
+    }
+
+    /// Returns the registration id of `fd`.
+    fn registration_id(selector: &Selector, fd: &File) -> u64 {
+        match selector.kind {
+            Kind::Ring(ref ring) => ring.lock().registrations[&fd.as_raw_fd()].0,
+            Kind::Epoll(..) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn epoll_fallback() {
+        let err = io::Error::from_raw_os_error(libc::ENOSYS);
+        let selector = Selector::with_ring(Err(err)).unwrap();
+        if let Kind::Ring(..) = selector.kind {
+            panic!("expected the epoll selector");
+        }
+
+        let (receiver, mut sender) = pipe();
+        let fd = receiver.as_raw_fd();
+        selector.register(fd, TOKEN, Interest::READABLE).unwrap();
+        // Epoll doesn't need to remove registrations of dropped sources.
+        assert!(Registration::new(&selector, fd).is_none());
+
+        let mut events = Vec::with_capacity(8);
+        sender.write_all(b"hello").unwrap();
+        expect_event(&selector, &mut events);
+        expect_no_events(&selector, &mut events);
+    }
+
+    #[test]
+    fn edge_triggered_multishot() {
+        let selector = match Ring::new() {
+            Ok(ring) => Selector::with_ring(Ok(ring)).unwrap(),
+            // Kernel doesn't support io_uring.
+            Err(_) => return,
+        };
+
+        let (receiver, mut sender) = pipe();
+        selector
+            .register(receiver.as_raw_fd(), TOKEN, Interest::READABLE)
+            .unwrap();
+        let id = registration_id(&selector, &receiver);
+
+        let mut events = Vec::with_capacity(8);
+        sender.write_all(b"hello").unwrap();
+        expect_event(&selector, &mut events);
+        expect_no_events(&selector, &mut events);
+
+        // The multishot request stays armed, posting a completion for the
+        // next write without being re-added.
+        sender.write_all(b"world").unwrap();
+        expect_event(&selector, &mut events);
+        assert_eq!(registration_id(&selector, &receiver), id);
+    }
+
+    #[test]
+    fn level_triggered_rearm() {
+        let selector = match Ring::new() {
+            Ok(ring) => Selector::with_ring(Ok(ring)).unwrap(),
+            // Kernel doesn't support io_uring.
+            Err(_) => return,
+        };
+
+        let (mut receiver, mut sender) = pipe();
+        let interests = Interest::READABLE.with_mode(Mode::Level);
+        selector
+            .register(receiver.as_raw_fd(), TOKEN, interests)
+            .unwrap();
+
+        // The poll request is re-added after every completion, which
+        // completes again as long as the pipe is readable.
+        let mut events = Vec::with_capacity(8);
+        sender.write_all(b"hello").unwrap();
+        let mut id = registration_id(&selector, &receiver);
+        for _ in 0..3 {
+            expect_event(&selector, &mut events);
+            let new_id = registration_id(&selector, &receiver);
+            assert_ne!(new_id, id);
+            id = new_id;
+        }
+
+        let mut buf = [0; 8];
+        assert_eq!(receiver.read(&mut buf).unwrap(), 5);
+        expect_no_events(&selector, &mut events);
+
+        // The re-added request is still armed.
+        sender.write_all(b"world").unwrap();
+        expect_event(&selector, &mut events);
+    }
+}
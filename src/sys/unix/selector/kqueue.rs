@@ -1,4 +1,4 @@
-use crate::{Interest, Token};
+use crate::{Interest, Mode, Token};
 use log::error;
 use std::mem::MaybeUninit;
 use std::ops::{Deref, DerefMut};
@@ -122,7 +122,14 @@ impl Selector {
     }
 
     pub fn register(&self, fd: RawFd, token: Token, interests: Interest) -> io::Result<()> {
-        let flags = libc::EV_CLEAR | libc::EV_RECEIPT | libc::EV_ADD;
+        if interests.is_exclusive() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "exclusive registrations are not supported on this platform",
+            ));
+        }
+
+        let flags = mode_to_flags(interests)? | libc::EV_RECEIPT | libc::EV_ADD;
         // At most we need two changes, but maybe we only need 1.
         let mut changes: [MaybeUninit<libc::kevent>; 2] =
             [MaybeUninit::uninit(), MaybeUninit::uninit()];
@@ -161,7 +168,7 @@ impl Selector {
     }
 
     pub fn reregister(&self, fd: RawFd, token: Token, interests: Interest) -> io::Result<()> {
-        let flags = libc::EV_CLEAR | libc::EV_RECEIPT;
+        let flags = mode_to_flags(interests)? | libc::EV_RECEIPT;
         let write_flags = if interests.is_writable() {
             flags | libc::EV_ADD
         } else {
@@ -277,6 +284,23 @@ fn kevent_register(
     .and_then(|()| check_errors(&changes, ignored_errors))
 }
 
+/// Returns the flags for the registration mode of `interests`.
+fn mode_to_flags(interests: Interest) -> io::Result<Flags> {
+    match interests.mode() {
+        Mode::Edge => Ok(libc::EV_CLEAR),
+        Mode::Level => Ok(0),
+        // `EV_ONESHOT` applies to a single filter, so with both interests the
+        // other filter would still return an event after the first one.
+        Mode::Oneshot if interests.is_readable() && interests.is_writable() => {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "oneshot registrations can't be both readable and writable on this platform",
+            ))
+        }
+        Mode::Oneshot => Ok(libc::EV_ONESHOT),
+    }
+}
+
 /// Check all events for possible errors, it returns the first error found.
 fn check_errors(events: &[libc::kevent], ignored_errors: &[Data]) -> io::Result<()> {
     for event in events {
@@ -292,7 +316,7 @@ fn check_errors(events: &[libc::kevent], ignored_errors: &[Data]) -> io::Result<
     Ok(())
 }
 
-cfg_net! {
+cfg_io_source! {
     #[cfg(debug_assertions)]
     impl Selector {
         pub fn id(&self) -> usize {
@@ -432,6 +456,14 @@ pub mod event {
         }
     }
 
+    cfg_time! {
+        /// Create a readable event for an expired `TimerWheel` timeout.
+        pub fn timeout(token: Token) -> Event {
+            use super::UData;
+            kevent!(0, libc::EVFILT_READ, 0, token.0)
+        }
+    }
+
     pub fn debug_details(f: &mut fmt::Formatter<'_>, event: &Event) -> fmt::Result {
         debug_detail!(
             FilterDetails(Filter),
@@ -1,9 +1,23 @@
 #[cfg(any(target_os = "linux", target_os = "android", target_os = "solaris"))]
 mod epoll;
 
-#[cfg(any(target_os = "linux", target_os = "android", target_os = "solaris"))]
+#[cfg(all(
+    any(target_os = "linux", target_os = "android", target_os = "solaris"),
+    not(all(target_os = "linux", feature = "io-uring"))
+))]
 pub(crate) use self::epoll::{event, Event, Events, Selector};
 
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+mod io_uring;
+
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+pub(crate) use self::io_uring::{event, Event, Events, Registration, Selector};
+
+cfg_io_source! {
+    #[cfg(all(target_os = "linux", feature = "io-uring"))]
+    pub(crate) use self::io_uring::IoSourceState;
+}
+
 #[cfg(any(
     target_os = "dragonfly",
     target_os = "freebsd",
@@ -0,0 +1,585 @@
+use crate::io_source::IoSource;
+use crate::{event, Interest, Registry, Token};
+
+use std::fmt;
+use std::io;
+use std::os::unix::io::{AsRawFd, RawFd};
+
+/// Information about a signal received by [`Signals`].
+///
+/// [`Signals`]: struct.Signals.html
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SignalInfo {
+    signal: libc::c_int,
+    pid: u32,
+    uid: u32,
+}
+
+impl SignalInfo {
+    /// The number of the received signal, e.g. `libc::SIGINT`.
+    pub fn signal(&self) -> i32 {
+        self.signal
+    }
+
+    /// The process id of the sender of the signal.
+    ///
+    /// This is `0` if the signal was not sent by a process, e.g. when it was
+    /// sent by the kernel.
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// The real user id of the sender of the signal.
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+}
+
+/// Signal handling as an event source, which can be registered with [`Poll`].
+///
+/// Once one of the signals is received an event with [`readable`] readiness is
+/// returned. All received signals must then be retrieved using [`receive`],
+/// until it returns a [`WouldBlock`] error.
+///
+/// [`Poll`]: ../struct.Poll.html
+/// [`readable`]: ../event/struct.Event.html#method.is_readable
+/// [`receive`]: #method.receive
+/// [`WouldBlock`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.WouldBlock
+///
+/// # Notes
+///
+/// Signals are a process-wide resource and don't map nicely onto event
+/// sources, please read the implementation notes below.
+///
+/// # Implementation notes
+///
+/// On Linux and Android this uses [signalfd]. `signalfd` only receives signals
+/// that are blocked, so adding a signal blocks it for the calling thread.
+/// Other threads must block the signal as well, otherwise the signal can be
+/// delivered to them (using its default disposition). This is most easily
+/// achieved by creating `Signals` on the main thread before spawning any
+/// threads, as threads inherit the signal mask of their parent. Once the last
+/// `Signals` handling a signal removes it, or is dropped, the signal is
+/// unblocked again for the calling thread, unless it was already blocked
+/// before it was first added.
+///
+/// On other platforms a signal handler is installed which writes the signal
+/// into a pipe (the so called "self-pipe trick"). Only a single `Signals`
+/// can handle a signal at a time and only the standard signals (below 32) are
+/// supported. Removing a signal, or dropping `Signals`, restores the previous
+/// signal handler.
+///
+/// [signalfd]: http://man7.org/linux/man-pages/man2/signalfd.2.html
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::io;
+///
+/// use mio::unix::Signals;
+/// use mio::{Events, Interest, Poll, Token};
+///
+/// const SIGNALS: Token = Token(0);
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(8);
+///
+/// let mut signals = Signals::new(&[libc::SIGUSR1])?;
+/// poll.registry().register(&mut signals, SIGNALS, Interest::READABLE)?;
+///
+/// // Send ourselves a signal.
+/// unsafe { libc::raise(libc::SIGUSR1) };
+///
+/// poll.poll(&mut events, None)?;
+/// for event in events.iter() {
+///     if event.token() == SIGNALS {
+///         loop {
+///             match signals.receive() {
+///                 Ok(info) => assert_eq!(info.signal(), libc::SIGUSR1),
+///                 Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => break,
+///                 Err(err) => return Err(err.into()),
+///             }
+///         }
+///     }
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+pub struct Signals {
+    inner: IoSource<imp::Signals>,
+}
+
+impl Signals {
+    /// Create a new `Signals` handling `signals`.
+    pub fn new(signals: &[i32]) -> io::Result<Signals> {
+        let mut inner = imp::Signals::new()?;
+        for &signal in signals {
+            inner.add(signal)?;
+        }
+        Ok(Signals {
+            inner: IoSource::new(inner),
+        })
+    }
+
+    /// Start handling `signal`.
+    ///
+    /// Adding a signal that is already handled does nothing.
+    pub fn add(&mut self, signal: i32) -> io::Result<()> {
+        self.inner.add(signal)
+    }
+
+    /// Stop handling `signal`.
+    ///
+    /// Removing a signal that isn't handled does nothing.
+    pub fn remove(&mut self, signal: i32) -> io::Result<()> {
+        self.inner.remove(signal)
+    }
+
+    /// Receive a single signal.
+    ///
+    /// Returns a [`WouldBlock`] error if no more signals are pending.
+    ///
+    /// [`WouldBlock`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.WouldBlock
+    pub fn receive(&self) -> io::Result<SignalInfo> {
+        self.inner.do_io(|inner| inner.receive())
+    }
+}
+
+impl event::Source for Signals {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        self.inner.deregister(registry)
+    }
+}
+
+impl AsRawFd for Signals {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl fmt::Debug for Signals {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Signals")
+            .field("fd", &self.as_raw_fd())
+            .finish()
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod signalfd {
+    use super::SignalInfo;
+
+    use log::error;
+    use std::fs::File;
+    use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::{io, mem};
+
+    const FLAGS: libc::c_int = libc::SFD_CLOEXEC | libc::SFD_NONBLOCK;
+
+    /// Signals up to and including this number are supported.
+    const MAX_SIGNAL: usize = 64;
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const NOT_HANDLED: AtomicUsize = AtomicUsize::new(0);
+
+    /// State of each signal, indexed by signal number minus one. This is the
+    /// number of `Signals` handling the signal, shifted left by one, with the
+    /// lowest bit set if we blocked the signal and thus must unblock it once
+    /// the last `Signals` stops handling it.
+    static HANDLED: [AtomicUsize; MAX_SIGNAL] = [
+        NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED,
+        NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED,
+        NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED,
+        NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED,
+        NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED,
+        NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED,
+        NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED,
+        NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED,
+        NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED, NOT_HANDLED,
+        NOT_HANDLED,
+    ];
+
+    /// Bit in `HANDLED` indicating the signal must be unblocked.
+    const UNBLOCK: usize = 1;
+    /// A single `Signals` in `HANDLED`.
+    const ONE: usize = 2;
+
+    /// Signal handling backed by `signalfd`.
+    ///
+    /// `signalfd` only receives signals that are blocked, so signals are
+    /// blocked for the calling thread when they're added. They're unblocked
+    /// again when the last `Signals` handling them removes them, if they
+    /// weren't blocked before.
+    pub struct Signals {
+        fd: File,
+        mask: libc::sigset_t,
+    }
+
+    impl Signals {
+        pub fn new() -> io::Result<Signals> {
+            let mask = empty_set()?;
+            syscall!(signalfd(-1, &mask, FLAGS)).map(|fd| Signals {
+                fd: unsafe { File::from_raw_fd(fd) },
+                mask,
+            })
+        }
+
+        pub fn add(&mut self, signal: libc::c_int) -> io::Result<()> {
+            if self.contains(signal) {
+                return Ok(());
+            } else if signal <= 0 || signal as usize > MAX_SIGNAL {
+                return Err(io::Error::from_raw_os_error(libc::EINVAL));
+            }
+
+            let mut mask = self.mask;
+            syscall!(sigaddset(&mut mask, signal))?;
+            // Block the signal before updating the signalfd, otherwise it
+            // could be delivered to the (default) signal handler in between.
+            let was_blocked = set_blocked(signal, libc::SIG_BLOCK)?;
+            let state = &HANDLED[signal as usize - 1];
+            let mut current = state.load(Ordering::SeqCst);
+            loop {
+                let new = if current == 0 {
+                    // First `Signals` handling the signal, only unblock the
+                    // signal later if we blocked it.
+                    ONE | if was_blocked { 0 } else { UNBLOCK }
+                } else {
+                    current + ONE
+                };
+                match state.compare_exchange(current, new, Ordering::SeqCst, Ordering::SeqCst) {
+                    Ok(_) => break,
+                    Err(actual) => current = actual,
+                }
+            }
+
+            if let Err(err) = self.update(mask) {
+                release(signal);
+                return Err(err);
+            }
+            Ok(())
+        }
+
+        pub fn remove(&mut self, signal: libc::c_int) -> io::Result<()> {
+            if !self.contains(signal) {
+                return Ok(());
+            }
+
+            let mut mask = self.mask;
+            syscall!(sigdelset(&mut mask, signal))?;
+            self.update(mask)?;
+            release(signal);
+            Ok(())
+        }
+
+        pub fn receive(&self) -> io::Result<SignalInfo> {
+            let mut info: libc::signalfd_siginfo = unsafe { mem::zeroed() };
+            let size = mem::size_of::<libc::signalfd_siginfo>();
+            syscall!(read(
+                self.fd.as_raw_fd(),
+                &mut info as *mut _ as *mut libc::c_void,
+                size,
+            ))
+            .map(|n| {
+                // Reads always return complete `signalfd_siginfo` structures.
+                debug_assert_eq!(n as usize, size);
+                SignalInfo {
+                    signal: info.ssi_signo as libc::c_int,
+                    pid: info.ssi_pid,
+                    uid: info.ssi_uid,
+                }
+            })
+        }
+
+        fn contains(&self, signal: libc::c_int) -> bool {
+            unsafe { libc::sigismember(&self.mask, signal) == 1 }
+        }
+
+        fn update(&mut self, mask: libc::sigset_t) -> io::Result<()> {
+            syscall!(signalfd(self.fd.as_raw_fd(), &mask, FLAGS)).map(|_| self.mask = mask)
+        }
+    }
+
+    impl AsRawFd for Signals {
+        fn as_raw_fd(&self) -> RawFd {
+            self.fd.as_raw_fd()
+        }
+    }
+
+    impl Drop for Signals {
+        fn drop(&mut self) {
+            for signal in 1..=MAX_SIGNAL as libc::c_int {
+                if self.contains(signal) {
+                    release(signal);
+                }
+            }
+        }
+    }
+
+    /// Stop handling `signal` for a single `Signals`, unblocking the signal if
+    /// it was the last one and we blocked it.
+    fn release(signal: libc::c_int) {
+        let state = &HANDLED[signal as usize - 1];
+        let mut current = state.load(Ordering::SeqCst);
+        loop {
+            // The last `Signals` also clears the `UNBLOCK` bit.
+            let new = if current & !UNBLOCK == ONE { 0 } else { current - ONE };
+            match state.compare_exchange(current, new, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => break,
+                Err(actual) => current = actual,
+            }
+        }
+
+        if current == ONE | UNBLOCK {
+            if let Err(err) = set_blocked(signal, libc::SIG_UNBLOCK) {
+                error!("error unblocking signal: {}", err);
+            }
+        }
+    }
+
+    fn empty_set() -> io::Result<libc::sigset_t> {
+        let mut set: libc::sigset_t = unsafe { mem::zeroed() };
+        syscall!(sigemptyset(&mut set)).map(|_| set)
+    }
+
+    /// Block or unblock (depending on `how`) `signal` for the calling thread.
+    ///
+    /// Returns whether or not the signal was blocked before.
+    fn set_blocked(signal: libc::c_int, how: libc::c_int) -> io::Result<bool> {
+        let mut set = empty_set()?;
+        syscall!(sigaddset(&mut set, signal))?;
+        let mut previous = empty_set()?;
+        // `pthread_sigmask` returns the error, rather than setting `errno`.
+        match unsafe { libc::pthread_sigmask(how, &set, &mut previous) } {
+            0 => Ok(unsafe { libc::sigismember(&previous, signal) == 1 }),
+            err => Err(io::Error::from_raw_os_error(err)),
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use self::signalfd as imp;
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+mod pipe {
+    use super::SignalInfo;
+
+    use log::error;
+    use std::fs::File;
+    use std::io::{self, Read};
+    use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+    use std::sync::atomic::{AtomicI32, Ordering};
+    use std::{mem, ptr};
+
+    /// Signals up to (but not including) this number are supported.
+    const MAX_SIGNAL: usize = 32;
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const NO_SENDER: AtomicI32 = AtomicI32::new(-1);
+
+    /// The sending end of the pipe of the `Signals` handling each signal, or
+    /// `-1` if the signal isn't handled.
+    static SENDERS: [AtomicI32; MAX_SIGNAL] = [
+        NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER,
+        NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER,
+        NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER,
+        NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER, NO_SENDER,
+    ];
+
+    /// Size of a record written to the pipe by the signal handler: the signal
+    /// number, pid and uid, each as 32 bit integer. This is smaller than
+    /// `PIPE_BUF`, so writes are atomic.
+    const RECORD_SIZE: usize = 12;
+
+    /// Signal handling backed by a signal handler writing into a unix pipe.
+    pub struct Signals {
+        sender: File,
+        receiver: File,
+        /// Handled signals and their previous signal handler.
+        handlers: Vec<(libc::c_int, libc::sigaction)>,
+    }
+
+    impl Signals {
+        pub fn new() -> io::Result<Signals> {
+            let mut fds = [-1; 2];
+            syscall!(pipe(fds.as_mut_ptr()))?;
+            // Turn the file descriptors into files first so we're ensured
+            // they're closed when dropped, e.g. when `fcntl` below fails.
+            let receiver = unsafe { File::from_raw_fd(fds[0]) };
+            let sender = unsafe { File::from_raw_fd(fds[1]) };
+            for &fd in &fds {
+                syscall!(fcntl(fd, libc::F_SETFL, libc::O_NONBLOCK))?;
+                syscall!(fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC))?;
+            }
+            Ok(Signals {
+                sender,
+                receiver,
+                handlers: Vec::new(),
+            })
+        }
+
+        pub fn add(&mut self, signal: libc::c_int) -> io::Result<()> {
+            if signal <= 0 || signal as usize >= MAX_SIGNAL {
+                return Err(io::Error::from_raw_os_error(libc::EINVAL));
+            } else if self.handlers.iter().any(|&(s, _)| s == signal) {
+                return Ok(());
+            }
+
+            let sender = &SENDERS[signal as usize];
+            let fd = self.sender.as_raw_fd();
+            if sender
+                .compare_exchange(-1, fd, Ordering::SeqCst, Ordering::SeqCst)
+                .is_err()
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    "signal already handled by another `Signals`",
+                ));
+            }
+
+            let mut action: libc::sigaction = unsafe { mem::zeroed() };
+            action.sa_sigaction = handler as libc::sighandler_t;
+            action.sa_flags = libc::SA_SIGINFO | libc::SA_RESTART;
+            let mut previous: libc::sigaction = unsafe { mem::zeroed() };
+            match syscall!(sigemptyset(&mut action.sa_mask))
+                .and_then(|_| syscall!(sigaction(signal, &action, &mut previous)))
+            {
+                Ok(_) => {
+                    self.handlers.push((signal, previous));
+                    Ok(())
+                }
+                Err(err) => {
+                    sender.store(-1, Ordering::SeqCst);
+                    Err(err)
+                }
+            }
+        }
+
+        pub fn remove(&mut self, signal: libc::c_int) -> io::Result<()> {
+            let index = match self.handlers.iter().position(|&(s, _)| s == signal) {
+                Some(index) => index,
+                None => return Ok(()),
+            };
+
+            // Restore the previous handler before removing the sender, so that
+            // the handler never writes to a closed file descriptor.
+            syscall!(sigaction(signal, &self.handlers[index].1, ptr::null_mut()))?;
+            SENDERS[signal as usize].store(-1, Ordering::SeqCst);
+            let _ = self.handlers.swap_remove(index);
+            Ok(())
+        }
+
+        pub fn receive(&self) -> io::Result<SignalInfo> {
+            let mut record = [0; RECORD_SIZE];
+            (&self.receiver).read(&mut record).map(|n| {
+                // The signal handler only writes complete records.
+                debug_assert_eq!(n, RECORD_SIZE);
+                SignalInfo {
+                    signal: read_u32(&record[0..4]) as libc::c_int,
+                    pid: read_u32(&record[4..8]),
+                    uid: read_u32(&record[8..12]),
+                }
+            })
+        }
+    }
+
+    impl AsRawFd for Signals {
+        fn as_raw_fd(&self) -> RawFd {
+            self.receiver.as_raw_fd()
+        }
+    }
+
+    impl Drop for Signals {
+        fn drop(&mut self) {
+            while let Some(&(signal, _)) = self.handlers.last() {
+                if let Err(err) = self.remove(signal) {
+                    error!("error restoring signal handler: {}", err);
+                    let _ = self.handlers.pop();
+                }
+            }
+        }
+    }
+
+    /// The signal handler, writes a record of the signal into the pipe of the
+    /// `Signals` handling it.
+    ///
+    /// # Notes
+    ///
+    /// Only async-signal-safe functions may be called in here.
+    extern "C" fn handler(signal: libc::c_int, info: *mut libc::siginfo_t, _: *mut libc::c_void) {
+        let fd = match SENDERS.get(signal as usize) {
+            Some(sender) => sender.load(Ordering::SeqCst),
+            None => return,
+        };
+        if fd == -1 {
+            return;
+        }
+
+        let (pid, uid) = if info.is_null() {
+            (0, 0)
+        } else {
+            unsafe { ((*info).si_pid() as u32, (*info).si_uid() as u32) }
+        };
+        let mut record = [0; RECORD_SIZE];
+        record[0..4].copy_from_slice(&(signal as u32).to_ne_bytes());
+        record[4..8].copy_from_slice(&pid.to_ne_bytes());
+        record[8..12].copy_from_slice(&uid.to_ne_bytes());
+
+        // Don't overwrite `errno` for the interrupted code. If the pipe is
+        // full the write fails, but the pipe is readable so the signals will
+        // still be noticed.
+        unsafe {
+            let errno = *errno_location();
+            let _ = libc::write(fd, record.as_ptr() as *const libc::c_void, RECORD_SIZE);
+            *errno_location() = errno;
+        }
+    }
+
+    fn read_u32(bytes: &[u8]) -> u32 {
+        let mut buf = [0; 4];
+        buf.copy_from_slice(bytes);
+        u32::from_ne_bytes(buf)
+    }
+
+    #[cfg(any(target_os = "freebsd", target_os = "ios", target_os = "macos"))]
+    unsafe fn errno_location() -> *mut libc::c_int {
+        libc::__error()
+    }
+
+    #[cfg(target_os = "dragonfly")]
+    unsafe fn errno_location() -> *mut libc::c_int {
+        libc::__errno_location()
+    }
+
+    #[cfg(any(target_os = "netbsd", target_os = "openbsd"))]
+    unsafe fn errno_location() -> *mut libc::c_int {
+        libc::__errno()
+    }
+
+    #[cfg(target_os = "solaris")]
+    unsafe fn errno_location() -> *mut libc::c_int {
+        libc::___errno()
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+use self::pipe as imp;
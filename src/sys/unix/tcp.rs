@@ -1,56 +1,334 @@
+use std::convert::TryInto;
 use std::io;
 use std::mem::{size_of, MaybeUninit};
 use std::net::{self, SocketAddr};
 use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::time::Duration;
 
-use crate::sys::unix::net::{new_ip_socket, socket_addr, to_socket_addr};
-
-pub fn connect(addr: SocketAddr) -> io::Result<net::TcpStream> {
-    new_ip_socket(addr, libc::SOCK_STREAM)
-        .and_then(|socket| {
-            let (raw_addr, raw_addr_length) = socket_addr(&addr);
-            syscall!(connect(socket, raw_addr, raw_addr_length))
-                .or_else(|err| match err {
-                    // Connect hasn't finished, but that is fine.
-                    ref err if err.raw_os_error() == Some(libc::EINPROGRESS) => Ok(0),
-                    err => Err(err),
-                })
-                .map(|_| socket)
-                .map_err(|err| {
-                    // Close the socket if we hit an error, ignoring the error
-                    // from closing since we can't pass back two errors.
-                    let _ = unsafe { libc::close(socket) };
-                    err
-                })
-        })
-        .map(|socket| unsafe { net::TcpStream::from_raw_fd(socket) })
+use crate::sys::unix::net::{getsockopt, new_socket, setsockopt, socket_addr, to_socket_addr};
+
+pub(crate) use crate::sys::unix::net::{
+    recv_buffer_size, recv_with_flags, reuseaddr, send_buffer_size, send_with_flags,
+    set_recv_buffer_size, set_reuseaddr, set_send_buffer_size, set_tclass_v6, set_tos_v4,
+    tclass_v6, tos_v4,
+};
+#[cfg(not(target_os = "solaris"))]
+pub(crate) use crate::sys::unix::net::{reuseport, set_reuseport};
+
+pub type TcpSocket = libc::c_int;
+
+pub(crate) fn new_v4_socket() -> io::Result<TcpSocket> {
+    new_socket(libc::AF_INET, libc::SOCK_STREAM)
 }
 
-pub fn bind(addr: SocketAddr) -> io::Result<net::TcpListener> {
-    new_ip_socket(addr, libc::SOCK_STREAM).and_then(|socket| {
-        // Set SO_REUSEADDR (mirrors what libstd does).
-        syscall!(setsockopt(
-            socket,
-            libc::SOL_SOCKET,
-            libc::SO_REUSEADDR,
-            &1 as *const libc::c_int as *const libc::c_void,
-            size_of::<libc::c_int>() as libc::socklen_t,
-        ))
-        .and_then(|_| {
-            let (raw_addr, raw_addr_length) = socket_addr(&addr);
-            syscall!(bind(socket, raw_addr, raw_addr_length))
-        })
-        .and_then(|_| syscall!(listen(socket, 1024)))
-        .map_err(|err| {
-            // Close the socket if we hit an error, ignoring the error
-            // from closing since we can't pass back two errors.
-            let _ = unsafe { libc::close(socket) };
-            err
+pub(crate) fn new_v6_socket() -> io::Result<TcpSocket> {
+    new_socket(libc::AF_INET6, libc::SOCK_STREAM)
+}
+
+pub(crate) fn bind(socket: TcpSocket, addr: SocketAddr) -> io::Result<()> {
+    let (raw_addr, raw_addr_length) = socket_addr(&addr);
+    syscall!(bind(socket, raw_addr.as_ptr(), raw_addr_length))?;
+    Ok(())
+}
+
+pub(crate) fn connect(socket: TcpSocket, addr: SocketAddr) -> io::Result<net::TcpStream> {
+    let (raw_addr, raw_addr_length) = socket_addr(&addr);
+    match syscall!(connect(socket, raw_addr.as_ptr(), raw_addr_length)) {
+        // Connect hasn't finished, but that is fine.
+        Err(err) if err.raw_os_error() != Some(libc::EINPROGRESS) => Err(err),
+        _ => Ok(unsafe { net::TcpStream::from_raw_fd(socket) }),
+    }
+}
+
+pub(crate) fn listen(socket: TcpSocket, backlog: u32) -> io::Result<net::TcpListener> {
+    let backlog = backlog.try_into().unwrap_or(libc::c_int::MAX);
+    syscall!(listen(socket, backlog))?;
+    Ok(unsafe { net::TcpListener::from_raw_fd(socket) })
+}
+
+pub(crate) fn close(socket: TcpSocket) {
+    let _ = unsafe { net::TcpStream::from_raw_fd(socket) };
+}
+
+// On Apple platforms `SO_LINGER` uses ticks rather than seconds.
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+const SO_LINGER: libc::c_int = libc::SO_LINGER_SEC;
+#[cfg(not(any(target_os = "ios", target_os = "macos")))]
+const SO_LINGER: libc::c_int = libc::SO_LINGER;
+
+pub(crate) fn set_linger(socket: TcpSocket, dur: Option<Duration>) -> io::Result<()> {
+    let val = libc::linger {
+        l_onoff: dur.is_some() as libc::c_int,
+        l_linger: dur
+            .map(|dur| dur.as_secs() as libc::c_int)
+            .unwrap_or_default(),
+    };
+    setsockopt(socket, libc::SOL_SOCKET, SO_LINGER, val)
+}
+
+pub(crate) fn linger(socket: TcpSocket) -> io::Result<Option<Duration>> {
+    let val: libc::linger = getsockopt(socket, libc::SOL_SOCKET, SO_LINGER)?;
+    if val.l_onoff == 0 {
+        Ok(None)
+    } else {
+        Ok(Some(Duration::from_secs(val.l_linger as u64)))
+    }
+}
+
+pub(crate) fn set_keepalive(socket: TcpSocket, keepalive: bool) -> io::Result<()> {
+    setsockopt(
+        socket,
+        libc::SOL_SOCKET,
+        libc::SO_KEEPALIVE,
+        keepalive as libc::c_int,
+    )
+}
+
+pub(crate) fn keepalive(socket: TcpSocket) -> io::Result<bool> {
+    getsockopt::<libc::c_int>(socket, libc::SOL_SOCKET, libc::SO_KEEPALIVE).map(|on| on != 0)
+}
+
+// Apple platforms name the option to set the keepalive idle time
+// `TCP_KEEPALIVE`.
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+const KEEPALIVE_IDLE: libc::c_int = libc::TCP_KEEPALIVE;
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "linux",
+    target_os = "netbsd"
+))]
+const KEEPALIVE_IDLE: libc::c_int = libc::TCP_KEEPIDLE;
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd"
+))]
+pub(crate) fn set_keepalive_idle(socket: TcpSocket, idle: Duration) -> io::Result<()> {
+    let secs = idle
+        .as_secs()
+        .try_into()
+        .unwrap_or(libc::c_int::MAX);
+    setsockopt(socket, libc::IPPROTO_TCP, KEEPALIVE_IDLE, secs)
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd"
+))]
+pub(crate) fn keepalive_idle(socket: TcpSocket) -> io::Result<Duration> {
+    getsockopt::<libc::c_int>(socket, libc::IPPROTO_TCP, KEEPALIVE_IDLE)
+        .map(|secs| Duration::from_secs(secs as u64))
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd"
+))]
+pub(crate) fn set_keepalive_interval(socket: TcpSocket, interval: Duration) -> io::Result<()> {
+    let secs = interval
+        .as_secs()
+        .try_into()
+        .unwrap_or(libc::c_int::MAX);
+    setsockopt(socket, libc::IPPROTO_TCP, libc::TCP_KEEPINTVL, secs)
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd"
+))]
+pub(crate) fn keepalive_interval(socket: TcpSocket) -> io::Result<Duration> {
+    getsockopt::<libc::c_int>(socket, libc::IPPROTO_TCP, libc::TCP_KEEPINTVL)
+        .map(|secs| Duration::from_secs(secs as u64))
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd"
+))]
+pub(crate) fn set_keepalive_count(socket: TcpSocket, count: u32) -> io::Result<()> {
+    let count = count.try_into().unwrap_or(libc::c_int::MAX);
+    setsockopt(socket, libc::IPPROTO_TCP, libc::TCP_KEEPCNT, count)
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd"
+))]
+pub(crate) fn keepalive_count(socket: TcpSocket) -> io::Result<u32> {
+    getsockopt::<libc::c_int>(socket, libc::IPPROTO_TCP, libc::TCP_KEEPCNT)
+        .map(|count| count as u32)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn set_user_timeout(socket: TcpSocket, timeout: Option<Duration>) -> io::Result<()> {
+    // Zero means the system default is used.
+    let millis = timeout
+        .map(|timeout| {
+            timeout
+                .as_millis()
+                .try_into()
+                .unwrap_or(libc::c_uint::MAX)
         })
-        .map(|_| unsafe { net::TcpListener::from_raw_fd(socket) })
+        .unwrap_or(0);
+    setsockopt(socket, libc::IPPROTO_TCP, libc::TCP_USER_TIMEOUT, millis)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn user_timeout(socket: TcpSocket) -> io::Result<Option<Duration>> {
+    getsockopt::<libc::c_uint>(socket, libc::IPPROTO_TCP, libc::TCP_USER_TIMEOUT).map(|millis| {
+        if millis == 0 {
+            None
+        } else {
+            Some(Duration::from_millis(millis.into()))
+        }
     })
 }
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn set_quickack(socket: TcpSocket, quickack: bool) -> io::Result<()> {
+    setsockopt(
+        socket,
+        libc::IPPROTO_TCP,
+        libc::TCP_QUICKACK,
+        quickack as libc::c_int,
+    )
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn quickack(socket: TcpSocket) -> io::Result<bool> {
+    getsockopt::<libc::c_int>(socket, libc::IPPROTO_TCP, libc::TCP_QUICKACK).map(|on| on != 0)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn set_cork(socket: TcpSocket, cork: bool) -> io::Result<()> {
+    setsockopt(
+        socket,
+        libc::IPPROTO_TCP,
+        libc::TCP_CORK,
+        cork as libc::c_int,
+    )
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn cork(socket: TcpSocket) -> io::Result<bool> {
+    getsockopt::<libc::c_int>(socket, libc::IPPROTO_TCP, libc::TCP_CORK).map(|on| on != 0)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn set_notsent_lowat(socket: TcpSocket, lowat: u32) -> io::Result<()> {
+    let lowat = lowat.try_into().unwrap_or(libc::c_int::MAX);
+    setsockopt(socket, libc::IPPROTO_TCP, libc::TCP_NOTSENT_LOWAT, lowat)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn notsent_lowat(socket: TcpSocket) -> io::Result<u32> {
+    getsockopt::<libc::c_int>(socket, libc::IPPROTO_TCP, libc::TCP_NOTSENT_LOWAT)
+        .map(|lowat| lowat as u32)
+}
+
+/// Maximum length of a congestion control algorithm name, including the
+/// null byte (`TCP_CA_NAME_MAX`).
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const CA_NAME_MAX: usize = 16;
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn set_congestion(socket: TcpSocket, algorithm: &str) -> io::Result<()> {
+    syscall!(setsockopt(
+        socket,
+        libc::IPPROTO_TCP,
+        libc::TCP_CONGESTION,
+        algorithm.as_ptr() as *const libc::c_void,
+        algorithm.len() as libc::socklen_t,
+    ))
+    .map(|_| ())
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn congestion(socket: TcpSocket) -> io::Result<String> {
+    let mut name = [0u8; CA_NAME_MAX];
+    let mut len = name.len() as libc::socklen_t;
+    syscall!(getsockopt(
+        socket,
+        libc::IPPROTO_TCP,
+        libc::TCP_CONGESTION,
+        name.as_mut_ptr() as *mut libc::c_void,
+        &mut len,
+    ))?;
+    let name = &name[..len as usize];
+    // The name is null terminated if it's shorter than the buffer.
+    let name = match name.iter().position(|b| *b == 0) {
+        Some(end) => &name[..end],
+        None => name,
+    };
+    String::from_utf8(name.to_vec()).map_err(|_| io::ErrorKind::InvalidData.into())
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn tcp_info(socket: TcpSocket) -> io::Result<crate::sys::TcpInfo> {
+    getsockopt(socket, libc::IPPROTO_TCP, libc::TCP_INFO)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn bind_device(socket: TcpSocket, interface: Option<&[u8]>) -> io::Result<()> {
+    let (value, len) = match interface {
+        Some(interface) => (interface.as_ptr(), interface.len()),
+        // An empty name removes the binding.
+        None => (std::ptr::null(), 0),
+    };
+    syscall!(setsockopt(
+        socket,
+        libc::SOL_SOCKET,
+        libc::SO_BINDTODEVICE,
+        value as *const libc::c_void,
+        len as libc::socklen_t,
+    ))
+    .map(|_| ())
+}
+
+pub(crate) fn local_addr(socket: TcpSocket) -> io::Result<SocketAddr> {
+    let mut addr: MaybeUninit<libc::sockaddr_storage> = MaybeUninit::uninit();
+    let mut length = size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+    syscall!(getsockname(
+        socket,
+        addr.as_mut_ptr() as *mut _,
+        &mut length
+    ))?;
+    // This is safe because `getsockname` initialised the address.
+    unsafe { to_socket_addr(addr.as_ptr()) }
+}
+
 pub fn accept(listener: &net::TcpListener) -> io::Result<(net::TcpStream, SocketAddr)> {
     let mut addr: MaybeUninit<libc::sockaddr_storage> = MaybeUninit::uninit();
     let mut length = size_of::<libc::sockaddr_storage>() as libc::socklen_t;
@@ -0,0 +1,216 @@
+use std::time::Duration;
+
+/// TCP connection statistics, as returned by the `TCP_INFO` socket option.
+///
+/// Returned by [`TcpStream::tcp_info`]. Only the fields that have been part
+/// of the kernel's `struct tcp_info` since Linux 2.6 are exposed.
+///
+/// [`TcpStream::tcp_info`]: struct.TcpStream.html#method.tcp_info
+//
+// The layout must match the start of `struct tcp_info` in
+// `include/uapi/linux/tcp.h`, the kernel only copies the part we ask for.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct TcpInfo {
+    tcpi_state: u8,
+    tcpi_ca_state: u8,
+    tcpi_retransmits: u8,
+    tcpi_probes: u8,
+    tcpi_backoff: u8,
+    tcpi_options: u8,
+    tcpi_snd_rcv_wscale: u8,
+    // Bitfields added in later kernel versions.
+    _tcpi_flags: u8,
+
+    tcpi_rto: u32,
+    tcpi_ato: u32,
+    tcpi_snd_mss: u32,
+    tcpi_rcv_mss: u32,
+
+    tcpi_unacked: u32,
+    tcpi_sacked: u32,
+    tcpi_lost: u32,
+    tcpi_retrans: u32,
+    tcpi_fackets: u32,
+
+    tcpi_last_data_sent: u32,
+    // Not tracked by the kernel.
+    _tcpi_last_ack_sent: u32,
+    tcpi_last_data_recv: u32,
+    tcpi_last_ack_recv: u32,
+
+    tcpi_pmtu: u32,
+    tcpi_rcv_ssthresh: u32,
+    tcpi_rtt: u32,
+    tcpi_rttvar: u32,
+    tcpi_snd_ssthresh: u32,
+    tcpi_snd_cwnd: u32,
+    tcpi_advmss: u32,
+    tcpi_reordering: u32,
+
+    tcpi_rcv_rtt: u32,
+    tcpi_rcv_space: u32,
+
+    tcpi_total_retrans: u32,
+}
+
+impl TcpInfo {
+    /// The state of the connection, one of the `TCP_*` states, e.g.
+    /// `TCP_ESTABLISHED` (1).
+    pub fn state(&self) -> u8 {
+        self.tcpi_state
+    }
+
+    /// The state of the congestion control state machine, one of the
+    /// `TCP_CA_*` states.
+    pub fn ca_state(&self) -> u8 {
+        self.tcpi_ca_state
+    }
+
+    /// Number of unrecovered retransmission timeouts.
+    pub fn retransmits(&self) -> u8 {
+        self.tcpi_retransmits
+    }
+
+    /// Number of unanswered zero window or keepalive probes.
+    pub fn probes(&self) -> u8 {
+        self.tcpi_probes
+    }
+
+    /// Exponential backoff of the retransmission timer.
+    pub fn backoff(&self) -> u8 {
+        self.tcpi_backoff
+    }
+
+    /// The options negotiated for the connection, a combination of the
+    /// `TCPI_OPT_*` flags.
+    pub fn options(&self) -> u8 {
+        self.tcpi_options
+    }
+
+    /// The window scale used for sending.
+    pub fn snd_wscale(&self) -> u8 {
+        self.tcpi_snd_rcv_wscale & 0x0f
+    }
+
+    /// The window scale used for receiving.
+    pub fn rcv_wscale(&self) -> u8 {
+        self.tcpi_snd_rcv_wscale >> 4
+    }
+
+    /// Retransmission timeout.
+    pub fn rto(&self) -> Duration {
+        Duration::from_micros(self.tcpi_rto.into())
+    }
+
+    /// Delayed acknowledgement timeout.
+    pub fn ato(&self) -> Duration {
+        Duration::from_micros(self.tcpi_ato.into())
+    }
+
+    /// Maximum segment size for sending.
+    pub fn snd_mss(&self) -> u32 {
+        self.tcpi_snd_mss
+    }
+
+    /// Maximum segment size for receiving.
+    pub fn rcv_mss(&self) -> u32 {
+        self.tcpi_rcv_mss
+    }
+
+    /// Number of sent but unacknowledged segments.
+    pub fn unacked(&self) -> u32 {
+        self.tcpi_unacked
+    }
+
+    /// Number of selectively acknowledged segments.
+    pub fn sacked(&self) -> u32 {
+        self.tcpi_sacked
+    }
+
+    /// Number of segments considered lost.
+    pub fn lost(&self) -> u32 {
+        self.tcpi_lost
+    }
+
+    /// Number of segments currently being retransmitted.
+    pub fn retrans(&self) -> u32 {
+        self.tcpi_retrans
+    }
+
+    /// Number of forward acknowledged segments.
+    pub fn fackets(&self) -> u32 {
+        self.tcpi_fackets
+    }
+
+    /// Time since data was last sent.
+    pub fn last_data_sent(&self) -> Duration {
+        Duration::from_millis(self.tcpi_last_data_sent.into())
+    }
+
+    /// Time since data was last received.
+    pub fn last_data_recv(&self) -> Duration {
+        Duration::from_millis(self.tcpi_last_data_recv.into())
+    }
+
+    /// Time since an acknowledgement was last received.
+    pub fn last_ack_recv(&self) -> Duration {
+        Duration::from_millis(self.tcpi_last_ack_recv.into())
+    }
+
+    /// Path MTU.
+    pub fn pmtu(&self) -> u32 {
+        self.tcpi_pmtu
+    }
+
+    /// Slow start threshold for receiving.
+    pub fn rcv_ssthresh(&self) -> u32 {
+        self.tcpi_rcv_ssthresh
+    }
+
+    /// Smoothed round trip time.
+    pub fn rtt(&self) -> Duration {
+        Duration::from_micros(self.tcpi_rtt.into())
+    }
+
+    /// Round trip time variance.
+    pub fn rtt_var(&self) -> Duration {
+        Duration::from_micros(self.tcpi_rttvar.into())
+    }
+
+    /// Slow start threshold for sending.
+    pub fn snd_ssthresh(&self) -> u32 {
+        self.tcpi_snd_ssthresh
+    }
+
+    /// Congestion window, in segments.
+    pub fn snd_cwnd(&self) -> u32 {
+        self.tcpi_snd_cwnd
+    }
+
+    /// Advertised maximum segment size.
+    pub fn advmss(&self) -> u32 {
+        self.tcpi_advmss
+    }
+
+    /// Amount of reordering tolerated before assuming a segment is lost.
+    pub fn reordering(&self) -> u32 {
+        self.tcpi_reordering
+    }
+
+    /// Estimated round trip time as seen by the receiver.
+    pub fn rcv_rtt(&self) -> Duration {
+        Duration::from_micros(self.tcpi_rcv_rtt.into())
+    }
+
+    /// Advertised receive window space.
+    pub fn rcv_space(&self) -> u32 {
+        self.tcpi_rcv_space
+    }
+
+    /// Total number of retransmitted segments over the lifetime of the
+    /// connection.
+    pub fn total_retrans(&self) -> u32 {
+        self.tcpi_total_retrans
+    }
+}
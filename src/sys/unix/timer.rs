@@ -0,0 +1,104 @@
+use crate::time::Clock;
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::mem::MaybeUninit;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::time::Duration;
+
+/// Timer backed by `timerfd`.
+///
+/// A `timerfd` becomes readable once the timer expires, reading from it
+/// returns the number of expirations (as a native endian 64 bit unsigned
+/// integer) since the last read or (re)arming of the timer.
+#[derive(Debug)]
+pub struct Timer {
+    fd: File,
+}
+
+impl Timer {
+    pub fn new(clock: Clock) -> io::Result<Timer> {
+        syscall!(timerfd_create(
+            clock_id(clock),
+            libc::TFD_CLOEXEC | libc::TFD_NONBLOCK
+        ))
+        .map(|fd| Timer {
+            fd: unsafe { File::from_raw_fd(fd) },
+        })
+    }
+
+    /// Arm (or disarm if `value` is zero) the timer. If `absolute` is true
+    /// `value` is interpreted as an absolute value of the timer's clock.
+    pub fn set(&self, value: Duration, interval: Duration, absolute: bool) -> io::Result<()> {
+        let new_value = libc::itimerspec {
+            it_interval: to_timespec(interval),
+            it_value: to_timespec(value),
+        };
+        let flags = if absolute { libc::TFD_TIMER_ABSTIME } else { 0 };
+        syscall!(timerfd_settime(
+            self.fd.as_raw_fd(),
+            flags,
+            &new_value,
+            std::ptr::null_mut(),
+        ))
+        .map(|_| ())
+    }
+
+    /// Returns the time until the next expiration and the interval.
+    pub fn get(&self) -> io::Result<(Duration, Duration)> {
+        let mut curr_value = MaybeUninit::<libc::itimerspec>::uninit();
+        syscall!(timerfd_gettime(
+            self.fd.as_raw_fd(),
+            curr_value.as_mut_ptr()
+        ))?;
+        // This is safe because `timerfd_gettime` initialised the value.
+        let curr_value = unsafe { curr_value.assume_init() };
+        Ok((
+            from_timespec(curr_value.it_value),
+            from_timespec(curr_value.it_interval),
+        ))
+    }
+
+    pub fn read(&self) -> io::Result<u64> {
+        let mut buf: [u8; 8] = 0u64.to_ne_bytes();
+        (&self.fd).read_exact(&mut buf)?;
+        Ok(u64::from_ne_bytes(buf))
+    }
+}
+
+impl AsRawFd for Timer {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl IntoRawFd for Timer {
+    fn into_raw_fd(self) -> RawFd {
+        self.fd.into_raw_fd()
+    }
+}
+
+pub fn now(clock: Clock) -> io::Result<Duration> {
+    let mut tp = MaybeUninit::<libc::timespec>::uninit();
+    syscall!(clock_gettime(clock_id(clock), tp.as_mut_ptr()))?;
+    // This is safe because `clock_gettime` initialised the value.
+    Ok(from_timespec(unsafe { tp.assume_init() }))
+}
+
+fn clock_id(clock: Clock) -> libc::clockid_t {
+    match clock {
+        Clock::Monotonic => libc::CLOCK_MONOTONIC,
+        Clock::Realtime => libc::CLOCK_REALTIME,
+    }
+}
+
+fn to_timespec(duration: Duration) -> libc::timespec {
+    libc::timespec {
+        tv_sec: duration.as_secs() as libc::time_t,
+        tv_nsec: duration.subsec_nanos() as libc::c_long,
+    }
+}
+
+fn from_timespec(ts: libc::timespec) -> Duration {
+    Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
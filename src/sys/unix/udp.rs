@@ -1,36 +1,407 @@
-use crate::sys::unix::net::{new_ip_socket, socket_addr};
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use crate::sys::unix::net::SocketAddrCRepr;
+use crate::sys::unix::net::{getsockopt, new_socket, setsockopt, socket_addr, to_socket_addr};
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use crate::sys::{ControlMessageEncoder, SegmentSize};
+use crate::sys::{MsgFlags, RecvMsg};
 
-use std::io;
+pub(crate) use crate::sys::unix::net::{
+    recv_buffer_size, reuseaddr, send_buffer_size, set_recv_buffer_size, set_reuseaddr,
+    set_send_buffer_size, set_tclass_v6, set_tos_v4, tclass_v6, tos_v4,
+};
+#[cfg(not(target_os = "solaris"))]
+pub(crate) use crate::sys::unix::net::{reuseport, set_reuseport};
+
+use std::io::{self, IoSlice, IoSliceMut};
+use std::mem;
 use std::net::{self, SocketAddr};
-use std::os::unix::io::FromRawFd;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+
+pub(crate) type UdpSocketBuilder = libc::c_int;
+
+pub(crate) fn new_v4_socket() -> io::Result<UdpSocketBuilder> {
+    new_udp_socket(libc::AF_INET)
+}
 
-pub fn bind(addr: SocketAddr) -> io::Result<net::UdpSocket> {
+pub(crate) fn new_v6_socket() -> io::Result<UdpSocketBuilder> {
+    new_udp_socket(libc::AF_INET6)
+}
+
+fn new_udp_socket(domain: libc::c_int) -> io::Result<UdpSocketBuilder> {
     // Gives a warning for non Apple platforms.
     #[allow(clippy::let_and_return)]
-    let socket = new_ip_socket(addr, libc::SOCK_DGRAM);
+    let socket = new_socket(domain, libc::SOCK_DGRAM);
 
     // Set SO_NOSIGPIPE on iOS and macOS (mirrors what libstd does).
     #[cfg(any(target_os = "ios", target_os = "macos"))]
     let socket = socket.and_then(|socket| {
-        syscall!(setsockopt(
+        setsockopt(
             socket,
             libc::SOL_SOCKET,
             libc::SO_NOSIGPIPE,
-            &1 as *const libc::c_int as *const libc::c_void,
-            std::mem::size_of::<libc::c_int>() as libc::socklen_t,
-        ))
-        .map(|_| socket)
+            1 as libc::c_int,
+        )
+        .map(|()| socket)
+        .map_err(|err| {
+            // Close the socket if we hit an error, ignoring the error
+            // from closing since we can't pass back two errors.
+            let _ = unsafe { libc::close(socket) };
+            err
+        })
     });
 
-    socket.and_then(|socket| {
-        let (raw_addr, raw_addr_length) = socket_addr(&addr);
-        syscall!(bind(socket, raw_addr, raw_addr_length))
-            .map_err(|err| {
-                // Close the socket if we hit an error, ignoring the error
-                // from closing since we can't pass back two errors.
-                let _ = unsafe { libc::close(socket) };
-                err
-            })
-            .map(|_| unsafe { net::UdpSocket::from_raw_fd(socket) })
+    socket
+}
+
+pub(crate) fn bind(socket: UdpSocketBuilder, addr: SocketAddr) -> io::Result<net::UdpSocket> {
+    let (raw_addr, raw_addr_length) = socket_addr(&addr);
+    syscall!(bind(socket, raw_addr.as_ptr(), raw_addr_length))?;
+    Ok(unsafe { net::UdpSocket::from_raw_fd(socket) })
+}
+
+pub(crate) fn close(socket: UdpSocketBuilder) {
+    let _ = unsafe { net::UdpSocket::from_raw_fd(socket) };
+}
+
+pub(crate) fn set_only_v6(socket: UdpSocketBuilder, only_v6: bool) -> io::Result<()> {
+    setsockopt(
+        socket,
+        libc::IPPROTO_IPV6,
+        libc::IPV6_V6ONLY,
+        only_v6 as libc::c_int,
+    )
+}
+
+pub(crate) fn only_v6(socket: UdpSocketBuilder) -> io::Result<bool> {
+    getsockopt::<libc::c_int>(socket, libc::IPPROTO_IPV6, libc::IPV6_V6ONLY).map(|on| on != 0)
+}
+
+pub fn send_msg(
+    socket: &net::UdpSocket,
+    bufs: &[IoSlice<'_>],
+    target: Option<SocketAddr>,
+    control: &[u8],
+    flags: MsgFlags,
+) -> io::Result<usize> {
+    let raw_addr = target.map(|addr| socket_addr(&addr));
+
+    // Using `zeroed` so we don't have to deal with the platform specific
+    // padding fields.
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    if let Some((ref raw_addr, raw_addr_length)) = raw_addr {
+        msg.msg_name = raw_addr.as_ptr() as *mut _;
+        msg.msg_namelen = raw_addr_length;
+    }
+    // `IoSlice` is guaranteed to be ABI compatible with `iovec`.
+    msg.msg_iov = bufs.as_ptr() as *mut libc::iovec;
+    msg.msg_iovlen = bufs.len() as _;
+    if !control.is_empty() {
+        msg.msg_control = control.as_ptr() as *mut _;
+        msg.msg_controllen = control.len() as _;
+    }
+
+    syscall!(sendmsg(socket.as_raw_fd(), &msg, flags.bits())).map(|n| n as usize)
+}
+
+pub fn recv_msg(
+    socket: &net::UdpSocket,
+    bufs: &mut [IoSliceMut<'_>],
+    control: &mut [u8],
+    flags: MsgFlags,
+) -> io::Result<RecvMsg> {
+    let mut storage: libc::sockaddr_storage = unsafe { mem::zeroed() };
+
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_name = &mut storage as *mut _ as *mut _;
+    msg.msg_namelen = mem::size_of_val(&storage) as libc::socklen_t;
+    // `IoSliceMut` is guaranteed to be ABI compatible with `iovec`.
+    msg.msg_iov = bufs.as_mut_ptr() as *mut libc::iovec;
+    msg.msg_iovlen = bufs.len() as _;
+    if !control.is_empty() {
+        msg.msg_control = control.as_mut_ptr() as *mut _;
+        msg.msg_controllen = control.len() as _;
+    }
+
+    let len = syscall!(recvmsg(socket.as_raw_fd(), &mut msg, flags.bits()))? as usize;
+    // The datagram is already consumed at this point, so don't return an
+    // error if the address can't be converted. This is safe because `storage`
+    // is either zeroed or initialised by `recvmsg`.
+    let addr = unsafe { to_socket_addr(&storage).ok() };
+    Ok(RecvMsg {
+        len,
+        addr,
+        control_len: msg.msg_controllen as usize,
+        flags: MsgFlags::from_bits(msg.msg_flags),
     })
 }
+
+/// Maximum number of datagrams send or received in a single batch.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const MAX_BATCH: usize = 32;
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn send_to_batch(
+    socket: &net::UdpSocket,
+    bufs: &[IoSlice<'_>],
+    targets: &[SocketAddr],
+) -> io::Result<usize> {
+    let n = bufs.len().min(targets.len()).min(MAX_BATCH);
+    if n == 0 {
+        return Ok(0);
+    }
+
+    // Using `zeroed` so we don't have to deal with the platform specific
+    // padding fields.
+    let mut addrs: [SocketAddrCRepr; MAX_BATCH] = unsafe { mem::zeroed() };
+    let mut msgs: [libc::mmsghdr; MAX_BATCH] = unsafe { mem::zeroed() };
+    for i in 0..n {
+        let (raw_addr, raw_addr_length) = socket_addr(&targets[i]);
+        addrs[i] = raw_addr;
+        let hdr = &mut msgs[i].msg_hdr;
+        hdr.msg_name = addrs[i].as_ptr() as *mut _;
+        hdr.msg_namelen = raw_addr_length;
+        // `IoSlice` is guaranteed to be ABI compatible with `iovec`.
+        hdr.msg_iov = &bufs[i] as *const IoSlice<'_> as *mut libc::iovec;
+        hdr.msg_iovlen = 1;
+    }
+
+    syscall!(sendmmsg(socket.as_raw_fd(), msgs.as_mut_ptr(), n as _, 0)).map(|n| n as usize)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn recv_from_batch(
+    socket: &net::UdpSocket,
+    bufs: &mut [IoSliceMut<'_>],
+    results: &mut [(usize, Option<SocketAddr>)],
+) -> io::Result<usize> {
+    let n = bufs.len().min(results.len()).min(MAX_BATCH);
+    if n == 0 {
+        return Ok(0);
+    }
+
+    let mut storage: [libc::sockaddr_storage; MAX_BATCH] = unsafe { mem::zeroed() };
+    let mut msgs: [libc::mmsghdr; MAX_BATCH] = unsafe { mem::zeroed() };
+    for i in 0..n {
+        let hdr = &mut msgs[i].msg_hdr;
+        hdr.msg_name = &mut storage[i] as *mut _ as *mut _;
+        hdr.msg_namelen = mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+        // `IoSliceMut` is guaranteed to be ABI compatible with `iovec`.
+        hdr.msg_iov = &mut bufs[i] as *mut IoSliceMut<'_> as *mut libc::iovec;
+        hdr.msg_iovlen = 1;
+    }
+
+    let received = syscall!(recvmmsg(
+        socket.as_raw_fd(),
+        msgs.as_mut_ptr(),
+        n as _,
+        0,
+        std::ptr::null_mut(),
+    ))? as usize;
+    for i in 0..received {
+        // The datagrams are already consumed at this point, so don't return
+        // an error if an address can't be converted. This is safe because
+        // `storage` is either zeroed or initialised by `recvmmsg`.
+        let addr = unsafe { to_socket_addr(&storage[i]).ok() };
+        results[i] = (msgs[i].msg_len as usize, addr);
+    }
+    Ok(received)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn set_gro(socket: &net::UdpSocket, on: bool) -> io::Result<()> {
+    setsockopt(
+        socket.as_raw_fd(),
+        libc::SOL_UDP,
+        libc::UDP_GRO,
+        on as libc::c_int,
+    )
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn gro(socket: &net::UdpSocket) -> io::Result<bool> {
+    getsockopt::<libc::c_int>(socket.as_raw_fd(), libc::SOL_UDP, libc::UDP_GRO).map(|on| on != 0)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn set_gso_segment_size(socket: &net::UdpSocket, size: u16) -> io::Result<()> {
+    setsockopt(
+        socket.as_raw_fd(),
+        libc::SOL_UDP,
+        libc::UDP_SEGMENT,
+        libc::c_int::from(size),
+    )
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn gso_segment_size(socket: &net::UdpSocket) -> io::Result<u16> {
+    getsockopt::<libc::c_int>(socket.as_raw_fd(), libc::SOL_UDP, libc::UDP_SEGMENT)
+        .map(|size| size as u16)
+}
+
+/// Returns true if the kernel supports `UDP_SEGMENT` (Linux 4.18+).
+///
+/// Older kernels silently ignore the `UDP_SEGMENT` control message, sending
+/// one large datagram instead, so this must be checked up front.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn gso_supported(socket: &net::UdpSocket) -> bool {
+    use std::sync::atomic::{AtomicU8, Ordering};
+
+    const UNKNOWN: u8 = 0;
+    const SUPPORTED: u8 = 1;
+    const UNSUPPORTED: u8 = 2;
+    static GSO: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+    match GSO.load(Ordering::Relaxed) {
+        SUPPORTED => true,
+        UNSUPPORTED => false,
+        _ => {
+            let supported = gso_segment_size(socket).is_ok();
+            let state = if supported { SUPPORTED } else { UNSUPPORTED };
+            GSO.store(state, Ordering::Relaxed);
+            supported
+        }
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn send_to_gso(
+    socket: &net::UdpSocket,
+    buf: &[u8],
+    target: SocketAddr,
+    segment_size: u16,
+) -> io::Result<usize> {
+    if segment_size == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "segment size must be larger than zero",
+        ));
+    } else if buf.len() <= segment_size as usize {
+        // Only a single segment, no need for offloading.
+        return socket.send_to(buf, target);
+    }
+
+    if gso_supported(socket) {
+        let mut control = [0; 32];
+        let mut encoder = ControlMessageEncoder::new(&mut control);
+        SegmentSize::new(segment_size).encode(&mut encoder)?;
+        let bufs = [IoSlice::new(buf)];
+        match send_msg(
+            socket,
+            &bufs,
+            Some(target),
+            encoder.as_bytes(),
+            MsgFlags::empty(),
+        ) {
+            // `EIO` is returned if the network device doesn't support
+            // checksum offloading, which is required for segmentation
+            // offloading. `EINVAL` is returned if the kernel rejects the
+            // segmentation, e.g. for more than 64 segments.
+            Err(ref err)
+                if err.raw_os_error() == Some(libc::EIO)
+                    || err.raw_os_error() == Some(libc::EINVAL) => {}
+            res => return res,
+        }
+    }
+
+    // Fallback to sending each segment as a separate datagram.
+    let mut sent = 0;
+    for segment in buf.chunks(segment_size as usize) {
+        match socket.send_to(segment, target) {
+            Ok(n) => sent += n,
+            Err(err) if sent == 0 => return Err(err),
+            // Report the error on the next call, if it persists.
+            Err(_) => break,
+        }
+    }
+    Ok(sent)
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos"
+))]
+pub fn set_recv_pktinfo_v4(socket: &net::UdpSocket, on: bool) -> io::Result<()> {
+    setsockopt(
+        socket.as_raw_fd(),
+        libc::IPPROTO_IP,
+        libc::IP_PKTINFO,
+        on as libc::c_int,
+    )
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos"
+))]
+pub fn recv_pktinfo_v4(socket: &net::UdpSocket) -> io::Result<bool> {
+    getsockopt::<libc::c_int>(socket.as_raw_fd(), libc::IPPROTO_IP, libc::IP_PKTINFO)
+        .map(|on| on != 0)
+}
+
+pub fn set_recv_pktinfo_v6(socket: &net::UdpSocket, on: bool) -> io::Result<()> {
+    setsockopt(
+        socket.as_raw_fd(),
+        libc::IPPROTO_IPV6,
+        libc::IPV6_RECVPKTINFO,
+        on as libc::c_int,
+    )
+}
+
+pub fn recv_pktinfo_v6(socket: &net::UdpSocket) -> io::Result<bool> {
+    getsockopt::<libc::c_int>(
+        socket.as_raw_fd(),
+        libc::IPPROTO_IPV6,
+        libc::IPV6_RECVPKTINFO,
+    )
+    .map(|on| on != 0)
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos"
+))]
+pub fn set_recv_tos_v4(socket: &net::UdpSocket, on: bool) -> io::Result<()> {
+    setsockopt(
+        socket.as_raw_fd(),
+        libc::IPPROTO_IP,
+        libc::IP_RECVTOS,
+        on as libc::c_int,
+    )
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos"
+))]
+pub fn recv_tos_v4(socket: &net::UdpSocket) -> io::Result<bool> {
+    getsockopt::<libc::c_int>(socket.as_raw_fd(), libc::IPPROTO_IP, libc::IP_RECVTOS)
+        .map(|on| on != 0)
+}
+
+pub fn set_recv_tclass_v6(socket: &net::UdpSocket, on: bool) -> io::Result<()> {
+    setsockopt(
+        socket.as_raw_fd(),
+        libc::IPPROTO_IPV6,
+        libc::IPV6_RECVTCLASS,
+        on as libc::c_int,
+    )
+}
+
+pub fn recv_tclass_v6(socket: &net::UdpSocket) -> io::Result<bool> {
+    getsockopt::<libc::c_int>(
+        socket.as_raw_fd(),
+        libc::IPPROTO_IPV6,
+        libc::IPV6_RECVTCLASS,
+    )
+    .map(|on| on != 0)
+}
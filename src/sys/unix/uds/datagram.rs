@@ -1,21 +1,57 @@
-use super::{socket_addr, SocketAddr};
+use super::SocketAddr;
 use crate::sys::unix::net::new_socket;
 
 use std::io;
-use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
 use std::os::unix::net;
 use std::path::Path;
 
 pub(crate) fn bind(path: &Path) -> io::Result<net::UnixDatagram> {
+    bind_addr(&SocketAddr::from_pathname(path)?)
+}
+
+pub(crate) fn bind_addr(address: &SocketAddr) -> io::Result<net::UnixDatagram> {
     let fd = new_socket(libc::AF_UNIX, libc::SOCK_DGRAM)?;
     // Ensure the fd is closed.
     let socket = unsafe { net::UnixDatagram::from_raw_fd(fd) };
-    let (sockaddr, socklen) = socket_addr(path)?;
-    let sockaddr = &sockaddr as *const libc::sockaddr_un as *const _;
+    let (sockaddr, socklen) = address.as_raw();
     syscall!(bind(fd, sockaddr, socklen))?;
     Ok(socket)
 }
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn autobind(socket: &net::UnixDatagram) -> io::Result<()> {
+    // Binding to an address containing only the address family makes the
+    // kernel pick an unique address in the abstract namespace.
+    let mut sockaddr: libc::sockaddr_un = unsafe { std::mem::zeroed() };
+    sockaddr.sun_family = libc::AF_UNIX as libc::sa_family_t;
+    let sockaddr = &sockaddr as *const libc::sockaddr_un as *const _;
+    let socklen = std::mem::size_of::<libc::sa_family_t>() as libc::socklen_t;
+    syscall!(bind(socket.as_raw_fd(), sockaddr, socklen)).map(|_| ())
+}
+
+pub(crate) fn connect_addr(socket: &net::UnixDatagram, address: &SocketAddr) -> io::Result<()> {
+    let (sockaddr, socklen) = address.as_raw();
+    syscall!(connect(socket.as_raw_fd(), sockaddr, socklen)).map(|_| ())
+}
+
+pub(crate) fn send_to_addr(
+    socket: &net::UnixDatagram,
+    buf: &[u8],
+    address: &SocketAddr,
+) -> io::Result<usize> {
+    let (sockaddr, socklen) = address.as_raw();
+    syscall!(sendto(
+        socket.as_raw_fd(),
+        buf.as_ptr() as *const _,
+        buf.len(),
+        0,
+        sockaddr,
+        socklen,
+    ))
+    .map(|n| n as usize)
+}
+
 pub(crate) fn unbound() -> io::Result<net::UnixDatagram> {
     new_socket(libc::AF_UNIX, libc::SOCK_DGRAM)
         .map(|socket| unsafe { net::UnixDatagram::from_raw_fd(socket) })
@@ -54,3 +90,46 @@ pub(crate) fn recv_from(
     })?;
     Ok((count as usize, socketaddr))
 }
+
+pub(crate) fn send_with_fds(
+    socket: &net::UnixDatagram,
+    buf: &[u8],
+    fds: &[RawFd],
+) -> io::Result<usize> {
+    super::send_with_fds(socket.as_raw_fd(), buf, fds)
+}
+
+pub(crate) fn recv_with_fds(
+    socket: &net::UnixDatagram,
+    buf: &mut [u8],
+    fds: &mut [RawFd],
+) -> io::Result<(usize, usize, bool)> {
+    super::recv_with_fds(socket.as_raw_fd(), buf, fds)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn send_with_creds(
+    socket: &net::UnixDatagram,
+    buf: &[u8],
+    creds: &super::UCred,
+) -> io::Result<usize> {
+    super::send_with_creds(socket.as_raw_fd(), buf, creds)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn recv_with_creds(
+    socket: &net::UnixDatagram,
+    buf: &mut [u8],
+) -> io::Result<(usize, Option<super::UCred>)> {
+    super::recv_with_creds(socket.as_raw_fd(), buf)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn set_passcred(socket: &net::UnixDatagram, passcred: bool) -> io::Result<()> {
+    super::set_passcred(socket.as_raw_fd(), passcred)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn passcred(socket: &net::UnixDatagram) -> io::Result<bool> {
+    super::passcred(socket.as_raw_fd())
+}
@@ -1,6 +1,29 @@
 mod socketaddr;
 pub use self::socketaddr::SocketAddr;
 
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod ucred;
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+pub use self::ucred::UCred;
+
 /// Get the `sun_path` field offset of `sockaddr_un` for the target OS.
 ///
 /// On Linux, this funtion equates to the same value as
@@ -14,14 +37,30 @@ pub(in crate::sys) fn path_offset(sockaddr: &libc::sockaddr_un) -> usize {
 }
 
 cfg_os_poll! {
+    use crate::sys::unix::net::new_socket;
+    use crate::sys::{ControlMessageEncoder, ControlMessages};
     use std::cmp::Ordering;
+    use std::fs::File;
     use std::os::unix::ffi::OsStrExt;
-    use std::os::unix::io::{RawFd, FromRawFd};
+    use std::os::unix::io::{FromRawFd, IntoRawFd, RawFd};
+    #[cfg(not(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "linux",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
+    use std::os::unix::io::AsRawFd;
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    use crate::sys::unix::net::{getsockopt, setsockopt};
     use std::path::Path;
-    use std::{io, mem};
+    use std::{io, mem, ptr, slice};
 
     pub(crate) mod datagram;
     pub(crate) mod listener;
+    #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+    pub(crate) mod seqpacket;
     pub(crate) mod stream;
 
     pub(in crate::sys) fn socket_addr(path: &Path) -> io::Result<(libc::sockaddr_un, libc::socklen_t)> {
@@ -100,6 +139,73 @@ cfg_os_poll! {
         Ok(pair)
     }
 
+    /// Creates a new socket of `socket_type`, bound to `address` and listening
+    /// for connections.
+    fn listen<T: FromRawFd>(address: &SocketAddr, socket_type: libc::c_int) -> io::Result<T> {
+        let fd = new_socket(libc::AF_UNIX, socket_type)?;
+        let (sockaddr, socklen) = address.as_raw();
+
+        // Ensure the socket is closed if binding or listening fails.
+        let socket = unsafe { T::from_raw_fd(fd) };
+        syscall!(bind(fd, sockaddr, socklen))
+            .and_then(|_| syscall!(listen(fd, 1024)))
+            .map(|_| socket)
+    }
+
+    fn accept<T: FromRawFd>(listener: RawFd) -> io::Result<(T, SocketAddr)> {
+        let sockaddr = mem::MaybeUninit::<libc::sockaddr_un>::zeroed();
+
+        // This is safe to assume because a `libc::sockaddr_un` filled with `0`
+        // bytes is properly initialized.
+        //
+        // `0` is a valid value for `sockaddr_un::sun_family`; it is
+        // `libc::AF_UNSPEC`.
+        //
+        // `[0; 108]` is a valid value for `sockaddr_un::sun_path`; it begins an
+        // abstract path.
+        let mut sockaddr = unsafe { sockaddr.assume_init() };
+
+        sockaddr.sun_family = libc::AF_UNIX as libc::sa_family_t;
+        let mut socklen = mem::size_of_val(&sockaddr) as libc::socklen_t;
+
+        #[cfg(not(any(
+            target_os = "ios",
+            target_os = "macos",
+            target_os = "netbsd",
+            target_os = "solaris"
+        )))]
+        let socket = {
+            let flags = libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC;
+            syscall!(accept4(
+                listener,
+                &mut sockaddr as *mut libc::sockaddr_un as *mut libc::sockaddr,
+                &mut socklen,
+                flags
+            ))
+            .map(|socket| unsafe { T::from_raw_fd(socket) })
+        };
+
+        #[cfg(any(
+            target_os = "ios",
+            target_os = "macos",
+            target_os = "netbsd",
+            target_os = "solaris"
+        ))]
+        let socket = syscall!(accept(
+            listener,
+            &mut sockaddr as *mut libc::sockaddr_un as *mut libc::sockaddr,
+            &mut socklen,
+        ))
+        .and_then(|socket| {
+            // Ensure the socket is closed if either of the `fcntl` calls
+            // error below.
+            let s = unsafe { T::from_raw_fd(socket) };
+            syscall!(fcntl(socket, libc::F_SETFD, libc::FD_CLOEXEC)).map(|_| s)
+        });
+
+        socket.map(|socket| (socket, SocketAddr::from_parts(sockaddr, socklen)))
+    }
+
     // The following functions can't simply be replaced with a call to
     // `net::UnixDatagram` because of our `SocketAddr` type.
 
@@ -111,6 +217,254 @@ cfg_os_poll! {
         SocketAddr::new(|sockaddr, socklen| syscall!(getpeername(socket, sockaddr, socklen)))
     }
 
+    /// Maximum number of file descriptors that can be sent in a single
+    /// message (`SCM_MAX_FD` on Linux).
+    const MAX_FDS: usize = 253;
+
+    /// Size of the control message buffer to send or receive `n` file
+    /// descriptors.
+    fn fds_control_len(n: usize) -> usize {
+        ControlMessageEncoder::space(n * mem::size_of::<RawFd>())
+    }
+
+    fn send_with_fds(socket: RawFd, buf: &[u8], fds: &[RawFd]) -> io::Result<usize> {
+        if fds.len() > MAX_FDS {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "too many file descriptors",
+            ));
+        }
+
+        let mut control = vec![0; fds_control_len(fds.len())];
+        let mut encoder = ControlMessageEncoder::new(&mut control);
+        if !fds.is_empty() {
+            // This is safe because `RawFd` is a plain integer.
+            let data = unsafe {
+                let len = mem::size_of_val(fds);
+                slice::from_raw_parts(fds.as_ptr() as *const u8, len)
+            };
+            encoder.push(libc::SOL_SOCKET, libc::SCM_RIGHTS, data)?;
+        }
+        send_control(socket, buf, encoder.as_bytes())
+    }
+
+    fn recv_with_fds(
+        socket: RawFd,
+        buf: &mut [u8],
+        fds: &mut [RawFd],
+    ) -> io::Result<(usize, usize, bool)> {
+        // Always allow room for a single descriptor, so that we can detect
+        // truncation if the caller doesn't expect any.
+        let mut control = vec![0u8; fds_control_len(fds.len().max(1))];
+
+        // Atomically set the close-on-exec flag on the received descriptors,
+        // where supported.
+        #[cfg(any(
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "linux",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        let flags = libc::MSG_CMSG_CLOEXEC;
+        #[cfg(not(any(
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "linux",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        )))]
+        let flags = 0;
+
+        let (n, msg_flags, control_len) = recv_control(socket, buf, &mut control, flags)?;
+
+        let mut received = 0;
+        let mut truncated = msg_flags & libc::MSG_CTRUNC != 0;
+        // Excess descriptors are closed when they're dropped, but the data is
+        // already read so we must return it.
+        for fd in received_fds(&control[..control_len]) {
+            #[cfg(not(any(
+                target_os = "android",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "linux",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            )))]
+            {
+                if syscall!(fcntl(fd.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC)).is_err() {
+                    truncated = true;
+                    continue;
+                }
+            }
+            if received < fds.len() {
+                fds[received] = fd.into_raw_fd();
+                received += 1;
+            } else {
+                truncated = true;
+            }
+        }
+        Ok((n, received, truncated))
+    }
+
+    /// Returns the file descriptors in all `SCM_RIGHTS` control messages in
+    /// `control`. The descriptors are owned by the returned `File`s, so they
+    /// are closed if not used.
+    fn received_fds(control: &[u8]) -> impl Iterator<Item = File> + '_ {
+        ControlMessages::new(control)
+            .filter(|cmsg| cmsg.level() == libc::SOL_SOCKET && cmsg.kind() == libc::SCM_RIGHTS)
+            .flat_map(|cmsg| cmsg.data().chunks_exact(mem::size_of::<RawFd>()))
+            .map(|data| {
+                // This is safe because `data` contains a full `RawFd`, which
+                // doesn't have to be aligned, and the kernel gave us
+                // ownership of the descriptor.
+                unsafe { File::from_raw_fd(ptr::read_unaligned(data.as_ptr() as *const RawFd)) }
+            })
+    }
+
+    /// Sends `buf` with the ancillary data in `control` using `sendmsg(2)`.
+    fn send_control(socket: RawFd, buf: &[u8], control: &[u8]) -> io::Result<usize> {
+        let mut iov = libc::iovec {
+            iov_base: buf.as_ptr() as *mut _,
+            iov_len: buf.len(),
+        };
+        // Using `zeroed` so we don't have to deal with the platform specific
+        // padding fields.
+        let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        if !control.is_empty() {
+            msg.msg_control = control.as_ptr() as *mut _;
+            msg.msg_controllen = control.len() as _;
+        }
+        // Mirror what libstd does for `send` on platforms that support it.
+        #[cfg(any(
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "linux",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        let flags = libc::MSG_NOSIGNAL;
+        #[cfg(not(any(
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "linux",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        )))]
+        let flags = 0;
+
+        syscall!(sendmsg(socket, &msg, flags)).map(|n| n as usize)
+    }
+
+    /// Receives into `buf` and the ancillary data into `control` using
+    /// `recvmsg(2)`. Returns the number of bytes read, the flags of the
+    /// received message and the length of the ancillary data.
+    fn recv_control(
+        socket: RawFd,
+        buf: &mut [u8],
+        control: &mut [u8],
+        flags: libc::c_int,
+    ) -> io::Result<(usize, libc::c_int, usize)> {
+        let mut iov = libc::iovec {
+            iov_base: buf.as_mut_ptr() as *mut _,
+            iov_len: buf.len(),
+        };
+        let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = control.as_mut_ptr() as *mut _;
+        msg.msg_controllen = control.len() as _;
+
+        let n = syscall!(recvmsg(socket, &mut msg, flags))? as usize;
+        let control_len = (msg.msg_controllen as usize).min(control.len());
+        Ok((n, msg.msg_flags, control_len))
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn peer_cred(socket: RawFd) -> io::Result<UCred> {
+        let cred: libc::ucred = getsockopt(socket, libc::SOL_SOCKET, libc::SO_PEERCRED)?;
+        Ok(UCred {
+            uid: cred.uid,
+            gid: cred.gid,
+            pid: Some(cred.pid),
+        })
+    }
+
+    #[cfg(any(
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    fn peer_cred(socket: RawFd) -> io::Result<UCred> {
+        let mut uid = 0;
+        let mut gid = 0;
+        syscall!(getpeereid(socket, &mut uid, &mut gid))?;
+        Ok(UCred { uid, gid, pid: None })
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn send_with_creds(socket: RawFd, buf: &[u8], creds: &UCred) -> io::Result<usize> {
+        let cred = libc::ucred {
+            pid: creds.pid.unwrap_or_else(|| unsafe { libc::getpid() }),
+            uid: creds.uid,
+            gid: creds.gid,
+        };
+        // This is safe because `ucred` is a C type without padding.
+        let data = unsafe {
+            slice::from_raw_parts(&cred as *const libc::ucred as *const u8, mem::size_of_val(&cred))
+        };
+        let mut control = vec![0; ControlMessageEncoder::space(data.len())];
+        let mut encoder = ControlMessageEncoder::new(&mut control);
+        encoder.push(libc::SOL_SOCKET, libc::SCM_CREDENTIALS, data)?;
+        send_control(socket, buf, encoder.as_bytes())
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn recv_with_creds(socket: RawFd, buf: &mut [u8]) -> io::Result<(usize, Option<UCred>)> {
+        let mut control = vec![0; ControlMessageEncoder::space(mem::size_of::<libc::ucred>())];
+        let (n, _, control_len) = recv_control(socket, buf, &mut control, libc::MSG_CMSG_CLOEXEC)?;
+
+        let control = &control[..control_len];
+        // We're not expecting any file descriptors, but the peer could send
+        // them anyway, close them so they don't leak.
+        received_fds(control).for_each(drop);
+
+        let creds = ControlMessages::new(control)
+            .find(|cmsg| cmsg.level() == libc::SOL_SOCKET && cmsg.kind() == libc::SCM_CREDENTIALS)
+            .filter(|cmsg| cmsg.data().len() >= mem::size_of::<libc::ucred>())
+            .map(|cmsg| {
+                // This is safe because we checked the size above and the data
+                // doesn't have to be aligned.
+                let cred =
+                    unsafe { ptr::read_unaligned(cmsg.data().as_ptr() as *const libc::ucred) };
+                UCred {
+                    uid: cred.uid,
+                    gid: cred.gid,
+                    pid: Some(cred.pid),
+                }
+            });
+        Ok((n, creds))
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn set_passcred(socket: RawFd, passcred: bool) -> io::Result<()> {
+        setsockopt(socket, libc::SOL_SOCKET, libc::SO_PASSCRED, passcred as libc::c_int)
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn passcred(socket: RawFd) -> io::Result<bool> {
+        getsockopt::<libc::c_int>(socket, libc::SOL_SOCKET, libc::SO_PASSCRED).map(|v| v != 0)
+    }
+
     #[cfg(test)]
     mod tests {
         use super::{path_offset, socket_addr};
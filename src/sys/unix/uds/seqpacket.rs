@@ -0,0 +1,69 @@
+use super::SocketAddr;
+use crate::sys::unix::net::new_socket;
+
+pub(crate) use crate::sys::unix::net::take_error;
+
+use std::fs::File;
+use std::io;
+use std::net::Shutdown;
+use std::os::unix::io::{FromRawFd, RawFd};
+use std::path::Path;
+
+pub(crate) fn bind(path: &Path) -> io::Result<File> {
+    bind_addr(&SocketAddr::from_pathname(path)?)
+}
+
+pub(crate) fn bind_addr(address: &SocketAddr) -> io::Result<File> {
+    super::listen(address, libc::SOCK_SEQPACKET)
+}
+
+pub(crate) fn accept(listener: RawFd) -> io::Result<(File, SocketAddr)> {
+    super::accept(listener)
+}
+
+pub(crate) fn connect(path: &Path) -> io::Result<File> {
+    connect_addr(&SocketAddr::from_pathname(path)?)
+}
+
+pub(crate) fn connect_addr(address: &SocketAddr) -> io::Result<File> {
+    let fd = new_socket(libc::AF_UNIX, libc::SOCK_SEQPACKET)?;
+    // Ensure the fd is closed.
+    let socket = unsafe { File::from_raw_fd(fd) };
+    let (sockaddr, socklen) = address.as_raw();
+    match syscall!(connect(fd, sockaddr, socklen)) {
+        Ok(_) => {}
+        Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {}
+        Err(e) => return Err(e),
+    }
+    Ok(socket)
+}
+
+pub(crate) fn pair() -> io::Result<(File, File)> {
+    super::pair(libc::SOCK_SEQPACKET)
+}
+
+pub(crate) fn local_addr(socket: RawFd) -> io::Result<SocketAddr> {
+    super::local_addr(socket)
+}
+
+pub(crate) fn peer_addr(socket: RawFd) -> io::Result<SocketAddr> {
+    super::peer_addr(socket)
+}
+
+pub(crate) fn send(socket: RawFd, buf: &[u8]) -> io::Result<usize> {
+    super::send_control(socket, buf, &[])
+}
+
+pub(crate) fn recv(socket: RawFd, buf: &mut [u8]) -> io::Result<(usize, bool)> {
+    super::recv_control(socket, buf, &mut [], 0)
+        .map(|(n, flags, _)| (n, flags & libc::MSG_TRUNC != 0))
+}
+
+pub(crate) fn shutdown(socket: RawFd, how: Shutdown) -> io::Result<()> {
+    let how = match how {
+        Shutdown::Write => libc::SHUT_WR,
+        Shutdown::Read => libc::SHUT_RD,
+        Shutdown::Both => libc::SHUT_RDWR,
+    };
+    syscall!(shutdown(socket, how)).map(|_| ())
+}
@@ -46,6 +46,7 @@ impl SocketAddr {
 }
 
 cfg_os_poll! {
+    use super::socket_addr;
     use std::{io, mem};
 
     impl SocketAddr {
@@ -69,6 +70,52 @@ cfg_os_poll! {
             SocketAddr { sockaddr, socklen }
         }
 
+        /// Returns a pointer to the raw address and its length, to pass to
+        /// e.g. `bind(2)`.
+        pub(crate) fn as_raw(&self) -> (*const libc::sockaddr, libc::socklen_t) {
+            (&self.sockaddr as *const libc::sockaddr_un as *const libc::sockaddr, self.socklen)
+        }
+
+        /// Creates a `SocketAddr` from a `pathname` address.
+        ///
+        /// Returns an error if the path is longer than `SUN_LEN`. Paths
+        /// starting with a null byte are treated as abstract addresses.
+        pub fn from_pathname<P: AsRef<Path>>(path: P) -> io::Result<SocketAddr> {
+            socket_addr(path.as_ref()).map(|(sockaddr, socklen)| SocketAddr { sockaddr, socklen })
+        }
+
+        /// Creates a `SocketAddr` in the abstract namespace.
+        ///
+        /// Abstract addresses are not visible in the filesystem and are
+        /// released once all sockets using them are closed. `name` should not
+        /// include the leading null byte.
+        ///
+        /// Returns an error if the name is longer than `SUN_LEN - 1`.
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        pub fn from_abstract_name<N: AsRef<[u8]>>(name: N) -> io::Result<SocketAddr> {
+            let name = name.as_ref();
+            let mut sockaddr = {
+                let sockaddr = mem::MaybeUninit::<libc::sockaddr_un>::zeroed();
+                unsafe { sockaddr.assume_init() }
+            };
+            sockaddr.sun_family = libc::AF_UNIX as libc::sa_family_t;
+
+            // The first byte is the null byte that marks the address as
+            // abstract, already set above.
+            if name.len() >= sockaddr.sun_path.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "abstract name must be shorter than libc::sockaddr_un.sun_path",
+                ));
+            }
+            for (dst, src) in sockaddr.sun_path[1..].iter_mut().zip(name.iter()) {
+                *dst = *src as libc::c_char;
+            }
+
+            let socklen = path_offset(&sockaddr) + 1 + name.len();
+            Ok(SocketAddr { sockaddr, socklen: socklen as libc::socklen_t })
+        }
+
         /// Returns `true` if the address is unnamed.
         ///
         /// Documentation reflected in [`SocketAddr`]
@@ -94,6 +141,17 @@ cfg_os_poll! {
                 None
             }
         }
+
+        /// Returns the contents of this address, without the leading null
+        /// byte, if it is an `abstract` address.
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        pub fn as_abstract_name(&self) -> Option<&[u8]> {
+            if let AddressKind::Abstract(name) = self.address() {
+                Some(name)
+            } else {
+                None
+            }
+        }
     }
 }
 
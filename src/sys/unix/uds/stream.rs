@@ -1,15 +1,20 @@
-use super::{socket_addr, SocketAddr};
+use super::SocketAddr;
 use crate::sys::unix::net::new_socket;
 
+pub(crate) use crate::sys::unix::net::{recv_with_flags, send_with_flags};
+
 use std::io;
-use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
 use std::os::unix::net;
 use std::path::Path;
 
 pub(crate) fn connect(path: &Path) -> io::Result<net::UnixStream> {
+    connect_addr(&SocketAddr::from_pathname(path)?)
+}
+
+pub(crate) fn connect_addr(address: &SocketAddr) -> io::Result<net::UnixStream> {
     let socket = new_socket(libc::AF_UNIX, libc::SOCK_STREAM)?;
-    let (sockaddr, socklen) = socket_addr(path)?;
-    let sockaddr = &sockaddr as *const libc::sockaddr_un as *const libc::sockaddr;
+    let (sockaddr, socklen) = address.as_raw();
 
     match syscall!(connect(socket, sockaddr, socklen)) {
         Ok(_) => {}
@@ -37,3 +42,33 @@ pub(crate) fn local_addr(socket: &net::UnixStream) -> io::Result<SocketAddr> {
 pub(crate) fn peer_addr(socket: &net::UnixStream) -> io::Result<SocketAddr> {
     super::peer_addr(socket.as_raw_fd())
 }
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+pub(crate) fn peer_cred(socket: &net::UnixStream) -> io::Result<super::UCred> {
+    super::peer_cred(socket.as_raw_fd())
+}
+
+pub(crate) fn send_with_fds(
+    socket: &net::UnixStream,
+    buf: &[u8],
+    fds: &[RawFd],
+) -> io::Result<usize> {
+    super::send_with_fds(socket.as_raw_fd(), buf, fds)
+}
+
+pub(crate) fn recv_with_fds(
+    socket: &net::UnixStream,
+    buf: &mut [u8],
+    fds: &mut [RawFd],
+) -> io::Result<(usize, usize, bool)> {
+    super::recv_with_fds(socket.as_raw_fd(), buf, fds)
+}
@@ -0,0 +1,18 @@
+/// Credentials of a process on the other side of a Unix socket.
+///
+/// Returned by [`UnixStream::peer_cred`] and, on Linux and Android, used to
+/// send and receive `SCM_CREDENTIALS` ancillary data with a [`UnixDatagram`].
+///
+/// [`UnixStream::peer_cred`]: struct.UnixStream.html#method.peer_cred
+/// [`UnixDatagram`]: struct.UnixDatagram.html
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct UCred {
+    /// The effective user ID of the process.
+    pub uid: libc::uid_t,
+    /// The effective group ID of the process.
+    pub gid: libc::gid_t,
+    /// The process ID of the process, if known.
+    ///
+    /// This is only available on Linux and Android.
+    pub pid: Option<libc::pid_t>,
+}
@@ -1,5 +1,7 @@
 #[cfg(any(target_os = "linux", target_os = "android"))]
 mod eventfd {
+    #[cfg(all(target_os = "linux", feature = "io-uring"))]
+    use crate::sys::unix::selector::Registration;
     use crate::sys::Selector;
     use crate::{Interest, Token};
 
@@ -15,6 +17,8 @@ mod eventfd {
     /// reset the count to 0, returning the count.
     #[derive(Debug)]
     pub struct Waker {
+        #[cfg(all(target_os = "linux", feature = "io-uring"))]
+        _registration: Option<Registration>,
         fd: File,
     }
 
@@ -26,7 +30,11 @@ mod eventfd {
                 let file = unsafe { File::from_raw_fd(fd) };
                 selector
                     .register(fd, token, Interest::READABLE)
-                    .map(|()| Waker { fd: file })
+                    .map(|()| Waker {
+                        #[cfg(all(target_os = "linux", feature = "io-uring"))]
+                        _registration: Registration::new(selector, fd),
+                        fd: file,
+                    })
             })
         }
 
@@ -50,6 +50,16 @@ pub fn is_lio(_: &Event) -> bool {
     false
 }
 
+cfg_time! {
+    /// Create a readable event for an expired `TimerWheel` timeout.
+    pub fn timeout(token: Token) -> Event {
+        Event {
+            flags: afd::POLL_RECEIVE,
+            data: token.0 as u64,
+        }
+    }
+}
+
 pub fn debug_details(f: &mut fmt::Formatter<'_>, event: &Event) -> fmt::Result {
     #[allow(clippy::trivially_copy_pass_by_ref)]
     fn check_flags(got: &u32, want: &u32) -> bool {
@@ -117,3 +127,15 @@ impl Events {
         }
     }
 }
+
+cfg_time! {
+    impl Events {
+        pub fn len(&self) -> usize {
+            self.events.len()
+        }
+
+        pub fn push(&mut self, event: Event) {
+            self.events.push(event)
+        }
+    }
+}
@@ -41,7 +41,7 @@ cfg_net! {
     use std::pin::Pin;
     use std::sync::{Arc, Mutex};
 
-    use crate::{poll, Interest, Registry, Token};
+    use crate::{poll, Interest, Mode, Registry, Token};
 
     mod net;
 
@@ -59,6 +59,23 @@ cfg_net! {
         }
     }
 
+    /// Only edge-triggered, non-exclusive, registrations are supported.
+    fn check_interests(interests: Interest) -> io::Result<()> {
+        if interests.is_exclusive() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "exclusive registrations are not supported on this platform",
+            ));
+        }
+        match interests.mode() {
+            Mode::Edge => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "registration mode not supported on Windows",
+            )),
+        }
+    }
+
     pub struct IoSourceState {
         // This is `None` if the socket has not yet been registered.
         //
@@ -96,6 +113,7 @@ cfg_net! {
             interests: Interest,
             socket: RawSocket,
         ) -> io::Result<()> {
+            check_interests(interests)?;
             if self.inner.is_some() {
                 Err(io::ErrorKind::AlreadyExists.into())
             } else {
@@ -113,6 +131,7 @@ cfg_net! {
             token: Token,
             interests: Interest,
         ) -> io::Result<()> {
+            check_interests(interests)?;
             match self.inner.as_mut() {
                 Some(state) => {
                     poll::selector(registry)
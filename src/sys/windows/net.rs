@@ -1,14 +1,15 @@
 use std::io;
-use std::mem::size_of_val;
+use std::mem::{self, size_of, size_of_val};
 use std::net::SocketAddr;
 #[cfg(all(feature = "os-poll", feature = "tcp"))]
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
 use std::sync::Once;
 
-use winapi::ctypes::c_int;
-use winapi::shared::ws2def::SOCKADDR;
+use winapi::ctypes::{c_char, c_int};
+use winapi::shared::ws2def::{SOCKADDR, SOL_SOCKET, SO_RCVBUF, SO_REUSEADDR, SO_SNDBUF};
 use winapi::um::winsock2::{
-    ioctlsocket, socket, FIONBIO, INVALID_SOCKET, PF_INET, PF_INET6, SOCKET,
+    getsockopt as win_getsockopt, ioctlsocket, setsockopt as win_setsockopt, socket, FIONBIO,
+    INVALID_SOCKET, SOCKET, SOCKET_ERROR,
 };
 
 /// Initialise the network stack for Windows.
@@ -23,12 +24,7 @@ pub(crate) fn init() {
 }
 
 /// Create a new non-blocking socket.
-pub(crate) fn new_socket(addr: SocketAddr, socket_type: c_int) -> io::Result<SOCKET> {
-    let domain = match addr {
-        SocketAddr::V4(..) => PF_INET,
-        SocketAddr::V6(..) => PF_INET6,
-    };
-
+pub(crate) fn new_socket(domain: c_int, socket_type: c_int) -> io::Result<SOCKET> {
     syscall!(
         socket(domain, socket_type, 0),
         PartialEq::eq,
@@ -67,3 +63,61 @@ pub(crate) fn inaddr_any(other: SocketAddr) -> SocketAddr {
         }
     }
 }
+
+/// Sets the socket option `name` at `level` to `value`.
+pub(crate) fn setsockopt<T>(socket: SOCKET, level: c_int, name: c_int, value: T) -> io::Result<()> {
+    syscall!(
+        win_setsockopt(
+            socket,
+            level,
+            name,
+            &value as *const T as *const c_char,
+            size_of::<T>() as c_int,
+        ),
+        PartialEq::eq,
+        SOCKET_ERROR
+    )
+    .map(|_| ())
+}
+
+/// Gets the value of the socket option `name` at `level`.
+pub(crate) fn getsockopt<T: Copy>(socket: SOCKET, level: c_int, name: c_int) -> io::Result<T> {
+    let mut value: T = unsafe { mem::zeroed() };
+    let mut length = size_of::<T>() as c_int;
+    syscall!(
+        win_getsockopt(
+            socket,
+            level,
+            name,
+            &mut value as *mut T as *mut c_char,
+            &mut length,
+        ),
+        PartialEq::eq,
+        SOCKET_ERROR
+    )
+    .map(|_| value)
+}
+
+pub(crate) fn set_reuseaddr(socket: SOCKET, reuseaddr: bool) -> io::Result<()> {
+    setsockopt(socket, SOL_SOCKET, SO_REUSEADDR, reuseaddr as c_int)
+}
+
+pub(crate) fn reuseaddr(socket: SOCKET) -> io::Result<bool> {
+    getsockopt::<c_int>(socket, SOL_SOCKET, SO_REUSEADDR).map(|on| on != 0)
+}
+
+pub(crate) fn set_send_buffer_size(socket: SOCKET, size: u32) -> io::Result<()> {
+    setsockopt(socket, SOL_SOCKET, SO_SNDBUF, size as c_int)
+}
+
+pub(crate) fn send_buffer_size(socket: SOCKET) -> io::Result<u32> {
+    getsockopt::<c_int>(socket, SOL_SOCKET, SO_SNDBUF).map(|size| size as u32)
+}
+
+pub(crate) fn set_recv_buffer_size(socket: SOCKET, size: u32) -> io::Result<()> {
+    setsockopt(socket, SOL_SOCKET, SO_RCVBUF, size as c_int)
+}
+
+pub(crate) fn recv_buffer_size(socket: SOCKET) -> io::Result<u32> {
+    getsockopt::<c_int>(socket, SOL_SOCKET, SO_RCVBUF).map(|size| size as u32)
+}
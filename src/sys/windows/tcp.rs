@@ -1,68 +1,102 @@
 use std::io;
+use std::mem::ManuallyDrop;
 use std::net::{self, SocketAddr};
 use std::os::windows::io::FromRawSocket;
 use std::os::windows::raw::SOCKET as StdSocket; // winapi uses usize, stdlib uses u32/u64.
+use std::time::Duration;
 
+use winapi::ctypes::{c_int, c_ushort};
+use winapi::shared::ws2def::{SOL_SOCKET, SO_KEEPALIVE, SO_LINGER};
 use winapi::um::winsock2::{
-    bind as win_bind, closesocket, connect as win_connect, listen, SOCKET_ERROR, SOCK_STREAM,
+    bind as win_bind, closesocket, connect as win_connect, linger, listen, PF_INET, PF_INET6,
+    SOCKET, SOCKET_ERROR, SOCK_STREAM,
 };
 
-use crate::sys::windows::net::{inaddr_any, init, new_socket, socket_addr};
+use crate::sys::windows::net::{getsockopt, init, new_socket, setsockopt, socket_addr};
 
-pub fn connect(addr: SocketAddr) -> io::Result<net::TcpStream> {
+pub(crate) use crate::sys::windows::net::{
+    inaddr_any, recv_buffer_size, reuseaddr, send_buffer_size, set_recv_buffer_size, set_reuseaddr,
+    set_send_buffer_size,
+};
+
+pub(crate) type TcpSocket = SOCKET;
+
+pub(crate) fn new_v4_socket() -> io::Result<TcpSocket> {
     init();
-    new_socket(addr, SOCK_STREAM)
-        .and_then(|socket| {
-            // Required for a future `connect_overlapped` operation to be
-            // executed successfully.
-            let any_addr = inaddr_any(addr);
-            let (raw_addr, raw_addr_length) = socket_addr(&any_addr);
-            syscall!(
-                win_bind(socket, raw_addr, raw_addr_length),
-                PartialEq::eq,
-                SOCKET_ERROR
-            )
-            .and_then(|_| {
-                let (raw_addr, raw_addr_length) = socket_addr(&addr);
-                syscall!(
-                    win_connect(socket, raw_addr, raw_addr_length),
-                    PartialEq::eq,
-                    SOCKET_ERROR
-                )
-                .or_else(|err| match err {
-                    ref err if err.kind() == io::ErrorKind::WouldBlock => Ok(0),
-                    err => Err(err),
-                })
-            })
-            .map(|_| socket)
-            .map_err(|err| {
-                // Close the socket if we hit an error, ignoring the error
-                // from closing since we can't pass back two errors.
-                let _ = unsafe { closesocket(socket) };
-                err
-            })
-        })
-        .map(|socket| unsafe { net::TcpStream::from_raw_socket(socket as StdSocket) })
+    new_socket(PF_INET, SOCK_STREAM)
 }
 
-pub fn bind(addr: SocketAddr) -> io::Result<net::TcpListener> {
+pub(crate) fn new_v6_socket() -> io::Result<TcpSocket> {
     init();
-    new_socket(addr, SOCK_STREAM).and_then(|socket| {
-        let (raw_addr, raw_addr_length) = socket_addr(&addr);
-        syscall!(
-            win_bind(socket, raw_addr, raw_addr_length,),
-            PartialEq::eq,
-            SOCKET_ERROR
-        )
-        .and_then(|_| syscall!(listen(socket, 1024), PartialEq::eq, SOCKET_ERROR))
-        .map_err(|err| {
-            // Close the socket if we hit an error, ignoring the error
-            // from closing since we can't pass back two errors.
-            let _ = unsafe { closesocket(socket) };
-            err
-        })
-        .map(|_| unsafe { net::TcpListener::from_raw_socket(socket as StdSocket) })
-    })
+    new_socket(PF_INET6, SOCK_STREAM)
+}
+
+pub(crate) fn bind(socket: TcpSocket, addr: SocketAddr) -> io::Result<()> {
+    let (raw_addr, raw_addr_length) = socket_addr(&addr);
+    syscall!(
+        win_bind(socket, raw_addr, raw_addr_length),
+        PartialEq::eq,
+        SOCKET_ERROR
+    )?;
+    Ok(())
+}
+
+/// The socket must be bound before calling this, see `TcpStream::connect`.
+pub(crate) fn connect(socket: TcpSocket, addr: SocketAddr) -> io::Result<net::TcpStream> {
+    let (raw_addr, raw_addr_length) = socket_addr(&addr);
+    let res = syscall!(
+        win_connect(socket, raw_addr, raw_addr_length),
+        PartialEq::eq,
+        SOCKET_ERROR
+    );
+    match res {
+        // Connect hasn't finished, but that is fine.
+        Err(err) if err.kind() != io::ErrorKind::WouldBlock => Err(err),
+        _ => Ok(unsafe { net::TcpStream::from_raw_socket(socket as StdSocket) }),
+    }
+}
+
+pub(crate) fn listen(socket: TcpSocket, backlog: u32) -> io::Result<net::TcpListener> {
+    use std::convert::TryInto;
+
+    let backlog = backlog.try_into().unwrap_or(c_int::MAX);
+    syscall!(listen(socket, backlog), PartialEq::eq, SOCKET_ERROR)?;
+    Ok(unsafe { net::TcpListener::from_raw_socket(socket as StdSocket) })
+}
+
+pub(crate) fn close(socket: TcpSocket) {
+    let _ = unsafe { closesocket(socket) };
+}
+
+pub(crate) fn set_linger(socket: TcpSocket, dur: Option<Duration>) -> io::Result<()> {
+    let val = linger {
+        l_onoff: dur.is_some() as c_ushort,
+        l_linger: dur.map(|dur| dur.as_secs() as c_ushort).unwrap_or_default(),
+    };
+    setsockopt(socket, SOL_SOCKET, SO_LINGER, val)
+}
+
+pub(crate) fn linger(socket: TcpSocket) -> io::Result<Option<Duration>> {
+    let val: linger = getsockopt(socket, SOL_SOCKET, SO_LINGER)?;
+    if val.l_onoff == 0 {
+        Ok(None)
+    } else {
+        Ok(Some(Duration::from_secs(val.l_linger as u64)))
+    }
+}
+
+pub(crate) fn set_keepalive(socket: TcpSocket, keepalive: bool) -> io::Result<()> {
+    setsockopt(socket, SOL_SOCKET, SO_KEEPALIVE, keepalive as c_int)
+}
+
+pub(crate) fn keepalive(socket: TcpSocket) -> io::Result<bool> {
+    getsockopt::<c_int>(socket, SOL_SOCKET, SO_KEEPALIVE).map(|on| on != 0)
+}
+
+pub(crate) fn local_addr(socket: TcpSocket) -> io::Result<SocketAddr> {
+    // The socket is still owned by the caller, so don't close it.
+    let socket = ManuallyDrop::new(unsafe { net::TcpStream::from_raw_socket(socket as StdSocket) });
+    socket.local_addr()
 }
 
 pub fn accept(listener: &net::TcpListener) -> io::Result<(net::TcpStream, SocketAddr)> {
@@ -3,25 +3,50 @@ use std::net::{self, SocketAddr};
 use std::os::windows::io::FromRawSocket;
 use std::os::windows::raw::SOCKET as StdSocket; // winapi uses usize, stdlib uses u32/u64.
 
-use winapi::um::winsock2::{bind as win_bind, closesocket, SOCKET_ERROR, SOCK_DGRAM};
+use winapi::ctypes::c_int;
+use winapi::shared::ws2def::IPPROTO_IPV6;
+use winapi::shared::ws2ipdef::IPV6_V6ONLY;
+use winapi::um::winsock2::{
+    bind as win_bind, closesocket, PF_INET, PF_INET6, SOCKET, SOCKET_ERROR, SOCK_DGRAM,
+};
 
-use crate::sys::windows::net::{init, new_socket, socket_addr};
+use crate::sys::windows::net::{getsockopt, init, new_socket, setsockopt, socket_addr};
 
-pub fn bind(addr: SocketAddr) -> io::Result<net::UdpSocket> {
+pub(crate) use crate::sys::windows::net::{
+    recv_buffer_size, reuseaddr, send_buffer_size, set_recv_buffer_size, set_reuseaddr,
+    set_send_buffer_size,
+};
+
+pub(crate) type UdpSocketBuilder = SOCKET;
+
+pub(crate) fn new_v4_socket() -> io::Result<UdpSocketBuilder> {
     init();
-    new_socket(addr, SOCK_DGRAM).and_then(|socket| {
-        let (raw_addr, raw_addr_length) = socket_addr(&addr);
-        syscall!(
-            win_bind(socket, raw_addr, raw_addr_length,),
-            PartialEq::eq,
-            SOCKET_ERROR
-        )
-        .map_err(|err| {
-            // Close the socket if we hit an error, ignoring the error
-            // from closing since we can't pass back two errors.
-            let _ = unsafe { closesocket(socket) };
-            err
-        })
-        .map(|_| unsafe { net::UdpSocket::from_raw_socket(socket as StdSocket) })
-    })
+    new_socket(PF_INET, SOCK_DGRAM)
+}
+
+pub(crate) fn new_v6_socket() -> io::Result<UdpSocketBuilder> {
+    init();
+    new_socket(PF_INET6, SOCK_DGRAM)
+}
+
+pub(crate) fn bind(socket: UdpSocketBuilder, addr: SocketAddr) -> io::Result<net::UdpSocket> {
+    let (raw_addr, raw_addr_length) = socket_addr(&addr);
+    syscall!(
+        win_bind(socket, raw_addr, raw_addr_length),
+        PartialEq::eq,
+        SOCKET_ERROR
+    )?;
+    Ok(unsafe { net::UdpSocket::from_raw_socket(socket as StdSocket) })
+}
+
+pub(crate) fn close(socket: UdpSocketBuilder) {
+    let _ = unsafe { closesocket(socket) };
+}
+
+pub(crate) fn set_only_v6(socket: UdpSocketBuilder, only_v6: bool) -> io::Result<()> {
+    setsockopt(socket, IPPROTO_IPV6 as c_int, IPV6_V6ONLY, only_v6 as c_int)
+}
+
+pub(crate) fn only_v6(socket: UdpSocketBuilder) -> io::Result<bool> {
+    getsockopt::<c_int>(socket, IPPROTO_IPV6 as c_int, IPV6_V6ONLY).map(|on| on != 0)
 }
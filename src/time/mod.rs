@@ -0,0 +1,23 @@
+//! Timer primitives.
+//!
+//! Timers deliver their expirations as regular readiness events, which means
+//! they are polled using [`Poll`] just like any other event source.
+//!
+//! [`TimerWheel`] is a user space timer wheel owned by [`Poll`], useful for a
+//! large number of timeouts, e.g. per connection idle timeouts. On Linux and
+//! Android a kernel backed [`Timer`] is available as well, which can be
+//! registered with a [`Registry`].
+//!
+//! [`Registry`]: ../struct.Registry.html
+//! [`Poll`]: ../struct.Poll.html
+//! [`TimerWheel`]: struct.TimerWheel.html
+//! [`Timer`]: struct.Timer.html
+
+mod wheel;
+pub use self::wheel::{Timeout, TimerWheel};
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+cfg_os_poll! {
+    mod timer;
+    pub use self::timer::{Clock, Timer};
+}
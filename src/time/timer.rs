@@ -0,0 +1,229 @@
+use crate::io_source::IoSource;
+use crate::{event, sys, Interest, Registry, Token};
+
+use std::io;
+use std::os::unix::io::{AsRawFd, IntoRawFd, RawFd};
+use std::time::Duration;
+
+/// Clock used by a [`Timer`] to measure the passing of time.
+///
+/// [`Timer`]: struct.Timer.html
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Clock {
+    /// A non-settable clock that is not affected by discontinuous changes in
+    /// the system time, `CLOCK_MONOTONIC`. Its epoch is unspecified, but fixed
+    /// for the lifetime of the system.
+    Monotonic,
+    /// The settable system-wide real-time clock, `CLOCK_REALTIME`. Its epoch
+    /// is the Unix epoch.
+    Realtime,
+}
+
+impl Clock {
+    /// Returns the current time of the clock, measured from the clock's epoch.
+    ///
+    /// This can be used to calculate absolute deadlines for
+    /// [`Timer::set_deadline`].
+    ///
+    /// [`Timer::set_deadline`]: struct.Timer.html#method.set_deadline
+    pub fn now(self) -> io::Result<Duration> {
+        sys::timer::now(self)
+    }
+}
+
+/// A timer which can be registered with [`Poll`].
+///
+/// Once the timer expires it will cause an event with [`readable`] readiness
+/// and the token it was registered with. The number of expirations can then be
+/// retrieved using [`read`], which also resets the readiness.
+///
+/// A newly created timer is disarmed. It can be armed as a one-shot timer
+/// using [`set_timeout`] or [`set_deadline`], or as a periodic timer using
+/// [`set_interval`] or [`set_deadline_interval`].
+///
+/// [`Poll`]: ../struct.Poll.html
+/// [`readable`]: ../event/struct.Event.html#method.is_readable
+/// [`read`]: #method.read
+/// [`set_timeout`]: #method.set_timeout
+/// [`set_deadline`]: #method.set_deadline
+/// [`set_interval`]: #method.set_interval
+/// [`set_deadline_interval`]: #method.set_deadline_interval
+///
+/// # Implementation notes
+///
+/// This is only available on Linux and Android, where it is backed by
+/// [timerfd].
+///
+/// [timerfd]: http://man7.org/linux/man-pages/man2/timerfd_create.2.html
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::time::Duration;
+///
+/// use mio::time::{Clock, Timer};
+/// use mio::{Events, Interest, Poll, Token};
+///
+/// const TIMER: Token = Token(0);
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(8);
+///
+/// let mut timer = Timer::new(Clock::Monotonic)?;
+/// poll.registry().register(&mut timer, TIMER, Interest::READABLE)?;
+///
+/// timer.set_timeout(Duration::from_millis(10))?;
+///
+/// poll.poll(&mut events, None)?;
+/// let event = events.iter().next().unwrap();
+/// assert_eq!(event.token(), TIMER);
+/// assert_eq!(timer.read()?, 1);
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Timer {
+    inner: IoSource<sys::Timer>,
+}
+
+impl Timer {
+    /// Create a new, disarmed, `Timer` using `clock`.
+    pub fn new(clock: Clock) -> io::Result<Timer> {
+        sys::Timer::new(clock).map(|timer| Timer {
+            inner: IoSource::new(timer),
+        })
+    }
+
+    /// Arm the timer to expire once after `timeout`.
+    ///
+    /// This replaces any previous setting of the timer. A zero `timeout` is
+    /// rounded up to the smallest possible timeout.
+    pub fn set_timeout(&self, timeout: Duration) -> io::Result<()> {
+        self.inner
+            .set(non_zero(timeout), Duration::from_secs(0), false)
+    }
+
+    /// Arm the timer to expire periodically, first after `interval` and then
+    /// every `interval` after that.
+    ///
+    /// This replaces any previous setting of the timer. A zero `interval` is
+    /// rounded up to the smallest possible interval.
+    pub fn set_interval(&self, interval: Duration) -> io::Result<()> {
+        let interval = non_zero(interval);
+        self.inner.set(interval, interval, false)
+    }
+
+    /// Arm the timer to expire once at the absolute `deadline`, measured from
+    /// the epoch of the timer's [`Clock`].
+    ///
+    /// If `deadline` is in the past the timer will expire immediately. This
+    /// replaces any previous setting of the timer.
+    ///
+    /// [`Clock`]: enum.Clock.html
+    pub fn set_deadline(&self, deadline: Duration) -> io::Result<()> {
+        self.inner
+            .set(non_zero(deadline), Duration::from_secs(0), true)
+    }
+
+    /// Arm the timer to expire at the absolute `deadline`, and then every
+    /// `interval` after that.
+    ///
+    /// See [`set_deadline`] for more information.
+    ///
+    /// [`set_deadline`]: #method.set_deadline
+    pub fn set_deadline_interval(&self, deadline: Duration, interval: Duration) -> io::Result<()> {
+        self.inner.set(non_zero(deadline), non_zero(interval), true)
+    }
+
+    /// Disarm the timer.
+    ///
+    /// Expirations that have already occurred, but have not yet been
+    /// [`read`], are discarded.
+    ///
+    /// [`read`]: #method.read
+    pub fn disarm(&self) -> io::Result<()> {
+        let zero = Duration::from_secs(0);
+        self.inner.set(zero, zero, false)
+    }
+
+    /// Returns the amount of time until the timer expires next, or `None` if
+    /// the timer is disarmed.
+    pub fn remaining(&self) -> io::Result<Option<Duration>> {
+        self.inner.get().map(|(value, _)| {
+            if value == Duration::from_secs(0) {
+                None
+            } else {
+                Some(value)
+            }
+        })
+    }
+
+    /// Returns the interval of a periodic timer, or `None` if the timer is a
+    /// one-shot timer or is disarmed.
+    pub fn interval(&self) -> io::Result<Option<Duration>> {
+        self.inner.get().map(|(_, interval)| {
+            if interval == Duration::from_secs(0) {
+                None
+            } else {
+                Some(interval)
+            }
+        })
+    }
+
+    /// Returns the number of times the timer expired since it was last armed
+    /// or since the last call to `read`.
+    ///
+    /// If the timer hasn't expired yet this returns a [`WouldBlock`] error.
+    ///
+    /// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
+    pub fn read(&self) -> io::Result<u64> {
+        self.inner.do_io(|inner| inner.read())
+    }
+}
+
+impl event::Source for Timer {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        self.inner.deregister(registry)
+    }
+}
+
+impl AsRawFd for Timer {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl IntoRawFd for Timer {
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_inner().into_raw_fd()
+    }
+}
+
+/// A zero value disarms a `timerfd`, so we use the smallest non-zero value
+/// instead.
+fn non_zero(duration: Duration) -> Duration {
+    if duration == Duration::from_secs(0) {
+        Duration::from_nanos(1)
+    } else {
+        duration
+    }
+}
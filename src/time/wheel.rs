@@ -0,0 +1,604 @@
+use crate::{Events, Token};
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Number of bits used to index the slots in a single level.
+const SLOT_BITS: usize = 6;
+/// Number of slots in a single level.
+const SLOTS: usize = 1 << SLOT_BITS;
+const SLOT_MASK: u64 = SLOTS as u64 - 1;
+/// Number of levels in the wheel.
+const LEVELS: usize = 6;
+/// Largest timeout, in milliseconds, that can be represented by the wheel,
+/// roughly 2.2 years. Longer timeouts are clamped to this value.
+const MAX_DURATION: u64 = (1 << (SLOT_BITS * LEVELS)) - 1;
+
+/// A hierarchical timer wheel, driven by [`Poll::poll`].
+///
+/// The wheel allows for a large number of timeouts without requiring a file
+/// descriptor or system call per timeout, making it well suited for things
+/// like per connection idle timeouts. Adding, resetting and cancelling a
+/// timeout are all O(1) operations.
+///
+/// Every [`Poll`] instance owns a single wheel, which can be accessed using
+/// [`Poll::timers`]. When polling, the timeout passed to the selector is
+/// automatically reduced to the time until the next timeout expires. Expired
+/// timeouts are returned as events with [`readable`] readiness and the token
+/// provided when adding the timeout, in the same [`Events`] as the events of
+/// the other event sources.
+///
+/// The wheel has a resolution of one millisecond, timeouts never expire early
+/// but may expire up to a millisecond late (on top of the usual scheduling
+/// delays).
+///
+/// [`Poll::poll`]: ../struct.Poll.html#method.poll
+/// [`Poll`]: ../struct.Poll.html
+/// [`Poll::timers`]: ../struct.Poll.html#method.timers
+/// [`readable`]: ../event/struct.Event.html#method.is_readable
+/// [`Events`]: ../event/struct.Events.html
+///
+/// # Notes
+///
+/// If there are more expired timeouts than room in [`Events`] the remaining
+/// timeouts are returned in the next call to [`Poll::poll`], which will not
+/// block in that case.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::time::Duration;
+///
+/// use mio::{Events, Poll, Token};
+///
+/// const IDLE_TIMEOUT: Token = Token(0);
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(8);
+///
+/// let timeout = poll.timers().insert(IDLE_TIMEOUT, Duration::from_millis(10));
+///
+/// // No timeout is passed, but `poll` will return once the timeout expires.
+/// poll.poll(&mut events, None)?;
+///
+/// let event = events.iter().next().unwrap();
+/// assert_eq!(event.token(), IDLE_TIMEOUT);
+/// assert!(event.is_readable());
+///
+/// // Once expired the timeout can no longer be cancelled.
+/// assert!(!poll.timers().cancel(timeout));
+/// #     Ok(())
+/// # }
+/// ```
+pub struct TimerWheel {
+    /// Point in time all deadlines are relative to.
+    start: Instant,
+    /// Number of milliseconds since `start` the wheel has processed.
+    elapsed: u64,
+    levels: [Level; LEVELS],
+    entries: Vec<Entry>,
+    /// Head of the list of unused `entries`.
+    free: Option<usize>,
+    /// Expired timeouts, waiting to be returned by `Poll::poll`.
+    expired: List,
+    /// Number of timeouts in the wheel, including the expired ones.
+    len: usize,
+}
+
+/// Handle to a timeout added to a [`TimerWheel`].
+///
+/// The handle can be used to [`reset`] or [`cancel`] the timeout. Once the
+/// timeout is returned by [`Poll::poll`], or cancelled, the handle is no longer
+/// valid and using it has no effect.
+///
+/// [`TimerWheel`]: struct.TimerWheel.html
+/// [`reset`]: struct.TimerWheel.html#method.reset
+/// [`cancel`]: struct.TimerWheel.html#method.cancel
+/// [`Poll::poll`]: ../struct.Poll.html#method.poll
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Timeout {
+    index: usize,
+    generation: u32,
+}
+
+#[derive(Copy, Clone)]
+struct Level {
+    /// Bit set of the slots that contain at least a single entry.
+    occupied: u64,
+    /// Head of the list of entries for each slot.
+    slots: [Option<usize>; SLOTS],
+}
+
+/// Doubly linked list of entries.
+#[derive(Copy, Clone, Default)]
+struct List {
+    head: Option<usize>,
+    tail: Option<usize>,
+}
+
+struct Entry {
+    token: Token,
+    /// Deadline in milliseconds since `TimerWheel.start`.
+    deadline: u64,
+    /// Incremented every time the entry is released, invalidating any
+    /// outstanding `Timeout` handles.
+    generation: u32,
+    location: Location,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Location {
+    /// Entry is unused and part of the free list.
+    Free,
+    /// Entry is in the slot `slot` of level `level`.
+    Wheel { level: usize, slot: usize },
+    /// Entry is in the expired list.
+    Expired,
+}
+
+impl TimerWheel {
+    // Only used by `Poll::new`.
+    #[cfg(feature = "os-poll")]
+    pub(crate) fn new() -> TimerWheel {
+        TimerWheel {
+            start: Instant::now(),
+            elapsed: 0,
+            levels: [Level {
+                occupied: 0,
+                slots: [None; SLOTS],
+            }; LEVELS],
+            entries: Vec::new(),
+            free: None,
+            expired: List::default(),
+            len: 0,
+        }
+    }
+
+    /// Add a timeout that expires after `timeout`, returning a [`Timeout`]
+    /// handle to it.
+    ///
+    /// Once expired an event with `token` is returned by [`Poll::poll`].
+    ///
+    /// [`Timeout`]: struct.Timeout.html
+    /// [`Poll::poll`]: ../struct.Poll.html#method.poll
+    pub fn insert(&mut self, token: Token, timeout: Duration) -> Timeout {
+        let deadline = self.deadline_after(timeout);
+        self.insert_entry(token, deadline)
+    }
+
+    /// Add a timeout that expires at `deadline`, returning a [`Timeout`]
+    /// handle to it.
+    ///
+    /// If `deadline` is in the past the timeout is returned by the next call
+    /// to [`Poll::poll`].
+    ///
+    /// [`Timeout`]: struct.Timeout.html
+    /// [`Poll::poll`]: ../struct.Poll.html#method.poll
+    pub fn insert_at(&mut self, token: Token, deadline: Instant) -> Timeout {
+        let deadline = if deadline <= Instant::now() {
+            // Already expired, even if the wheel hasn't caught up yet.
+            self.elapsed
+        } else {
+            self.deadline_at(deadline)
+        };
+        self.insert_entry(token, deadline)
+    }
+
+    /// Reset `timeout` to expire after `after`, keeping its token.
+    ///
+    /// Returns `false` if `timeout` already expired or was cancelled.
+    pub fn reset(&mut self, timeout: Timeout, after: Duration) -> bool {
+        if !self.is_valid(timeout) {
+            return false;
+        }
+
+        let deadline = self.deadline_after(after);
+        self.unlink(timeout.index);
+        self.schedule(timeout.index, deadline);
+        true
+    }
+
+    /// Cancel `timeout`.
+    ///
+    /// Returns `false` if `timeout` already expired or was cancelled.
+    pub fn cancel(&mut self, timeout: Timeout) -> bool {
+        if !self.is_valid(timeout) {
+            return false;
+        }
+
+        self.unlink(timeout.index);
+        self.release(timeout.index);
+        true
+    }
+
+    /// Returns the number of pending timeouts.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no pending timeouts.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the amount of time until the next timeout expires, or `None`
+    /// if there are no pending timeouts.
+    pub fn next_timeout(&self) -> Option<Duration> {
+        if self.expired.head.is_some() {
+            return Some(Duration::from_millis(0));
+        }
+
+        self.next_expiration().map(|(_, deadline)| {
+            let deadline = self.start + Duration::from_millis(deadline);
+            let now = Instant::now();
+            if deadline > now {
+                round_up_to_millis(deadline - now)
+            } else {
+                Duration::from_millis(0)
+            }
+        })
+    }
+
+    /// Returns the timeout to use when polling, that is the smallest of
+    /// `timeout` and the time until the next timeout expires.
+    pub(crate) fn poll_timeout(&self, timeout: Option<Duration>) -> Option<Duration> {
+        match (self.next_timeout(), timeout) {
+            (Some(next), Some(timeout)) if next < timeout => Some(next),
+            (Some(next), None) => Some(next),
+            (_, timeout) => timeout,
+        }
+    }
+
+    /// Move all expired timeouts into `events`, as long as there is room.
+    pub(crate) fn expire(&mut self, events: &mut Events) {
+        let now = self.now();
+        self.advance(now);
+
+        while let Some(index) = self.expired.head {
+            if !events.push_timeout(self.entries[index].token) {
+                break;
+            }
+            self.unlink(index);
+            self.release(index);
+        }
+    }
+
+    /// Process all slots up to `now`, moving expired entries into the expired
+    /// list and moving other entries to lower levels.
+    fn advance(&mut self, now: u64) {
+        while let Some((level, deadline)) = self.next_expiration() {
+            if deadline > now {
+                break;
+            }
+
+            let slot = slot_for(deadline, level);
+            self.elapsed = deadline;
+
+            let mut next = self.levels[level].slots[slot].take();
+            self.levels[level].occupied &= !(1 << slot);
+            while let Some(index) = next {
+                next = self.entries[index].next;
+                let deadline = self.entries[index].deadline;
+                // The slot is emptied above, so we don't have to unlink the
+                // entries.
+                self.entries[index].prev = None;
+                self.entries[index].next = None;
+                self.schedule(index, deadline);
+            }
+        }
+
+        if now > self.elapsed {
+            self.elapsed = now;
+        }
+    }
+
+    /// Returns the level and deadline of the first occupied slot, or `None` if
+    /// all levels are empty.
+    ///
+    /// Entries in lower levels always expire before entries in higher levels,
+    /// so the first occupied level is the one we're looking for.
+    fn next_expiration(&self) -> Option<(usize, u64)> {
+        self.levels.iter().enumerate().find_map(|(level, l)| {
+            l.next_expiration(level, self.elapsed)
+                .map(|deadline| (level, deadline))
+        })
+    }
+
+    fn insert_entry(&mut self, token: Token, deadline: u64) -> Timeout {
+        let index = match self.free {
+            Some(index) => {
+                self.free = self.entries[index].next;
+                self.entries[index].token = token;
+                self.entries[index].next = None;
+                index
+            }
+            None => {
+                self.entries.push(Entry {
+                    token,
+                    deadline,
+                    generation: 0,
+                    location: Location::Free,
+                    prev: None,
+                    next: None,
+                });
+                self.entries.len() - 1
+            }
+        };
+
+        self.len += 1;
+        self.schedule(index, deadline);
+        Timeout {
+            index,
+            generation: self.entries[index].generation,
+        }
+    }
+
+    /// Link the (unlinked) entry at `index` into the expired list if
+    /// `deadline` has passed, or into the correct slot otherwise.
+    fn schedule(&mut self, index: usize, deadline: u64) {
+        let entry = &mut self.entries[index];
+        entry.deadline = deadline;
+
+        if deadline <= self.elapsed {
+            entry.location = Location::Expired;
+            entry.prev = self.expired.tail;
+            match self.expired.tail {
+                Some(tail) => self.entries[tail].next = Some(index),
+                None => self.expired.head = Some(index),
+            }
+            self.expired.tail = Some(index);
+        } else {
+            let level = level_for(self.elapsed, deadline);
+            let slot = slot_for(deadline, level);
+            entry.location = Location::Wheel { level, slot };
+            entry.next = self.levels[level].slots[slot];
+            if let Some(head) = entry.next {
+                self.entries[head].prev = Some(index);
+            }
+            self.levels[level].slots[slot] = Some(index);
+            self.levels[level].occupied |= 1 << slot;
+        }
+    }
+
+    /// Remove the entry at `index` from the list it's currently in.
+    fn unlink(&mut self, index: usize) {
+        let Entry {
+            prev,
+            next,
+            location,
+            ..
+        } = self.entries[index];
+
+        if let Some(next) = next {
+            self.entries[next].prev = prev;
+        }
+        if let Some(prev) = prev {
+            self.entries[prev].next = next;
+        }
+
+        match location {
+            Location::Wheel { level, slot } => {
+                if prev.is_none() {
+                    self.levels[level].slots[slot] = next;
+                    if next.is_none() {
+                        self.levels[level].occupied &= !(1 << slot);
+                    }
+                }
+            }
+            Location::Expired => {
+                if prev.is_none() {
+                    self.expired.head = next;
+                }
+                if next.is_none() {
+                    self.expired.tail = prev;
+                }
+            }
+            Location::Free => unreachable!("unlinking unused timer entry"),
+        }
+
+        self.entries[index].prev = None;
+        self.entries[index].next = None;
+    }
+
+    /// Add the (unlinked) entry at `index` to the free list.
+    fn release(&mut self, index: usize) {
+        let entry = &mut self.entries[index];
+        entry.generation = entry.generation.wrapping_add(1);
+        entry.location = Location::Free;
+        entry.next = self.free;
+        self.free = Some(index);
+        self.len -= 1;
+    }
+
+    fn is_valid(&self, timeout: Timeout) -> bool {
+        match self.entries.get(timeout.index) {
+            Some(entry) => {
+                entry.generation == timeout.generation && entry.location != Location::Free
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the current time in milliseconds since `start`, rounded down.
+    fn now(&self) -> u64 {
+        (Instant::now() - self.start).as_millis() as u64
+    }
+
+    fn deadline_after(&self, timeout: Duration) -> u64 {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.deadline_at(deadline),
+            None => self.elapsed + MAX_DURATION,
+        }
+    }
+
+    /// Converts `deadline` into milliseconds since `start`, rounded up.
+    fn deadline_at(&self, deadline: Instant) -> u64 {
+        if deadline <= self.start {
+            return 0;
+        }
+
+        let deadline = round_up_to_millis(deadline - self.start).as_millis();
+        let max = self.elapsed + MAX_DURATION;
+        if deadline >= u128::from(max) {
+            max
+        } else {
+            deadline as u64
+        }
+    }
+}
+
+impl fmt::Debug for TimerWheel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TimerWheel")
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl Level {
+    /// Returns the deadline of the first occupied slot in this level.
+    fn next_expiration(&self, level: usize, elapsed: u64) -> Option<u64> {
+        if self.occupied == 0 {
+            return None;
+        }
+
+        let slot_range = slot_range(level);
+        let level_range = slot_range << SLOT_BITS;
+        let now_slot = slot_for(elapsed, level);
+        let zeros = self.occupied.rotate_right(now_slot as u32).trailing_zeros() as usize;
+        let slot = (zeros + now_slot) % SLOTS;
+
+        let level_start = elapsed & !(level_range - 1);
+        let mut deadline = level_start + slot as u64 * slot_range;
+        if deadline <= elapsed {
+            // The slot is "before" the current time in this level, so it
+            // refers to the next rotation of the level.
+            deadline += level_range;
+        }
+        Some(deadline)
+    }
+}
+
+/// Returns the level at which an entry with `deadline` must be placed.
+fn level_for(elapsed: u64, deadline: u64) -> usize {
+    let mut masked = (elapsed ^ deadline) | SLOT_MASK;
+    if masked >= MAX_DURATION {
+        masked = MAX_DURATION - 1;
+    }
+    let significant = 63 - masked.leading_zeros() as usize;
+    significant / SLOT_BITS
+}
+
+fn slot_for(deadline: u64, level: usize) -> usize {
+    ((deadline >> (level * SLOT_BITS)) & SLOT_MASK) as usize
+}
+
+/// Number of milliseconds covered by a single slot at `level`.
+fn slot_range(level: usize) -> u64 {
+    1 << (level * SLOT_BITS)
+}
+
+fn round_up_to_millis(duration: Duration) -> Duration {
+    let millis = Duration::from_millis(duration.as_millis() as u64);
+    if millis < duration {
+        millis + Duration::from_millis(1)
+    } else {
+        millis
+    }
+}
+
+#[cfg(all(test, feature = "os-poll"))]
+mod tests {
+    use super::{TimerWheel, MAX_DURATION};
+    use crate::Token;
+
+    /// Advance `wheel` to `now` and remove all expired timeouts.
+    fn expire(wheel: &mut TimerWheel, now: u64) -> Vec<Token> {
+        wheel.advance(now);
+        let mut expired = Vec::new();
+        while let Some(index) = wheel.expired.head {
+            expired.push(wheel.entries[index].token);
+            wheel.unlink(index);
+            wheel.release(index);
+        }
+        expired
+    }
+
+    #[test]
+    fn expires_in_order_across_levels() {
+        let mut wheel = TimerWheel::new();
+        // Deadlines in levels 0 through 5.
+        let deadlines = [1, 63, 64, 100, 4_095, 4_096, 300_000, 20_000_000, 1 << 33];
+        for (i, deadline) in deadlines.iter().enumerate().rev() {
+            let _ = wheel.insert_entry(Token(i), *deadline);
+        }
+        assert_eq!(wheel.len(), deadlines.len());
+
+        for (i, deadline) in deadlines.iter().enumerate() {
+            assert_eq!(
+                wheel.next_expiration().map(|(_, d)| d <= *deadline),
+                Some(true)
+            );
+            assert!(expire(&mut wheel, deadline - 1).is_empty());
+            assert_eq!(expire(&mut wheel, *deadline), vec![Token(i)]);
+        }
+        assert!(wheel.is_empty());
+        assert_eq!(wheel.next_expiration(), None);
+    }
+
+    #[test]
+    fn expires_all_at_once() {
+        let mut wheel = TimerWheel::new();
+        for i in 0..1_000 {
+            let _ = wheel.insert_entry(Token(i), (i as u64 * 7_919) % 100_000 + 1);
+        }
+
+        let mut expired = expire(&mut wheel, 100_000);
+        expired.sort();
+        assert_eq!(expired, (0..1_000).map(Token).collect::<Vec<_>>());
+        assert!(wheel.is_empty());
+    }
+
+    #[test]
+    fn deadline_in_the_past() {
+        let mut wheel = TimerWheel::new();
+        assert!(expire(&mut wheel, 1_000).is_empty());
+
+        let _ = wheel.insert_entry(Token(0), 10);
+        let _ = wheel.insert_entry(Token(1), 1_000);
+        assert_eq!(expire(&mut wheel, 1_000), vec![Token(0), Token(1)]);
+    }
+
+    #[test]
+    fn cancel_and_reuse() {
+        let mut wheel = TimerWheel::new();
+        let timeout1 = wheel.insert_entry(Token(1), 100);
+        let timeout2 = wheel.insert_entry(Token(2), 100);
+        assert!(wheel.cancel(timeout1));
+        assert!(!wheel.cancel(timeout1));
+        assert_eq!(wheel.len(), 1);
+
+        // The entry of `timeout1` is reused, but the old handle must not
+        // affect the new timeout.
+        let timeout3 = wheel.insert_entry(Token(3), 200);
+        assert_ne!(timeout1, timeout3);
+        assert!(!wheel.cancel(timeout1));
+
+        assert_eq!(expire(&mut wheel, 100), vec![Token(2)]);
+        assert!(!wheel.cancel(timeout2));
+        assert_eq!(expire(&mut wheel, 200), vec![Token(3)]);
+    }
+
+    #[test]
+    fn clamps_long_deadlines() {
+        let mut wheel = TimerWheel::new();
+        let deadline = wheel.deadline_after(std::time::Duration::from_secs(u64::MAX));
+        assert_eq!(deadline, MAX_DURATION);
+        let _ = wheel.insert_entry(Token(0), deadline);
+        assert!(expire(&mut wheel, MAX_DURATION - 1).is_empty());
+        assert_eq!(expire(&mut wheel, MAX_DURATION), vec![Token(0)]);
+    }
+}
@@ -0,0 +1,682 @@
+//! Linux filesystem notifications using inotify.
+//!
+//! See [`Inotify`] for watching files and [`EventParser`] for parsing the
+//! notifications.
+//!
+//! [`Inotify`]: struct.Inotify.html
+//! [`EventParser`]: struct.EventParser.html
+
+use crate::io_source::IoSource;
+use crate::{event, sys, Interest, Registry, Token};
+
+use std::convert::TryInto;
+use std::ffi::{OsStr, OsString};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::path::{Path, PathBuf};
+use std::{fmt, ops};
+
+/// An inotify instance, watching files and directories for changes.
+///
+/// Once registered with [`READABLE`] interest, a readable event is returned
+/// when notifications are available, which can be read using [`read`] and
+/// parsed using an [`EventParser`].
+///
+/// [`READABLE`]: ../../struct.Interest.html#associatedconstant.READABLE
+/// [`read`]: #method.read
+/// [`EventParser`]: struct.EventParser.html
+///
+/// # Examples
+///
+/// Watching a directory for new files.
+///
+/// ```no_run
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use mio::unix::inotify::{EventKind, EventParser, Inotify, WatchMask};
+///
+/// let inotify = Inotify::new()?;
+/// inotify.add_watch("/var/spool/example", WatchMask::CREATE | WatchMask::MOVED_TO)?;
+///
+/// let mut parser = EventParser::new();
+/// let mut buf = [0; 4096];
+/// let n = inotify.read(&mut buf)?;
+/// for event in parser.events(&buf[..n]) {
+///     let event = event?;
+///     if let EventKind::Create = event.kind() {
+///         println!("new file: {:?}", event.name());
+///     }
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+pub struct Inotify {
+    inner: IoSource<File>,
+}
+
+impl Inotify {
+    /// Creates a new inotify instance, without any watches.
+    pub fn new() -> io::Result<Inotify> {
+        sys::inotify::new().map(|file| Inotify {
+            inner: IoSource::new(file),
+        })
+    }
+
+    /// Watches `path` for the events in `mask`.
+    ///
+    /// If `path` is already watched the mask of the existing watch is
+    /// replaced, unless [`WatchMask::MASK_ADD`] is included, and the same
+    /// watch descriptor is returned.
+    ///
+    /// [`WatchMask::MASK_ADD`]: struct.WatchMask.html#associatedconstant.MASK_ADD
+    pub fn add_watch<P: AsRef<Path>>(
+        &self,
+        path: P,
+        mask: WatchMask,
+    ) -> io::Result<WatchDescriptor> {
+        sys::inotify::add_watch(self.inner.as_raw_fd(), path.as_ref(), mask.0).map(WatchDescriptor)
+    }
+
+    /// Watches the directory `path` and all its subdirectories for the events
+    /// in `mask`.
+    ///
+    /// Returns the watch descriptor and path of all watched directories,
+    /// which can be used to map the [`Event::wd`] back to a path.
+    /// Subdirectories created after this call are not watched, include
+    /// [`WatchMask::CREATE`] in `mask` and call this function for new
+    /// directories to keep watching the entire tree. Symbolic links are not
+    /// followed.
+    ///
+    /// [`Event::wd`]: struct.Event.html#method.wd
+    /// [`WatchMask::CREATE`]: struct.WatchMask.html#associatedconstant.CREATE
+    pub fn add_watch_recursive<P: AsRef<Path>>(
+        &self,
+        path: P,
+        mask: WatchMask,
+    ) -> io::Result<Vec<(WatchDescriptor, PathBuf)>> {
+        let mask = mask | WatchMask::ONLYDIR | WatchMask::DONT_FOLLOW;
+        let mut watches = Vec::new();
+        let mut directories = vec![path.as_ref().to_path_buf()];
+        while let Some(dir) = directories.pop() {
+            let is_root = watches.is_empty();
+            let entries = match self.add_watch(&dir, mask).and_then(|wd| {
+                watches.push((wd, dir.clone()));
+                fs::read_dir(&dir)
+            }) {
+                Ok(entries) => entries,
+                // Subdirectories may be removed while we're walking the tree.
+                Err(ref err) if !is_root && err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            };
+            for entry in entries {
+                let entry = entry?;
+                if entry.file_type()?.is_dir() {
+                    directories.push(entry.path());
+                }
+            }
+        }
+        Ok(watches)
+    }
+
+    /// Removes the watch `wd`.
+    ///
+    /// An [`EventKind::Ignored`] event is generated for the removed watch.
+    ///
+    /// [`EventKind::Ignored`]: enum.EventKind.html#variant.Ignored
+    pub fn remove_watch(&self, wd: WatchDescriptor) -> io::Result<()> {
+        sys::inotify::remove_watch(self.inner.as_raw_fd(), wd.0)
+    }
+
+    /// Reads the available notifications into `buf`, see [`EventParser`] for
+    /// parsing them.
+    ///
+    /// `buf` must be large enough to hold at least a single event, i.e. at
+    /// least 16 bytes plus the longest file name (`NAME_MAX`, 255) plus one,
+    /// otherwise an error is returned.
+    ///
+    /// [`EventParser`]: struct.EventParser.html
+    pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.do_io(|inner| (&*inner).read(buf))
+    }
+}
+
+impl event::Source for Inotify {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        self.inner.deregister(registry)
+    }
+}
+
+impl fmt::Debug for Inotify {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Inotify")
+            .field("fd", &self.inner.as_raw_fd())
+            .finish()
+    }
+}
+
+impl IntoRawFd for Inotify {
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_inner().into_raw_fd()
+    }
+}
+
+impl AsRawFd for Inotify {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl FromRawFd for Inotify {
+    /// Converts a `RawFd` to an `Inotify` instance.
+    ///
+    /// # Notes
+    ///
+    /// The caller is responsible for ensuring that the file descriptor is in
+    /// non-blocking mode.
+    unsafe fn from_raw_fd(fd: RawFd) -> Inotify {
+        Inotify {
+            inner: IoSource::new(File::from_raw_fd(fd)),
+        }
+    }
+}
+
+/// Identifies a watch, returned by [`Inotify::add_watch`].
+///
+/// [`Inotify::add_watch`]: struct.Inotify.html#method.add_watch
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct WatchDescriptor(i32);
+
+/// The events to watch for, see [`Inotify::add_watch`].
+///
+/// [`Inotify::add_watch`]: struct.Inotify.html#method.add_watch
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct WatchMask(u32);
+
+const IN_MODIFY: u32 = 0x0000_0002;
+const IN_ATTRIB: u32 = 0x0000_0004;
+const IN_CLOSE_WRITE: u32 = 0x0000_0008;
+const IN_MOVED_FROM: u32 = 0x0000_0040;
+const IN_MOVED_TO: u32 = 0x0000_0080;
+const IN_CREATE: u32 = 0x0000_0100;
+const IN_DELETE: u32 = 0x0000_0200;
+const IN_DELETE_SELF: u32 = 0x0000_0400;
+const IN_MOVE_SELF: u32 = 0x0000_0800;
+const IN_Q_OVERFLOW: u32 = 0x0000_4000;
+const IN_IGNORED: u32 = 0x0000_8000;
+const IN_ONLYDIR: u32 = 0x0100_0000;
+const IN_DONT_FOLLOW: u32 = 0x0200_0000;
+const IN_MASK_ADD: u32 = 0x2000_0000;
+const IN_ISDIR: u32 = 0x4000_0000;
+
+impl WatchMask {
+    /// File was modified (`IN_MODIFY`).
+    pub const MODIFY: WatchMask = WatchMask(IN_MODIFY);
+
+    /// Metadata, e.g. permissions, changed (`IN_ATTRIB`).
+    pub const ATTRIB: WatchMask = WatchMask(IN_ATTRIB);
+
+    /// File opened for writing was closed (`IN_CLOSE_WRITE`).
+    pub const CLOSE_WRITE: WatchMask = WatchMask(IN_CLOSE_WRITE);
+
+    /// File was moved out of a watched directory (`IN_MOVED_FROM`).
+    pub const MOVED_FROM: WatchMask = WatchMask(IN_MOVED_FROM);
+
+    /// File was moved into a watched directory (`IN_MOVED_TO`).
+    pub const MOVED_TO: WatchMask = WatchMask(IN_MOVED_TO);
+
+    /// File was created in a watched directory (`IN_CREATE`).
+    pub const CREATE: WatchMask = WatchMask(IN_CREATE);
+
+    /// File was deleted from a watched directory (`IN_DELETE`).
+    pub const DELETE: WatchMask = WatchMask(IN_DELETE);
+
+    /// The watched file or directory itself was deleted (`IN_DELETE_SELF`).
+    pub const DELETE_SELF: WatchMask = WatchMask(IN_DELETE_SELF);
+
+    /// The watched file or directory itself was moved (`IN_MOVE_SELF`).
+    pub const MOVE_SELF: WatchMask = WatchMask(IN_MOVE_SELF);
+
+    /// Only watch the path if it's a directory (`IN_ONLYDIR`).
+    pub const ONLYDIR: WatchMask = WatchMask(IN_ONLYDIR);
+
+    /// Don't follow the path if it's a symbolic link (`IN_DONT_FOLLOW`).
+    pub const DONT_FOLLOW: WatchMask = WatchMask(IN_DONT_FOLLOW);
+
+    /// Add to the mask of an existing watch, rather than replacing it
+    /// (`IN_MASK_ADD`).
+    pub const MASK_ADD: WatchMask = WatchMask(IN_MASK_ADD);
+
+    /// Add together two `WatchMask`s.
+    ///
+    /// This does the same thing as the `BitOr` implementation, but is a
+    /// constant function.
+    #[allow(clippy::should_implement_trait)]
+    pub const fn add(self, other: WatchMask) -> WatchMask {
+        WatchMask(self.0 | other.0)
+    }
+
+    /// Returns true if all events in `other` are included in this mask.
+    pub const fn contains(self, other: WatchMask) -> bool {
+        (self.0 & other.0) == other.0
+    }
+
+    /// Returns the raw `IN_*` bits.
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+}
+
+impl ops::BitOr for WatchMask {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        self.add(other)
+    }
+}
+
+impl ops::BitOrAssign for WatchMask {
+    #[inline]
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+/// Size of `struct inotify_event`, without the name.
+const HEADER_LEN: usize = 16;
+
+/// Parser for the events read using [`Inotify::read`].
+///
+/// A [`MovedFrom`] event directly followed by a [`MovedTo`] event with the
+/// same cookie is returned as a single [`Moved`] event. The kernel writes
+/// both halves of a rename next to each other, but they can end up in
+/// different reads if the buffer fills up in between. To pair those moves as
+/// well, a `MovedFrom` event that is the last event in a buffer is held back
+/// until the events of the next read are parsed. If the next read doesn't
+/// start with the matching `MovedTo` event the held event is returned first,
+/// unpaired.
+///
+/// As the last event before the queue runs empty can also be an unpaired
+/// `MovedFrom` event, e.g. for a file moved to an unwatched directory, call
+/// [`flush`] once [`Inotify::read`] returns a [`WouldBlock`] error to get
+/// the held event.
+///
+/// [`Inotify::read`]: struct.Inotify.html#method.read
+/// [`MovedFrom`]: enum.EventKind.html#variant.MovedFrom
+/// [`MovedTo`]: enum.EventKind.html#variant.MovedTo
+/// [`Moved`]: enum.EventKind.html#variant.Moved
+/// [`flush`]: #method.flush
+/// [`WouldBlock`]: std::io::ErrorKind::WouldBlock
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use mio::unix::inotify::{EventParser, Inotify, WatchMask};
+/// use std::io;
+///
+/// let inotify = Inotify::new()?;
+/// inotify.add_watch("/var/spool/example", WatchMask::MOVED_FROM | WatchMask::MOVED_TO)?;
+///
+/// let mut parser = EventParser::new();
+/// let mut buf = [0; 4096];
+/// loop {
+///     let n = match inotify.read(&mut buf) {
+///         Ok(n) => n,
+///         Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => break,
+///         Err(err) => return Err(err.into()),
+///     };
+///     for event in parser.events(&buf[..n]) {
+///         println!("event: {:?}", event?);
+///     }
+/// }
+/// if let Some(event) = parser.flush() {
+///     println!("event: {:?}", event);
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct EventParser {
+    /// `MovedFrom` event at the end of the last parsed buffer.
+    pending: Option<HeldMove>,
+    /// The pending event while it's borrowed by `Events` or `flush`.
+    held: Option<HeldMove>,
+}
+
+/// An owned `MovedFrom` event, held between reads.
+#[derive(Debug)]
+struct HeldMove {
+    wd: i32,
+    mask: u32,
+    cookie: u32,
+    /// Empty if the event has no name.
+    name: OsString,
+}
+
+impl HeldMove {
+    fn name(&self) -> Option<&OsStr> {
+        if self.name.is_empty() {
+            None
+        } else {
+            Some(&self.name)
+        }
+    }
+
+    fn event(&self) -> Event<'_> {
+        Event {
+            wd: WatchDescriptor(self.wd),
+            mask: self.mask,
+            name: self.name(),
+            kind: EventKind::MovedFrom {
+                cookie: self.cookie,
+            },
+        }
+    }
+}
+
+impl EventParser {
+    /// Creates a new parser.
+    pub fn new() -> EventParser {
+        EventParser::default()
+    }
+
+    /// Returns an iterator over the events in `buf`, e.g. read using
+    /// [`Inotify::read`].
+    ///
+    /// If the iterator is dropped before returning all events, the remaining
+    /// events in `buf` are lost.
+    ///
+    /// [`Inotify::read`]: struct.Inotify.html#method.read
+    pub fn events<'a>(&'a mut self, buf: &'a [u8]) -> Events<'a> {
+        self.held = self.pending.take();
+        Events {
+            buf,
+            held: self.held.as_ref(),
+            pending: &mut self.pending,
+        }
+    }
+
+    /// Returns the `MovedFrom` event held back from the last parsed buffer,
+    /// if any, as an unpaired event.
+    pub fn flush(&mut self) -> Option<Event<'_>> {
+        self.held = self.pending.take();
+        self.held.as_ref().map(HeldMove::event)
+    }
+}
+
+/// Iterator over inotify events, see [`EventParser::events`].
+///
+/// Returns an error if an event is truncated, after which iteration stops.
+///
+/// [`EventParser::events`]: struct.EventParser.html#method.events
+#[derive(Debug)]
+pub struct Events<'a> {
+    buf: &'a [u8],
+    /// `MovedFrom` event held from the previous buffer, not yet returned.
+    held: Option<&'a HeldMove>,
+    pending: &'a mut Option<HeldMove>,
+}
+
+/// `struct inotify_event`.
+struct RawEvent<'a> {
+    wd: i32,
+    mask: u32,
+    cookie: u32,
+    name: Option<&'a OsStr>,
+}
+
+impl<'a> RawEvent<'a> {
+    /// Returns the `Moved` event if this is the `MovedTo` half of the move
+    /// with `cookie`.
+    fn moved_to(
+        &self,
+        cookie: u32,
+        from: i32,
+        from_name: Option<&'a OsStr>,
+    ) -> Option<Event<'a>> {
+        if self.mask & IN_MOVED_TO != 0 && self.cookie == cookie {
+            Some(Event {
+                wd: WatchDescriptor(self.wd),
+                mask: self.mask,
+                name: self.name,
+                kind: EventKind::Moved {
+                    cookie,
+                    from: WatchDescriptor(from),
+                    from_name,
+                },
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> Events<'a> {
+    fn next_raw(&mut self) -> Option<io::Result<RawEvent<'a>>> {
+        let buf = self.buf;
+        if buf.is_empty() {
+            return None;
+        }
+        if buf.len() < HEADER_LEN {
+            self.buf = &[];
+            return Some(Err(truncated()));
+        }
+        let len = read_u32(buf, 12) as usize;
+        if buf.len() - HEADER_LEN < len {
+            self.buf = &[];
+            return Some(Err(truncated()));
+        }
+        self.buf = &buf[HEADER_LEN + len..];
+        // The name is padded with null bytes.
+        let name = &buf[HEADER_LEN..HEADER_LEN + len];
+        let name = match name.iter().position(|&b| b == 0) {
+            Some(0) => None,
+            Some(end) => Some(OsStr::from_bytes(&name[..end])),
+            None if name.is_empty() => None,
+            None => Some(OsStr::from_bytes(name)),
+        };
+        Some(Ok(RawEvent {
+            wd: read_u32(buf, 0) as i32,
+            mask: read_u32(buf, 4),
+            cookie: read_u32(buf, 8),
+            name,
+        }))
+    }
+}
+
+impl<'a> Iterator for Events<'a> {
+    type Item = io::Result<Event<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(held) = self.held.take() {
+            // Pair the move held from the previous buffer with the first
+            // event, if it's the other half.
+            let rest = self.buf;
+            if let Some(Ok(to)) = self.next_raw() {
+                if let Some(moved) = to.moved_to(held.cookie, held.wd, held.name()) {
+                    return Some(Ok(moved));
+                }
+            }
+            self.buf = rest;
+            return Some(Ok(held.event()));
+        }
+
+        let raw = match self.next_raw()? {
+            Ok(raw) => raw,
+            Err(err) => return Some(Err(err)),
+        };
+        let kind = if raw.mask & IN_MOVED_FROM != 0 {
+            if self.buf.is_empty() {
+                // The other half may be in the next read, hold on to the
+                // event until then.
+                *self.pending = Some(HeldMove {
+                    wd: raw.wd,
+                    mask: raw.mask,
+                    cookie: raw.cookie,
+                    name: raw.name.map(OsStr::to_os_string).unwrap_or_default(),
+                });
+                return None;
+            }
+            // Pair the move with the next event, if it's the other half.
+            let rest = self.buf;
+            if let Some(Ok(to)) = self.next_raw() {
+                if let Some(moved) = to.moved_to(raw.cookie, raw.wd, raw.name) {
+                    return Some(Ok(moved));
+                }
+            }
+            self.buf = rest;
+            EventKind::MovedFrom { cookie: raw.cookie }
+        } else if raw.mask & IN_MOVED_TO != 0 {
+            EventKind::MovedTo { cookie: raw.cookie }
+        } else if raw.mask & IN_CREATE != 0 {
+            EventKind::Create
+        } else if raw.mask & IN_MODIFY != 0 {
+            EventKind::Modify
+        } else if raw.mask & IN_DELETE != 0 {
+            EventKind::Delete
+        } else if raw.mask & IN_Q_OVERFLOW != 0 {
+            EventKind::Overflow
+        } else if raw.mask & IN_IGNORED != 0 {
+            EventKind::Ignored
+        } else {
+            EventKind::Other
+        };
+        Some(Ok(Event {
+            wd: WatchDescriptor(raw.wd),
+            mask: raw.mask,
+            name: raw.name,
+            kind,
+        }))
+    }
+}
+
+fn truncated() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "truncated inotify event")
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_ne_bytes(buf[offset..offset + 4].try_into().unwrap())
+}
+
+/// A single inotify event, see [`EventParser`].
+///
+/// [`EventParser`]: struct.EventParser.html
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Event<'a> {
+    wd: WatchDescriptor,
+    mask: u32,
+    name: Option<&'a OsStr>,
+    kind: EventKind<'a>,
+}
+
+impl<'a> Event<'a> {
+    /// Returns the watch the event is for. For [`Moved`] events this is the
+    /// watch of the directory the file was moved to.
+    ///
+    /// For [`Overflow`] events this is invalid.
+    ///
+    /// [`Moved`]: enum.EventKind.html#variant.Moved
+    /// [`Overflow`]: enum.EventKind.html#variant.Overflow
+    pub fn wd(&self) -> WatchDescriptor {
+        self.wd
+    }
+
+    /// Returns the raw `IN_*` mask of the event.
+    pub fn mask(&self) -> u32 {
+        self.mask
+    }
+
+    /// Returns the name of the file, relative to the watched directory, or
+    /// `None` if the event is for the watched file or directory itself. For
+    /// [`Moved`] events this is the new name.
+    ///
+    /// [`Moved`]: enum.EventKind.html#variant.Moved
+    pub fn name(&self) -> Option<&'a OsStr> {
+        self.name
+    }
+
+    /// Returns the kind of event.
+    pub fn kind(&self) -> EventKind<'a> {
+        self.kind
+    }
+
+    /// Returns `true` if the event is about a directory (`IN_ISDIR`).
+    pub fn is_dir(&self) -> bool {
+        self.mask & IN_ISDIR != 0
+    }
+}
+
+/// The kind of an inotify [`Event`].
+///
+/// [`Event`]: struct.Event.html
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EventKind<'a> {
+    /// A file was created (`IN_CREATE`).
+    Create,
+    /// A file was modified (`IN_MODIFY`).
+    Modify,
+    /// A file was deleted (`IN_DELETE`).
+    Delete,
+    /// A file was moved out of a watched directory (`IN_MOVED_FROM`), without
+    /// a matching [`MovedTo`] event, e.g. because it was moved to an
+    /// unwatched directory.
+    ///
+    /// [`MovedTo`]: #variant.MovedTo
+    MovedFrom {
+        /// Cookie to pair this event with a `MovedTo` event.
+        cookie: u32,
+    },
+    /// A file was moved into a watched directory (`IN_MOVED_TO`), without a
+    /// matching [`MovedFrom`] event, e.g. because it was moved from an
+    /// unwatched directory.
+    ///
+    /// [`MovedFrom`]: #variant.MovedFrom
+    MovedTo {
+        /// Cookie to pair this event with a `MovedFrom` event.
+        cookie: u32,
+    },
+    /// A file was moved (renamed) within or between watched directories, i.e.
+    /// a paired `IN_MOVED_FROM` and `IN_MOVED_TO` event.
+    Moved {
+        /// Cookie of the move.
+        cookie: u32,
+        /// The watch of the directory the file was moved from.
+        from: WatchDescriptor,
+        /// The old name of the file.
+        from_name: Option<&'a OsStr>,
+    },
+    /// The event queue overflowed and events were dropped
+    /// (`IN_Q_OVERFLOW`).
+    Overflow,
+    /// The watch was removed (`IN_IGNORED`), either explicitly or because the
+    /// watched file was deleted.
+    Ignored,
+    /// Any other event, see [`Event::mask`].
+    ///
+    /// [`Event::mask`]: struct.Event.html#method.mask
+    Other,
+}
@@ -0,0 +1,592 @@
+//! Linux netlink sockets.
+//!
+//! See [`Netlink`] for the socket and [`messages`] for parsing the received
+//! messages.
+//!
+//! [`Netlink`]: struct.Netlink.html
+//! [`messages`]: fn.messages.html
+
+use crate::io_source::IoSource;
+use crate::{event, sys, Interest, Registry, Token};
+
+use std::convert::TryInto;
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+
+/// A Linux netlink (`AF_NETLINK`) socket.
+///
+/// Netlink sockets are used to communicate with the kernel, e.g. to request
+/// the configured interfaces or to be notified of changes to them using the
+/// `NETLINK_ROUTE` protocol.
+///
+/// Each [`recv`] call receives a single datagram, which can contain multiple
+/// netlink messages, see [`messages`].
+///
+/// [`recv`]: #method.recv
+/// [`messages`]: fn.messages.html
+///
+/// # Examples
+///
+/// Receiving link (interface) change notifications.
+///
+/// ```no_run
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use mio::unix::netlink::{self, Netlink, RouteMessage};
+///
+/// const NETLINK_ROUTE: i32 = 0;
+/// const RTNLGRP_LINK: u32 = 1;
+///
+/// let socket = Netlink::new(NETLINK_ROUTE)?;
+/// socket.bind(0, 0)?;
+/// socket.join_group(RTNLGRP_LINK)?;
+///
+/// let mut buf = [0; 8192];
+/// let n = socket.recv(&mut buf)?;
+/// for message in netlink::messages(&buf[..n]) {
+///     if let Some(RouteMessage::NewLink(link)) = message?.route()? {
+///         println!("interface {:?} up: {}", link.name, link.is_up());
+///     }
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+pub struct Netlink {
+    inner: IoSource<File>,
+}
+
+impl Netlink {
+    fn new_inner(socket: File) -> Netlink {
+        Netlink {
+            inner: IoSource::new(socket),
+        }
+    }
+
+    /// Creates a new netlink socket for `protocol`, e.g. `NETLINK_ROUTE` or
+    /// `NETLINK_GENERIC`.
+    pub fn new(protocol: i32) -> io::Result<Netlink> {
+        sys::netlink::new(protocol).map(Netlink::new_inner)
+    }
+
+    /// Binds the socket to `port_id`, subscribing to the multicast `groups`
+    /// bitmask (only supporting the first 32 groups).
+    ///
+    /// If `port_id` is 0 the kernel assigns a unique port id, see
+    /// [`port_id`]. Use [`join_group`] to subscribe to a multicast group by
+    /// number.
+    ///
+    /// [`port_id`]: #method.port_id
+    /// [`join_group`]: #method.join_group
+    pub fn bind(&self, port_id: u32, groups: u32) -> io::Result<()> {
+        sys::netlink::bind(self.inner.as_raw_fd(), port_id, groups)
+    }
+
+    /// Returns the port id this socket is bound to.
+    pub fn port_id(&self) -> io::Result<u32> {
+        sys::netlink::local_addr(self.inner.as_raw_fd()).map(|(port_id, _)| port_id)
+    }
+
+    /// Subscribes to the multicast `group`, e.g. `RTNLGRP_LINK`.
+    pub fn join_group(&self, group: u32) -> io::Result<()> {
+        sys::netlink::join_group(self.inner.as_raw_fd(), group)
+    }
+
+    /// Unsubscribes from the multicast `group`.
+    pub fn leave_group(&self, group: u32) -> io::Result<()> {
+        sys::netlink::leave_group(self.inner.as_raw_fd(), group)
+    }
+
+    /// Sends the netlink message(s) in `buf` to the kernel.
+    ///
+    /// On success, returns the number of bytes written. See [`encode`] for
+    /// creating a message.
+    ///
+    /// [`encode`]: fn.encode.html
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.send_to(buf, 0, 0)
+    }
+
+    /// Sends the netlink message(s) in `buf` to `port_id` and the multicast
+    /// `groups` bitmask.
+    ///
+    /// On success, returns the number of bytes written.
+    pub fn send_to(&self, buf: &[u8], port_id: u32, groups: u32) -> io::Result<usize> {
+        self.inner
+            .do_io(|inner| sys::netlink::send_to(inner.as_raw_fd(), buf, port_id, groups))
+    }
+
+    /// Receives a single datagram.
+    ///
+    /// On success, returns the number of bytes read. If `buf` is too small to
+    /// hold the datagram the remainder is discarded.
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.recv_from(buf).map(|(n, _)| n)
+    }
+
+    /// Receives a single datagram.
+    ///
+    /// On success, returns the number of bytes read and the port id of the
+    /// sender, which is 0 for the kernel.
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, u32)> {
+        self.inner
+            .do_io(|inner| sys::netlink::recv_from(inner.as_raw_fd(), buf))
+            .map(|(n, port_id, _)| (n, port_id))
+    }
+}
+
+impl event::Source for Netlink {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        self.inner.deregister(registry)
+    }
+}
+
+impl fmt::Debug for Netlink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut builder = f.debug_struct("Netlink");
+        builder.field("fd", &self.inner.as_raw_fd());
+        if let Ok(port_id) = self.port_id() {
+            builder.field("port_id", &port_id);
+        }
+        builder.finish()
+    }
+}
+
+impl IntoRawFd for Netlink {
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_inner().into_raw_fd()
+    }
+}
+
+impl AsRawFd for Netlink {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl FromRawFd for Netlink {
+    /// Converts a `RawFd` to a `Netlink` socket.
+    ///
+    /// # Notes
+    ///
+    /// The caller is responsible for ensuring that the socket is in
+    /// non-blocking mode.
+    unsafe fn from_raw_fd(fd: RawFd) -> Netlink {
+        Netlink::new_inner(File::from_raw_fd(fd))
+    }
+}
+
+/// Size of `struct nlmsghdr`.
+const HEADER_LEN: usize = 16;
+
+const NLMSG_ERROR: u16 = 2;
+const NLMSG_DONE: u16 = 3;
+
+const RTM_NEWLINK: u16 = 16;
+const RTM_NEWADDR: u16 = 20;
+const RTM_NEWROUTE: u16 = 24;
+
+const IFLA_ADDRESS: u16 = 1;
+const IFLA_IFNAME: u16 = 3;
+const IFLA_MTU: u16 = 4;
+
+const IFA_ADDRESS: u16 = 1;
+const IFA_LOCAL: u16 = 2;
+const IFA_LABEL: u16 = 3;
+
+const RTA_DST: u16 = 1;
+const RTA_OIF: u16 = 4;
+const RTA_GATEWAY: u16 = 5;
+const RTA_PRIORITY: u16 = 6;
+const RTA_TABLE: u16 = 15;
+
+const IFF_UP: u32 = 1;
+
+/// Rounds `len` up to the netlink alignment of 4 bytes.
+fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn read_u16(buf: &[u8], offset: usize) -> u16 {
+    u16::from_ne_bytes(buf[offset..offset + 2].try_into().unwrap())
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_ne_bytes(buf[offset..offset + 4].try_into().unwrap())
+}
+
+/// Encodes a single netlink message, i.e. `struct nlmsghdr` followed by
+/// `payload`, to send using [`Netlink::send`].
+///
+/// [`Netlink::send`]: struct.Netlink.html#method.send
+pub fn encode(kind: u16, flags: u16, sequence: u32, payload: &[u8]) -> Vec<u8> {
+    let len = HEADER_LEN + payload.len();
+    let mut buf = Vec::with_capacity(align(len));
+    buf.extend_from_slice(&(len as u32).to_ne_bytes());
+    buf.extend_from_slice(&kind.to_ne_bytes());
+    buf.extend_from_slice(&flags.to_ne_bytes());
+    buf.extend_from_slice(&sequence.to_ne_bytes());
+    // Port id, filled in by the kernel.
+    buf.extend_from_slice(&0u32.to_ne_bytes());
+    buf.extend_from_slice(payload);
+    buf.resize(align(len), 0);
+    buf
+}
+
+/// Returns an iterator over the netlink messages in `buf`, e.g. a datagram
+/// received using [`Netlink::recv`].
+///
+/// [`Netlink::recv`]: struct.Netlink.html#method.recv
+pub fn messages(buf: &[u8]) -> Messages<'_> {
+    Messages { buf }
+}
+
+/// Iterator over netlink messages, see [`messages`].
+///
+/// Returns an error if a message is malformed, after which iteration stops.
+///
+/// [`messages`]: fn.messages.html
+#[derive(Debug)]
+pub struct Messages<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Iterator for Messages<'a> {
+    type Item = io::Result<Message<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+
+        let buf = self.buf;
+        if buf.len() < HEADER_LEN {
+            self.buf = &[];
+            return Some(Err(invalid_data("truncated netlink message header")));
+        }
+        let len = read_u32(buf, 0) as usize;
+        if len < HEADER_LEN || len > buf.len() {
+            self.buf = &[];
+            return Some(Err(invalid_data("invalid netlink message length")));
+        }
+        self.buf = &buf[align(len).min(buf.len())..];
+        Some(Ok(Message {
+            kind: read_u16(buf, 4),
+            flags: read_u16(buf, 6),
+            sequence: read_u32(buf, 8),
+            port_id: read_u32(buf, 12),
+            payload: &buf[HEADER_LEN..len],
+        }))
+    }
+}
+
+/// A single netlink message.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Message<'a> {
+    kind: u16,
+    flags: u16,
+    sequence: u32,
+    port_id: u32,
+    payload: &'a [u8],
+}
+
+impl<'a> Message<'a> {
+    /// Returns the message type, e.g. `RTM_NEWLINK`.
+    pub fn kind(&self) -> u16 {
+        self.kind
+    }
+
+    /// Returns the message flags, e.g. `NLM_F_MULTI`.
+    pub fn flags(&self) -> u16 {
+        self.flags
+    }
+
+    /// Returns the sequence number.
+    pub fn sequence(&self) -> u32 {
+        self.sequence
+    }
+
+    /// Returns the port id of the sender.
+    pub fn port_id(&self) -> u32 {
+        self.port_id
+    }
+
+    /// Returns the payload, i.e. the message without the netlink header.
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    /// Returns `true` if this is a `NLMSG_DONE` message, ending a multipart
+    /// message (e.g. the response to a dump request).
+    pub fn is_done(&self) -> bool {
+        self.kind == NLMSG_DONE
+    }
+
+    /// Returns the error if this is a `NLMSG_ERROR` message. Returns `None`
+    /// for acknowledgements, i.e. error messages with an error code of 0.
+    pub fn error(&self) -> Option<io::Error> {
+        if self.kind != NLMSG_ERROR {
+            return None;
+        }
+        if self.payload.len() < 4 {
+            return Some(invalid_data("truncated netlink error message"));
+        }
+        match read_u32(self.payload, 0) as i32 {
+            0 => None,
+            errno => Some(io::Error::from_raw_os_error(-errno)),
+        }
+    }
+
+    /// Parses the message as a `NETLINK_ROUTE` message.
+    ///
+    /// Returns `None` if the message type isn't one of `RTM_NEWLINK`,
+    /// `RTM_NEWADDR` or `RTM_NEWROUTE`.
+    pub fn route(&self) -> io::Result<Option<RouteMessage>> {
+        match self.kind {
+            RTM_NEWLINK => Link::parse(self.payload).map(|link| Some(RouteMessage::NewLink(link))),
+            RTM_NEWADDR => {
+                Address::parse(self.payload).map(|addr| Some(RouteMessage::NewAddr(addr)))
+            }
+            RTM_NEWROUTE => {
+                Route::parse(self.payload).map(|route| Some(RouteMessage::NewRoute(route)))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+/// A parsed `NETLINK_ROUTE` message, see [`Message::route`].
+///
+/// [`Message::route`]: struct.Message.html#method.route
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RouteMessage {
+    /// A `RTM_NEWLINK` message, describing a new or changed link.
+    NewLink(Link),
+    /// A `RTM_NEWADDR` message, describing a new address.
+    NewAddr(Address),
+    /// A `RTM_NEWROUTE` message, describing a new route.
+    NewRoute(Route),
+}
+
+/// A link (network interface), from a `RTM_NEWLINK` message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Link {
+    /// The interface index.
+    pub index: u32,
+    /// The ARP hardware type, e.g. `ARPHRD_ETHER`.
+    pub link_type: u16,
+    /// The device flags, e.g. `IFF_UP`.
+    pub flags: u32,
+    /// The interface name (`IFLA_IFNAME`).
+    pub name: Option<String>,
+    /// The maximum transmission unit (`IFLA_MTU`).
+    pub mtu: Option<u32>,
+    /// The hardware address (`IFLA_ADDRESS`).
+    pub address: Option<Vec<u8>>,
+}
+
+impl Link {
+    fn parse(payload: &[u8]) -> io::Result<Link> {
+        // `struct ifinfomsg`.
+        if payload.len() < 16 {
+            return Err(invalid_data("truncated link message"));
+        }
+        let mut link = Link {
+            link_type: read_u16(payload, 2),
+            index: read_u32(payload, 4),
+            flags: read_u32(payload, 8),
+            name: None,
+            mtu: None,
+            address: None,
+        };
+        for attribute in attributes(&payload[16..]) {
+            let (kind, data) = attribute?;
+            match kind {
+                IFLA_IFNAME => link.name = Some(parse_string(data)),
+                IFLA_MTU if data.len() == 4 => link.mtu = Some(read_u32(data, 0)),
+                IFLA_ADDRESS => link.address = Some(data.to_vec()),
+                _ => {}
+            }
+        }
+        Ok(link)
+    }
+
+    /// Returns `true` if the interface is administratively up (`IFF_UP`).
+    pub fn is_up(&self) -> bool {
+        self.flags & IFF_UP != 0
+    }
+}
+
+/// An interface address, from a `RTM_NEWADDR` message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Address {
+    /// The address family, `AF_INET` or `AF_INET6`.
+    pub family: u8,
+    /// The length of the network prefix.
+    pub prefix_len: u8,
+    /// The address scope, e.g. `RT_SCOPE_HOST`.
+    pub scope: u8,
+    /// The index of the interface the address is assigned to.
+    pub index: u32,
+    /// The (peer) address (`IFA_ADDRESS`).
+    pub address: Option<IpAddr>,
+    /// The local address (`IFA_LOCAL`).
+    pub local: Option<IpAddr>,
+    /// The label of the address (`IFA_LABEL`).
+    pub label: Option<String>,
+}
+
+impl Address {
+    fn parse(payload: &[u8]) -> io::Result<Address> {
+        // `struct ifaddrmsg`.
+        if payload.len() < 8 {
+            return Err(invalid_data("truncated address message"));
+        }
+        let mut addr = Address {
+            family: payload[0],
+            prefix_len: payload[1],
+            scope: payload[3],
+            index: read_u32(payload, 4),
+            address: None,
+            local: None,
+            label: None,
+        };
+        for attribute in attributes(&payload[8..]) {
+            let (kind, data) = attribute?;
+            match kind {
+                IFA_ADDRESS => addr.address = parse_ip(data),
+                IFA_LOCAL => addr.local = parse_ip(data),
+                IFA_LABEL => addr.label = Some(parse_string(data)),
+                _ => {}
+            }
+        }
+        Ok(addr)
+    }
+}
+
+/// A route, from a `RTM_NEWROUTE` message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Route {
+    /// The address family, `AF_INET` or `AF_INET6`.
+    pub family: u8,
+    /// The length of the destination prefix.
+    pub destination_len: u8,
+    /// The routing table, e.g. `RT_TABLE_MAIN`.
+    pub table: u32,
+    /// The routing protocol, i.e. the origin of the route, e.g.
+    /// `RTPROT_KERNEL`.
+    pub protocol: u8,
+    /// The route scope, e.g. `RT_SCOPE_UNIVERSE`.
+    pub scope: u8,
+    /// The route type, e.g. `RTN_UNICAST`.
+    pub kind: u8,
+    /// The destination network (`RTA_DST`), `None` for the default route.
+    pub destination: Option<IpAddr>,
+    /// The gateway (`RTA_GATEWAY`).
+    pub gateway: Option<IpAddr>,
+    /// The index of the output interface (`RTA_OIF`).
+    pub output_index: Option<u32>,
+    /// The priority (metric) of the route (`RTA_PRIORITY`).
+    pub priority: Option<u32>,
+}
+
+impl Route {
+    fn parse(payload: &[u8]) -> io::Result<Route> {
+        // `struct rtmsg`.
+        if payload.len() < 12 {
+            return Err(invalid_data("truncated route message"));
+        }
+        let mut route = Route {
+            family: payload[0],
+            destination_len: payload[1],
+            table: payload[4] as u32,
+            protocol: payload[5],
+            scope: payload[6],
+            kind: payload[7],
+            destination: None,
+            gateway: None,
+            output_index: None,
+            priority: None,
+        };
+        for attribute in attributes(&payload[12..]) {
+            let (kind, data) = attribute?;
+            match kind {
+                RTA_DST => route.destination = parse_ip(data),
+                RTA_GATEWAY => route.gateway = parse_ip(data),
+                RTA_OIF if data.len() == 4 => route.output_index = Some(read_u32(data, 0)),
+                RTA_PRIORITY if data.len() == 4 => route.priority = Some(read_u32(data, 0)),
+                // Tables with an id above 255 are only stored here.
+                RTA_TABLE if data.len() == 4 => route.table = read_u32(data, 0),
+                _ => {}
+            }
+        }
+        Ok(route)
+    }
+}
+
+/// Returns an iterator over the route attributes (`struct rtattr`) in `buf`,
+/// returning the attribute type and data.
+fn attributes(mut buf: &[u8]) -> impl Iterator<Item = io::Result<(u16, &[u8])>> {
+    std::iter::from_fn(move || {
+        if buf.len() < 4 {
+            // Ignore any trailing padding.
+            return None;
+        }
+        let len = read_u16(buf, 0) as usize;
+        if len < 4 || len > buf.len() {
+            buf = &[];
+            return Some(Err(invalid_data("invalid route attribute length")));
+        }
+        let attribute = (read_u16(buf, 2), &buf[4..len]);
+        buf = &buf[align(len).min(buf.len())..];
+        Some(Ok(attribute))
+    })
+}
+
+fn parse_ip(data: &[u8]) -> Option<IpAddr> {
+    match data.len() {
+        4 => {
+            let octets: [u8; 4] = data.try_into().unwrap();
+            Some(IpAddr::V4(Ipv4Addr::from(octets)))
+        }
+        16 => {
+            let octets: [u8; 16] = data.try_into().unwrap();
+            Some(IpAddr::V6(Ipv6Addr::from(octets)))
+        }
+        _ => None,
+    }
+}
+
+/// Parses a NUL terminated string attribute.
+fn parse_string(data: &[u8]) -> String {
+    let data = match data.iter().position(|&b| b == 0) {
+        Some(end) => &data[..end],
+        None => data,
+    };
+    String::from_utf8_lossy(data).into_owned()
+}
@@ -0,0 +1,246 @@
+//! Linux packet sockets.
+
+use crate::io_source::IoSource;
+use crate::{event, sys, Interest, Registry, Token};
+
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::mem;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+
+pub use crate::sys::packet::SockFilter;
+
+/// A link-layer address, i.e. a `sockaddr_ll`.
+///
+/// Returned by [`PacketSocket::recv_from`] to describe the received frame and
+/// passed to [`PacketSocket::send_to`] to select the outgoing interface and
+/// destination.
+///
+/// [`PacketSocket::recv_from`]: struct.PacketSocket.html#method.recv_from
+/// [`PacketSocket::send_to`]: struct.PacketSocket.html#method.send_to
+#[derive(Copy, Clone)]
+pub struct LinkAddr {
+    inner: libc::sockaddr_ll,
+}
+
+impl LinkAddr {
+    /// Creates a new link-layer address for interface `ifindex`, `protocol`
+    /// (the Ethernet protocol id in host byte order, e.g. `0x0800` for IPv4)
+    /// and hardware address `addr`.
+    ///
+    /// Returns an error if `addr` is longer than 8 bytes.
+    pub fn new(ifindex: u32, protocol: u16, addr: &[u8]) -> io::Result<LinkAddr> {
+        let mut inner: libc::sockaddr_ll = unsafe { mem::zeroed() };
+        if addr.len() > inner.sll_addr.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "hardware address longer than 8 bytes",
+            ));
+        }
+        inner.sll_family = libc::AF_PACKET as libc::c_ushort;
+        inner.sll_protocol = protocol.to_be();
+        inner.sll_ifindex = ifindex as libc::c_int;
+        inner.sll_halen = addr.len() as libc::c_uchar;
+        inner.sll_addr[..addr.len()].copy_from_slice(addr);
+        Ok(LinkAddr { inner })
+    }
+
+    /// Returns the Ethernet protocol id of the frame, in host byte order.
+    pub fn protocol(&self) -> u16 {
+        u16::from_be(self.inner.sll_protocol)
+    }
+
+    /// Returns the index of the interface the frame was received on.
+    pub fn ifindex(&self) -> u32 {
+        self.inner.sll_ifindex as u32
+    }
+
+    /// Returns the ARP hardware type, e.g. `ARPHRD_ETHER`.
+    pub fn hatype(&self) -> u16 {
+        self.inner.sll_hatype
+    }
+
+    /// Returns the packet type, e.g. `PACKET_HOST` or `PACKET_OUTGOING`.
+    pub fn pkttype(&self) -> u8 {
+        self.inner.sll_pkttype
+    }
+
+    /// Returns the hardware address, e.g. the source MAC address of a
+    /// received frame.
+    pub fn addr(&self) -> &[u8] {
+        let len = (self.inner.sll_halen as usize).min(self.inner.sll_addr.len());
+        &self.inner.sll_addr[..len]
+    }
+}
+
+impl fmt::Debug for LinkAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LinkAddr")
+            .field("protocol", &self.protocol())
+            .field("ifindex", &self.ifindex())
+            .field("hatype", &self.hatype())
+            .field("pkttype", &self.pkttype())
+            .field("addr", &self.addr())
+            .finish()
+    }
+}
+
+/// A Linux packet (`AF_PACKET`) socket.
+///
+/// Packet sockets send and receive frames at the device driver level. A raw
+/// socket ([`new_raw`]) includes the link-layer header, a datagram socket
+/// ([`new_dgram`]) has it removed on receive and generated on send based on
+/// the [`LinkAddr`]. Creating a packet socket requires the `CAP_NET_RAW`
+/// capability.
+///
+/// [`new_raw`]: #method.new_raw
+/// [`new_dgram`]: #method.new_dgram
+/// [`LinkAddr`]: struct.LinkAddr.html
+///
+/// # Examples
+///
+/// Receiving all IPv4 frames on the loopback interface.
+///
+/// ```no_run
+/// # use std::error::Error;
+/// #
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use mio::unix::PacketSocket;
+///
+/// // Ethernet protocol id of IPv4.
+/// const ETH_P_IP: u16 = 0x0800;
+///
+/// let socket = PacketSocket::new_raw(ETH_P_IP)?;
+/// // The loopback interface generally has index 1.
+/// socket.bind(1, ETH_P_IP)?;
+///
+/// let mut buf = [0; 1500];
+/// let (n, addr) = socket.recv_from(&mut buf)?;
+/// println!("received {} bytes on interface {}", n, addr.ifindex());
+/// #     Ok(())
+/// # }
+/// ```
+pub struct PacketSocket {
+    inner: IoSource<File>,
+}
+
+impl PacketSocket {
+    fn new(socket: File) -> PacketSocket {
+        PacketSocket {
+            inner: IoSource::new(socket),
+        }
+    }
+
+    /// Creates a new `SOCK_RAW` packet socket receiving frames of `protocol`
+    /// (in host byte order), e.g. `ETH_P_ALL` for all protocols.
+    pub fn new_raw(protocol: u16) -> io::Result<PacketSocket> {
+        sys::packet::new(libc::SOCK_RAW, protocol.to_be()).map(PacketSocket::new)
+    }
+
+    /// Creates a new `SOCK_DGRAM` packet socket receiving frames of
+    /// `protocol` (in host byte order), e.g. `ETH_P_ALL` for all protocols.
+    pub fn new_dgram(protocol: u16) -> io::Result<PacketSocket> {
+        sys::packet::new(libc::SOCK_DGRAM, protocol.to_be()).map(PacketSocket::new)
+    }
+
+    /// Binds the socket to the interface with index `ifindex`, only receiving
+    /// frames of `protocol` (in host byte order) on that interface. An
+    /// `ifindex` of 0 matches any interface.
+    pub fn bind(&self, ifindex: u32, protocol: u16) -> io::Result<()> {
+        let addr = LinkAddr::new(ifindex, protocol, &[])?;
+        sys::packet::bind(self.inner.as_raw_fd(), &addr.inner)
+    }
+
+    /// Sends a frame to the given `target`.
+    ///
+    /// On success, returns the number of bytes written.
+    pub fn send_to(&self, buf: &[u8], target: &LinkAddr) -> io::Result<usize> {
+        self.inner
+            .do_io(|inner| sys::packet::send_to(inner.as_raw_fd(), buf, &target.inner))
+    }
+
+    /// Receives a single frame.
+    ///
+    /// On success, returns the number of bytes read and the link-layer
+    /// address describing the frame. If `buf` is too small to hold the frame
+    /// the remainder is discarded.
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, LinkAddr)> {
+        self.inner
+            .do_io(|inner| sys::packet::recv_from(inner.as_raw_fd(), buf))
+            .map(|(n, inner)| (n, LinkAddr { inner }))
+    }
+
+    /// Attaches a classic BPF program to the socket (`SO_ATTACH_FILTER`),
+    /// replacing any previously attached program. Only frames accepted by
+    /// the program are received.
+    ///
+    /// Note that frames queued before the program is attached are still
+    /// received.
+    pub fn attach_filter(&self, filter: &[SockFilter]) -> io::Result<()> {
+        sys::packet::attach_filter(self.inner.as_raw_fd(), filter)
+    }
+
+    /// Removes the BPF program attached using [`attach_filter`].
+    ///
+    /// [`attach_filter`]: #method.attach_filter
+    pub fn detach_filter(&self) -> io::Result<()> {
+        sys::packet::detach_filter(self.inner.as_raw_fd())
+    }
+}
+
+impl event::Source for PacketSocket {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        self.inner.deregister(registry)
+    }
+}
+
+impl fmt::Debug for PacketSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PacketSocket")
+            .field("fd", &self.inner.as_raw_fd())
+            .finish()
+    }
+}
+
+impl IntoRawFd for PacketSocket {
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_inner().into_raw_fd()
+    }
+}
+
+impl AsRawFd for PacketSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl FromRawFd for PacketSocket {
+    /// Converts a `RawFd` to a `PacketSocket`.
+    ///
+    /// # Notes
+    ///
+    /// The caller is responsible for ensuring that the socket is in
+    /// non-blocking mode.
+    unsafe fn from_raw_fd(fd: RawFd) -> PacketSocket {
+        PacketSocket::new(File::from_raw_fd(fd))
+    }
+}
@@ -0,0 +1,218 @@
+//! Child processes and their standard I/O pipes.
+//!
+//! [`Child`] wraps a [`std::process::Child`] as an event source which becomes
+//! readable once the process exits. The pipes to its standard input, output
+//! and error are available as non-blocking [`ChildStdin`], [`ChildStdout`]
+//! and [`ChildStderr`], so both the I/O and the lifecycle of the process can
+//! be driven by the same [`Poll`].
+//!
+//! [`Child`]: struct.Child.html
+//! [`std::process::Child`]: https://doc.rust-lang.org/std/process/struct.Child.html
+//! [`ChildStdin`]: struct.ChildStdin.html
+//! [`ChildStdout`]: struct.ChildStdout.html
+//! [`ChildStderr`]: struct.ChildStderr.html
+//! [`Poll`]: ../../struct.Poll.html
+
+use crate::unix::pipe;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::io_source::IoSource;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::{event, sys, Interest, Registry, Token};
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use std::io;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use std::process::{self, ExitStatus};
+
+/// A child process which can be registered with [`Poll`] to get notified once
+/// it exits.
+///
+/// Once the process exits an event with [`readable`] readiness is returned,
+/// after which [`try_wait`] can be used to collect its exit status.
+///
+/// [`Poll`]: ../../struct.Poll.html
+/// [`readable`]: ../../event/struct.Event.html#method.is_readable
+/// [`try_wait`]: #method.try_wait
+///
+/// # Notes
+///
+/// Events can be spurious, always use [`try_wait`] to determine whether the
+/// process actually exited.
+///
+/// # Implementation notes
+///
+/// This is only available on Linux and Android, where it is backed by a
+/// [pidfd]. On kernels that don't support pidfds (before Linux 5.3) it falls
+/// back to a [signalfd] receiving `SIGCHLD`, which requires `SIGCHLD` to be
+/// blocked in all threads. It is blocked for the calling thread when creating
+/// a `Child` and unblocked again once the last `Child` is dropped, see
+/// [`Signals`]. With this fallback an event is returned for a *single*
+/// registered `Child` once any child process exits, not necessarily the one
+/// that exited. So when multiple `Child`s are used, [`try_wait`] must be
+/// called on all of them after an event for any of them.
+///
+/// [pidfd]: http://man7.org/linux/man-pages/man2/pidfd_open.2.html
+/// [signalfd]: http://man7.org/linux/man-pages/man2/signalfd.2.html
+/// [`Signals`]: ../struct.Signals.html
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::process::{Command, Stdio};
+///
+/// use mio::unix::process::Child;
+/// use mio::{Events, Interest, Poll, Token};
+///
+/// const CHILD: Token = Token(0);
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(8);
+///
+/// let child = Command::new("true").stdin(Stdio::null()).spawn()?;
+/// let mut child = Child::from_std(child)?;
+/// poll.registry().register(&mut child, CHILD, Interest::READABLE)?;
+///
+/// let status = loop {
+///     poll.poll(&mut events, None)?;
+///     if let Some(status) = child.try_wait()? {
+///         break status;
+///     }
+/// };
+/// assert!(status.success());
+/// #     Ok(())
+/// # }
+/// ```
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[derive(Debug)]
+pub struct Child {
+    inner: process::Child,
+    notifier: IoSource<sys::process::ExitNotifier>,
+    /// The handle for writing to the child's standard input, if it has been
+    /// captured.
+    pub stdin: Option<ChildStdin>,
+    /// The handle for reading from the child's standard output, if it has
+    /// been captured.
+    pub stdout: Option<ChildStdout>,
+    /// The handle for reading from the child's standard error, if it has been
+    /// captured.
+    pub stderr: Option<ChildStderr>,
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl Child {
+    /// Creates a new `Child` from a standard `process::Child`.
+    ///
+    /// The captured standard I/O pipes of `child` are taken and converted
+    /// into their non-blocking counterparts.
+    pub fn from_std(mut child: process::Child) -> io::Result<Child> {
+        let notifier = sys::process::ExitNotifier::new(child.id())?;
+        let stdin = child.stdin.take().map(ChildStdin::from);
+        if let Some(stdin) = &stdin {
+            stdin.set_nonblocking(true)?;
+        }
+        let stdout = child.stdout.take().map(ChildStdout::from);
+        if let Some(stdout) = &stdout {
+            stdout.set_nonblocking(true)?;
+        }
+        let stderr = child.stderr.take().map(ChildStderr::from);
+        if let Some(stderr) = &stderr {
+            stderr.set_nonblocking(true)?;
+        }
+        Ok(Child {
+            inner: child,
+            notifier: IoSource::new(notifier),
+            stdin,
+            stdout,
+            stderr,
+        })
+    }
+
+    /// Returns the OS-assigned process identifier of the child process.
+    pub fn id(&self) -> u32 {
+        self.inner.id()
+    }
+
+    /// Forces the child process to exit, see [`process::Child::kill`].
+    ///
+    /// [`process::Child::kill`]: https://doc.rust-lang.org/std/process/struct.Child.html#method.kill
+    pub fn kill(&mut self) -> io::Result<()> {
+        self.inner.kill()
+    }
+
+    /// Attempts to collect the exit status of the child process, without
+    /// blocking.
+    ///
+    /// Returns `Ok(None)` if the process hasn't exited yet, in which case
+    /// another event will be returned once it does.
+    pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        self.notifier.reset();
+        self.inner.try_wait()
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl event::Source for Child {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.notifier.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.notifier.reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        self.notifier.deregister(registry)
+    }
+}
+
+/// A handle to the standard input of a child process.
+///
+/// This is the sending end of a [`pipe`]. Use [`Sender::from`] to convert a
+/// standard [`process::ChildStdin`], followed by [`Sender::set_nonblocking`].
+/// [`Child::from_std`] does both for the captured pipes.
+///
+/// [`pipe`]: ../pipe/index.html
+/// [`Sender::from`]: ../pipe/struct.Sender.html#impl-From%3CChildStdin%3E
+/// [`process::ChildStdin`]: https://doc.rust-lang.org/std/process/struct.ChildStdin.html
+/// [`Sender::set_nonblocking`]: ../pipe/struct.Sender.html#method.set_nonblocking
+/// [`Child::from_std`]: struct.Child.html#method.from_std
+pub type ChildStdin = pipe::Sender;
+
+/// A handle to the standard output of a child process.
+///
+/// This is the receiving end of a [`pipe`]. Use [`Receiver::from`] to convert
+/// a standard [`process::ChildStdout`], followed by
+/// [`Receiver::set_nonblocking`]. [`Child::from_std`] does both for the
+/// captured pipes.
+///
+/// [`pipe`]: ../pipe/index.html
+/// [`Receiver::from`]: ../pipe/struct.Receiver.html#impl-From%3CChildStdout%3E
+/// [`process::ChildStdout`]: https://doc.rust-lang.org/std/process/struct.ChildStdout.html
+/// [`Receiver::set_nonblocking`]: ../pipe/struct.Receiver.html#method.set_nonblocking
+/// [`Child::from_std`]: struct.Child.html#method.from_std
+pub type ChildStdout = pipe::Receiver;
+
+/// A handle to the standard error of a child process.
+///
+/// This is the receiving end of a [`pipe`]. Use [`Receiver::from`] to convert
+/// a standard [`process::ChildStderr`], followed by
+/// [`Receiver::set_nonblocking`]. [`Child::from_std`] does both for the
+/// captured pipes.
+///
+/// [`pipe`]: ../pipe/index.html
+/// [`Receiver::from`]: ../pipe/struct.Receiver.html#impl-From%3CChildStderr%3E
+/// [`process::ChildStderr`]: https://doc.rust-lang.org/std/process/struct.ChildStderr.html
+/// [`Receiver::set_nonblocking`]: ../pipe/struct.Receiver.html#method.set_nonblocking
+/// [`Child::from_std`]: struct.Child.html#method.from_std
+pub type ChildStderr = pipe::Receiver;
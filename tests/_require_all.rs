@@ -3,6 +3,7 @@
     feature = "os-util",
     feature = "tcp",
     feature = "udp",
-    feature = "uds"
+    feature = "uds",
+    feature = "time"
 )))]
 compile_error!("run main Mio tests with `--all-features`");
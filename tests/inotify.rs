@@ -0,0 +1,235 @@
+#![cfg(all(target_os = "linux", feature = "os-poll", feature = "os-util"))]
+
+use mio::unix::inotify::{EventKind, EventParser, Inotify, WatchMask};
+use mio::{Interest, Token};
+use std::ffi::OsStr;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+mod util;
+use util::{
+    assert_send, assert_socket_close_on_exec, assert_socket_non_blocking, assert_sync,
+    assert_would_block, expect_events, init_with_poll, temp_file, ExpectEvent,
+};
+
+const ID1: Token = Token(0);
+
+/// Creates a new, empty, temporary directory.
+fn temp_dir(name: &'static str) -> PathBuf {
+    let path = temp_file(name);
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).unwrap();
+    path
+}
+
+/// Encodes a `struct inotify_event`.
+fn raw_event(buf: &mut Vec<u8>, wd: i32, mask: u32, cookie: u32, name: &str) {
+    // Name including null byte, padded to a multiple of 4 bytes.
+    let len = if name.is_empty() {
+        0
+    } else {
+        (name.len() + 4) & !3
+    };
+    buf.extend_from_slice(&wd.to_ne_bytes());
+    buf.extend_from_slice(&mask.to_ne_bytes());
+    buf.extend_from_slice(&cookie.to_ne_bytes());
+    buf.extend_from_slice(&(len as u32).to_ne_bytes());
+    buf.extend_from_slice(name.as_bytes());
+    buf.resize(buf.len() + len - name.len(), 0);
+}
+
+#[test]
+fn is_send_and_sync() {
+    assert_send::<Inotify>();
+    assert_sync::<Inotify>();
+}
+
+#[test]
+fn inotify_smoke() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let dir = temp_dir("inotify_smoke");
+    let mut inotify = Inotify::new().unwrap();
+    assert_socket_non_blocking(&inotify);
+    assert_socket_close_on_exec(&inotify);
+    let mask = WatchMask::CREATE
+        | WatchMask::MODIFY
+        | WatchMask::DELETE
+        | WatchMask::MOVED_FROM
+        | WatchMask::MOVED_TO;
+    let wd = inotify.add_watch(&dir, mask).unwrap();
+    poll.registry()
+        .register(&mut inotify, ID1, Interest::READABLE)
+        .unwrap();
+    let mut parser = EventParser::new();
+    let mut buf = [0; 4096];
+    assert_would_block(inotify.read(&mut buf));
+
+    let mut file = fs::File::create(dir.join("config")).unwrap();
+    file.write_all(b"hello").unwrap();
+    drop(file);
+    fs::rename(dir.join("config"), dir.join("config.old")).unwrap();
+    fs::remove_file(dir.join("config.old")).unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::READABLE)],
+    );
+
+    let n = inotify.read(&mut buf).unwrap();
+    let parsed = parser
+        .events(&buf[..n])
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(parsed.len(), 4, "unexpected events: {:?}", parsed);
+    for event in &parsed {
+        assert_eq!(event.wd(), wd);
+        assert!(!event.is_dir());
+    }
+    let config = Some(OsStr::new("config"));
+    assert_eq!(parsed[0].kind(), EventKind::Create);
+    assert_eq!(parsed[0].name(), config);
+    assert_eq!(parsed[1].kind(), EventKind::Modify);
+    assert_eq!(parsed[1].name(), config);
+    match parsed[2].kind() {
+        EventKind::Moved {
+            from, from_name, ..
+        } => {
+            assert_eq!(from, wd);
+            assert_eq!(from_name, config);
+        }
+        kind => panic!("unexpected event: {:?}", kind),
+    }
+    assert_eq!(parsed[2].name(), Some(OsStr::new("config.old")));
+    assert_eq!(parsed[3].kind(), EventKind::Delete);
+    assert_eq!(parsed[3].name(), Some(OsStr::new("config.old")));
+    assert_would_block(inotify.read(&mut buf));
+
+    inotify.remove_watch(wd).unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::READABLE)],
+    );
+    let n = inotify.read(&mut buf).unwrap();
+    let event = parser.events(&buf[..n]).next().unwrap().unwrap();
+    assert_eq!(event.wd(), wd);
+    assert_eq!(event.kind(), EventKind::Ignored);
+    assert_eq!(event.name(), None);
+}
+
+#[test]
+fn inotify_recursive() {
+    let dir = temp_dir("inotify_recursive");
+    fs::create_dir_all(dir.join("a").join("b")).unwrap();
+    fs::create_dir_all(dir.join("c")).unwrap();
+    fs::File::create(dir.join("a").join("file")).unwrap();
+
+    let inotify = Inotify::new().unwrap();
+    let mut watches = inotify
+        .add_watch_recursive(&dir, WatchMask::CREATE)
+        .unwrap();
+    watches.sort_by(|a, b| a.1.cmp(&b.1));
+    let paths: Vec<_> = watches.iter().map(|(_, path)| path.clone()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            dir.clone(),
+            dir.join("a"),
+            dir.join("a").join("b"),
+            dir.join("c")
+        ]
+    );
+
+    fs::create_dir(dir.join("a").join("b").join("new")).unwrap();
+    let mut parser = EventParser::new();
+    let mut buf = [0; 4096];
+    let n = inotify.read(&mut buf).unwrap();
+    let event = parser.events(&buf[..n]).next().unwrap().unwrap();
+    assert_eq!(event.wd(), watches[2].0);
+    assert_eq!(event.kind(), EventKind::Create);
+    assert_eq!(event.name(), Some(OsStr::new("new")));
+    assert!(event.is_dir());
+
+    // Only directories can be watched recursively.
+    assert!(inotify
+        .add_watch_recursive(dir.join("a").join("file"), WatchMask::CREATE)
+        .is_err());
+}
+
+#[test]
+fn inotify_parse_events() {
+    const IN_MOVED_FROM: u32 = 0x40;
+    const IN_MOVED_TO: u32 = 0x80;
+    const IN_Q_OVERFLOW: u32 = 0x4000;
+
+    let mut buf = Vec::new();
+    raw_event(&mut buf, 1, IN_MOVED_FROM, 7, "from");
+    raw_event(&mut buf, 1, IN_MOVED_TO, 8, "to");
+    raw_event(&mut buf, -1, IN_Q_OVERFLOW, 0, "");
+
+    let mut parser = EventParser::new();
+    let mut events = parser.events(&buf);
+    // Cookies don't match, so the moves aren't paired.
+    let event = events.next().unwrap().unwrap();
+    assert_eq!(event.kind(), EventKind::MovedFrom { cookie: 7 });
+    assert_eq!(event.name(), Some(OsStr::new("from")));
+    let event = events.next().unwrap().unwrap();
+    assert_eq!(event.kind(), EventKind::MovedTo { cookie: 8 });
+    assert_eq!(event.name(), Some(OsStr::new("to")));
+    let event = events.next().unwrap().unwrap();
+    assert_eq!(event.kind(), EventKind::Overflow);
+    assert_eq!(event.name(), None);
+    assert!(events.next().is_none());
+
+    // Truncated event.
+    let mut events = parser.events(&buf[..20]);
+    assert!(events.next().unwrap().is_err());
+    assert!(events.next().is_none());
+
+    // A move split over two reads is still paired.
+    let mut buf = Vec::new();
+    raw_event(&mut buf, 1, IN_MOVED_FROM, 9, "from");
+    assert!(parser.events(&buf).next().is_none());
+    let mut buf = Vec::new();
+    raw_event(&mut buf, 2, IN_MOVED_TO, 9, "to");
+    raw_event(&mut buf, 2, IN_MOVED_FROM, 10, "other");
+    let mut events = parser.events(&buf);
+    let event = events.next().unwrap().unwrap();
+    match event.kind() {
+        EventKind::Moved {
+            cookie,
+            from,
+            from_name,
+        } => {
+            assert_eq!(cookie, 9);
+            assert_ne!(from, event.wd());
+            assert_eq!(from_name, Some(OsStr::new("from")));
+        }
+        kind => panic!("unexpected event: {:?}", kind),
+    }
+    assert_eq!(event.name(), Some(OsStr::new("to")));
+    assert!(events.next().is_none());
+
+    // Without the other half in the next read the held event is returned
+    // unpaired, before the events of that read.
+    let mut buf = Vec::new();
+    raw_event(&mut buf, 1, IN_MOVED_TO, 11, "new");
+    let mut events = parser.events(&buf);
+    let event = events.next().unwrap().unwrap();
+    assert_eq!(event.kind(), EventKind::MovedFrom { cookie: 10 });
+    assert_eq!(event.name(), Some(OsStr::new("other")));
+    let event = events.next().unwrap().unwrap();
+    assert_eq!(event.kind(), EventKind::MovedTo { cookie: 11 });
+    assert!(events.next().is_none());
+
+    // Or when flushing the parser.
+    let mut buf = Vec::new();
+    raw_event(&mut buf, 1, IN_MOVED_FROM, 12, "last");
+    assert!(parser.events(&buf).next().is_none());
+    let event = parser.flush().unwrap();
+    assert_eq!(event.kind(), EventKind::MovedFrom { cookie: 12 });
+    assert_eq!(event.name(), Some(OsStr::new("last")));
+    assert!(parser.flush().is_none());
+}
@@ -1,4 +1,4 @@
-use mio::Interest;
+use mio::{Interest, Mode};
 
 #[test]
 fn is_tests() {
@@ -47,3 +47,22 @@ fn add() {
     assert!(interest.is_readable());
     assert!(interest.is_writable());
 }
+
+#[test]
+fn mode() {
+    assert_eq!(Interest::READABLE.mode(), Mode::Edge);
+
+    let interests = (Interest::READABLE | Interest::WRITABLE).with_mode(Mode::Level);
+    assert!(interests.is_readable());
+    assert!(interests.is_writable());
+    assert_eq!(interests.mode(), Mode::Level);
+    assert_eq!(format!("{:?}", interests), "READABLE | WRITABLE | LEVEL");
+
+    let interests = interests.with_mode(Mode::Oneshot);
+    assert_eq!(interests.mode(), Mode::Oneshot);
+    assert_eq!(format!("{:?}", interests), "READABLE | WRITABLE | ONESHOT");
+    assert_eq!(
+        interests.with_mode(Mode::Edge),
+        Interest::READABLE | Interest::WRITABLE
+    );
+}
@@ -0,0 +1,230 @@
+#![cfg(all(target_os = "linux", feature = "os-poll", feature = "os-util"))]
+
+use mio::unix::netlink::{self, Address, Route, RouteMessage};
+use mio::unix::Netlink;
+use mio::{Interest, Token};
+use std::io;
+use std::net::{IpAddr, Ipv4Addr};
+
+mod util;
+use util::{
+    assert_send, assert_socket_close_on_exec, assert_socket_non_blocking, assert_sync,
+    assert_would_block, expect_events, init_with_poll, ExpectEvent,
+};
+
+const ID1: Token = Token(0);
+
+const NETLINK_ROUTE: i32 = 0;
+const RTNLGRP_IPV4_IFADDR: u32 = 5;
+
+const RTM_NEWADDR: u16 = 20;
+const RTM_DELADDR: u16 = 21;
+const RTM_GETLINK: u16 = 18;
+const RTM_GETADDR: u16 = 22;
+const RTM_GETROUTE: u16 = 26;
+
+const NLM_F_REQUEST: u16 = 0x1;
+const NLM_F_ACK: u16 = 0x4;
+const NLM_F_EXCL: u16 = 0x200;
+const NLM_F_CREATE: u16 = 0x400;
+const NLM_F_DUMP: u16 = 0x300;
+
+const AF_INET: u8 = 2;
+const IFA_LOCAL: u16 = 2;
+const RT_TABLE_LOCAL: u32 = 255;
+
+/// Index of the loopback interface.
+const LOOPBACK: u32 = 1;
+
+/// Sends a dump request of `kind` with `payload` and returns all parsed
+/// `NETLINK_ROUTE` messages in the response.
+fn dump(kind: u16, payload: &[u8]) -> Vec<RouteMessage> {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut socket = Netlink::new(NETLINK_ROUTE).unwrap();
+    poll.registry()
+        .register(&mut socket, ID1, Interest::READABLE)
+        .unwrap();
+    let request = netlink::encode(kind, NLM_F_REQUEST | NLM_F_DUMP, 1, payload);
+    assert_eq!(socket.send(&request).unwrap(), request.len());
+
+    let mut messages = Vec::new();
+    let mut buf = vec![0; 32 * 1024];
+    loop {
+        expect_events(
+            &mut poll,
+            &mut events,
+            vec![ExpectEvent::new(ID1, Interest::READABLE)],
+        );
+        loop {
+            let n = match socket.recv_from(&mut buf) {
+                Ok((n, port_id)) => {
+                    // Sent by the kernel.
+                    assert_eq!(port_id, 0);
+                    n
+                }
+                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) => panic!("unexpected error: {}", err),
+            };
+            for message in netlink::messages(&buf[..n]) {
+                let message = message.unwrap();
+                assert!(message.error().is_none());
+                assert_eq!(message.sequence(), 1);
+                if message.is_done() {
+                    return messages;
+                }
+                messages.push(message.route().unwrap().expect("unexpected message"));
+            }
+        }
+    }
+}
+
+/// Encodes a `RTM_NEWADDR` or `RTM_DELADDR` payload for `addr` on the
+/// loopback interface.
+fn address_payload(addr: Ipv4Addr) -> Vec<u8> {
+    // `struct ifaddrmsg`.
+    let mut payload = vec![AF_INET, 32, 0, 0];
+    payload.extend_from_slice(&LOOPBACK.to_ne_bytes());
+    // `IFA_LOCAL` attribute.
+    payload.extend_from_slice(&8u16.to_ne_bytes());
+    payload.extend_from_slice(&IFA_LOCAL.to_ne_bytes());
+    payload.extend_from_slice(&addr.octets());
+    payload
+}
+
+/// Sends `request` and returns the error in the acknowledgement.
+fn request(socket: &Netlink, request: &[u8]) -> io::Result<()> {
+    socket.send(request)?;
+    let mut buf = [0; 1024];
+    loop {
+        match socket.recv(&mut buf) {
+            Ok(n) => {
+                let message = netlink::messages(&buf[..n]).next().unwrap().unwrap();
+                return match message.error() {
+                    Some(err) => Err(err),
+                    None => Ok(()),
+                };
+            }
+            // The response should be quick.
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {}
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[test]
+fn is_send_and_sync() {
+    assert_send::<Netlink>();
+    assert_sync::<Netlink>();
+}
+
+#[test]
+fn netlink_dump_links() {
+    let links = dump(RTM_GETLINK, &[0; 16]);
+    let lo = links
+        .into_iter()
+        .filter_map(|message| match message {
+            RouteMessage::NewLink(link) => Some(link),
+            _ => None,
+        })
+        .find(|link| link.index == LOOPBACK)
+        .expect("no loopback interface");
+    assert_eq!(lo.name, Some("lo".to_owned()));
+    assert!(lo.is_up());
+    assert!(lo.mtu.is_some());
+}
+
+#[test]
+fn netlink_dump_addresses() {
+    let localhost = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    let addresses: Vec<Address> = dump(RTM_GETADDR, &[AF_INET, 0, 0, 0, 0, 0, 0, 0])
+        .into_iter()
+        .map(|message| match message {
+            RouteMessage::NewAddr(addr) => addr,
+            message => panic!("unexpected message: {:?}", message),
+        })
+        .collect();
+    let addr = addresses
+        .iter()
+        .find(|addr| addr.local == Some(localhost))
+        .expect("no localhost address");
+    assert_eq!(addr.family, AF_INET);
+    assert_eq!(addr.index, LOOPBACK);
+    assert_eq!(addr.prefix_len, 8);
+    assert_eq!(addr.label, Some("lo".to_owned()));
+}
+
+#[test]
+fn netlink_dump_routes() {
+    let mut payload = [0; 12];
+    payload[0] = AF_INET;
+    let routes: Vec<Route> = dump(RTM_GETROUTE, &payload)
+        .into_iter()
+        .map(|message| match message {
+            RouteMessage::NewRoute(route) => route,
+            message => panic!("unexpected message: {:?}", message),
+        })
+        .collect();
+    // Local route to 127.0.0.1.
+    let route = routes
+        .iter()
+        .find(|route| {
+            route.table == RT_TABLE_LOCAL
+                && route.destination == Some(IpAddr::V4(Ipv4Addr::LOCALHOST))
+        })
+        .expect("no local route to localhost");
+    assert_eq!(route.family, AF_INET);
+    assert_eq!(route.destination_len, 32);
+    assert_eq!(route.output_index, Some(LOOPBACK));
+}
+
+#[test]
+fn netlink_multicast_group() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut listener = Netlink::new(NETLINK_ROUTE).unwrap();
+    assert_socket_non_blocking(&listener);
+    assert_socket_close_on_exec(&listener);
+    listener.bind(0, 0).unwrap();
+    assert_ne!(listener.port_id().unwrap(), 0);
+    listener.join_group(RTNLGRP_IPV4_IFADDR).unwrap();
+    poll.registry()
+        .register(&mut listener, ID1, Interest::READABLE)
+        .unwrap();
+    let mut buf = [0; 4096];
+    assert_would_block(listener.recv(&mut buf));
+
+    // Adding an address requires `CAP_NET_ADMIN`.
+    let addr = Ipv4Addr::new(127, 77, 77, 77);
+    let socket = Netlink::new(NETLINK_ROUTE).unwrap();
+    let flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_CREATE | NLM_F_EXCL;
+    let add = netlink::encode(RTM_NEWADDR, flags, 1, &address_payload(addr));
+    match request(&socket, &add) {
+        Ok(()) => {}
+        Err(ref err) if err.kind() == io::ErrorKind::PermissionDenied => {
+            eprintln!("skipping test, insufficient privileges: {}", err);
+            return;
+        }
+        Err(err) => panic!("unexpected error adding address: {}", err),
+    }
+
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::READABLE)],
+    );
+    let n = listener.recv(&mut buf).unwrap();
+    let message = netlink::messages(&buf[..n]).next().unwrap().unwrap();
+    assert_eq!(message.kind(), RTM_NEWADDR);
+    match message.route().unwrap() {
+        Some(RouteMessage::NewAddr(new)) => {
+            assert_eq!(new.local, Some(IpAddr::V4(addr)));
+            assert_eq!(new.index, LOOPBACK);
+        }
+        message => panic!("unexpected message: {:?}", message),
+    }
+
+    let flags = NLM_F_REQUEST | NLM_F_ACK;
+    let del = netlink::encode(RTM_DELADDR, flags, 2, &address_payload(addr));
+    request(&socket, &del).unwrap();
+}
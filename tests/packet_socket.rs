@@ -0,0 +1,123 @@
+#![cfg(all(target_os = "linux", feature = "os-poll", feature = "os-util"))]
+
+use mio::unix::{LinkAddr, PacketSocket, SockFilter};
+use mio::{Interest, Token};
+use std::ffi::CString;
+use std::io;
+use std::net::UdpSocket;
+
+mod util;
+use util::{
+    assert_send, assert_socket_close_on_exec, assert_socket_non_blocking, assert_sync,
+    assert_would_block, expect_events, expect_no_events, init_with_poll, ExpectEvent,
+};
+
+const ID1: Token = Token(0);
+
+const ETH_P_IP: u16 = 0x0800;
+
+const DATA: &[u8] = b"Hello packet socket!";
+
+/// Returns `None` if we don't have the required privileges.
+fn skip_unprivileged(result: io::Result<PacketSocket>) -> Option<PacketSocket> {
+    match result {
+        Ok(socket) => Some(socket),
+        Err(ref err) if err.kind() == io::ErrorKind::PermissionDenied => {
+            eprintln!("skipping test, insufficient privileges: {}", err);
+            None
+        }
+        Err(err) => panic!("unexpected error creating socket: {}", err),
+    }
+}
+
+fn loopback_index() -> u32 {
+    let name = CString::new("lo").unwrap();
+    let index = unsafe { libc::if_nametoindex(name.as_ptr()) };
+    assert_ne!(index, 0, "no loopback interface");
+    index
+}
+
+/// Send `DATA` in a UDP datagram over the loopback interface.
+fn send_udp() {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket.send_to(DATA, socket.local_addr().unwrap()).unwrap();
+}
+
+/// Receives frames until one containing `DATA` is found.
+fn recv_data(socket: &PacketSocket) -> LinkAddr {
+    let mut buf = [0; 2048];
+    loop {
+        let (n, addr) = socket.recv_from(&mut buf).unwrap();
+        if buf[..n].windows(DATA.len()).any(|window| window == DATA) {
+            return addr;
+        }
+    }
+}
+
+#[test]
+fn is_send_and_sync() {
+    assert_send::<PacketSocket>();
+    assert_sync::<PacketSocket>();
+}
+
+#[test]
+fn packet_socket_loopback() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut socket = match skip_unprivileged(PacketSocket::new_dgram(ETH_P_IP)) {
+        Some(socket) => socket,
+        None => return,
+    };
+    assert_socket_non_blocking(&socket);
+    assert_socket_close_on_exec(&socket);
+
+    let ifindex = loopback_index();
+    socket.bind(ifindex, ETH_P_IP).unwrap();
+    poll.registry()
+        .register(&mut socket, ID1, Interest::READABLE)
+        .unwrap();
+
+    send_udp();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::READABLE)],
+    );
+
+    let addr = recv_data(&socket);
+    assert_eq!(addr.ifindex(), ifindex);
+    assert_eq!(addr.protocol(), ETH_P_IP);
+}
+
+#[test]
+fn packet_socket_filter() {
+    let (mut poll, mut events) = init_with_poll();
+
+    // Don't receive any frames until the filter is attached.
+    let mut socket = match skip_unprivileged(PacketSocket::new_raw(0)) {
+        Some(socket) => socket,
+        None => return,
+    };
+    // `ret #0`, i.e. drop all frames.
+    socket
+        .attach_filter(&[SockFilter::new(0x06, 0, 0, 0)])
+        .unwrap();
+    socket.bind(loopback_index(), ETH_P_IP).unwrap();
+    poll.registry()
+        .register(&mut socket, ID1, Interest::READABLE)
+        .unwrap();
+
+    send_udp();
+    expect_no_events(&mut poll, &mut events);
+    let mut buf = [0; 2048];
+    assert_would_block(socket.recv_from(&mut buf));
+
+    socket.detach_filter().unwrap();
+    send_udp();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::READABLE)],
+    );
+    recv_data(&socket);
+}
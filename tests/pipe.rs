@@ -0,0 +1,214 @@
+#![cfg(all(unix, feature = "os-poll", feature = "os-util"))]
+
+use mio::unix::pipe::{self, Receiver, Sender};
+use mio::{Interest, Token};
+use std::io::{Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::process::{Command, Stdio};
+
+mod util;
+use util::{
+    assert_send, assert_socket_close_on_exec, assert_socket_non_blocking, assert_sync,
+    assert_would_block, expect_events, expect_no_events, init_with_poll, ExpectEvent, Readiness,
+};
+
+const RECEIVER: Token = Token(0);
+const SENDER: Token = Token(1);
+
+const DATA1: &[u8; 11] = b"Hello world";
+
+#[test]
+fn is_send_and_sync() {
+    assert_send::<Sender>();
+    assert_sync::<Sender>();
+    assert_send::<Receiver>();
+    assert_sync::<Receiver>();
+}
+
+#[test]
+fn pipe_flags() {
+    let (sender, receiver) = pipe::new().unwrap();
+    assert_socket_non_blocking(&sender);
+    assert_socket_close_on_exec(&sender);
+    assert_socket_non_blocking(&receiver);
+    assert_socket_close_on_exec(&receiver);
+}
+
+#[test]
+fn smoke() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let (mut sender, mut receiver) = pipe::new().unwrap();
+    poll.registry()
+        .register(&mut receiver, RECEIVER, Interest::READABLE)
+        .unwrap();
+    poll.registry()
+        .register(&mut sender, SENDER, Interest::WRITABLE)
+        .unwrap();
+
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(SENDER, Interest::WRITABLE)],
+    );
+    let mut buf = [0; 20];
+    assert_would_block(receiver.read(&mut buf));
+
+    let n = sender.write(DATA1).unwrap();
+    assert_eq!(n, DATA1.len());
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(RECEIVER, Interest::READABLE)],
+    );
+
+    let n = receiver.read(&mut buf).unwrap();
+    assert_eq!(n, DATA1.len());
+    assert_eq!(&buf[..n], DATA1);
+    assert_would_block(receiver.read(&mut buf));
+}
+
+#[test]
+fn full_pipe() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let (mut sender, mut receiver) = pipe::new().unwrap();
+    poll.registry()
+        .register(&mut sender, SENDER, Interest::WRITABLE)
+        .unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(SENDER, Interest::WRITABLE)],
+    );
+
+    // Fill the pipe.
+    let buf = [1; 4096];
+    loop {
+        match sender.write(&buf) {
+            Ok(_) => continue,
+            Err(ref err) if err.kind() == std::io::ErrorKind::WouldBlock => break,
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+    }
+    expect_no_events(&mut poll, &mut events);
+
+    // Emptying the pipe should make the sender writable again.
+    let mut buf = [0; 4096];
+    loop {
+        match receiver.read(&mut buf) {
+            Ok(_) => continue,
+            Err(ref err) if err.kind() == std::io::ErrorKind::WouldBlock => break,
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+    }
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(SENDER, Interest::WRITABLE)],
+    );
+}
+
+#[test]
+fn event_when_sender_is_dropped() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let (mut sender, mut receiver) = pipe::new().unwrap();
+    poll.registry()
+        .register(&mut receiver, RECEIVER, Interest::READABLE)
+        .unwrap();
+
+    expect_no_events(&mut poll, &mut events);
+    assert_eq!(sender.write(DATA1).unwrap(), DATA1.len());
+    drop(sender);
+
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(
+            RECEIVER,
+            Readiness::READABLE | Readiness::READ_CLOSED,
+        )],
+    );
+    let mut buf = [0; 20];
+    assert_eq!(receiver.read(&mut buf).unwrap(), DATA1.len());
+    assert_eq!(receiver.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn event_when_receiver_is_dropped() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let (mut sender, receiver) = pipe::new().unwrap();
+    poll.registry()
+        .register(&mut sender, SENDER, Interest::WRITABLE)
+        .unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(SENDER, Interest::WRITABLE)],
+    );
+
+    drop(receiver);
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(SENDER, Readiness::WRITE_CLOSED)],
+    );
+}
+
+#[test]
+fn set_nonblocking() {
+    let (sender, receiver) = pipe::new().unwrap();
+
+    receiver.set_nonblocking(false).unwrap();
+    let flags = unsafe { libc::fcntl(receiver.as_raw_fd(), libc::F_GETFL) };
+    assert_eq!(flags & libc::O_NONBLOCK, 0);
+    // Other flags must be retained.
+    assert_eq!(flags & libc::O_ACCMODE, libc::O_RDONLY);
+
+    receiver.set_nonblocking(true).unwrap();
+    assert_socket_non_blocking(&receiver);
+    sender.set_nonblocking(true).unwrap();
+    assert_socket_non_blocking(&sender);
+}
+
+#[test]
+fn from_child_process_io() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut child = Command::new("cat")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut sender = Sender::from(child.stdin.take().unwrap());
+    let mut receiver = Receiver::from(child.stdout.take().unwrap());
+    sender.set_nonblocking(true).unwrap();
+    receiver.set_nonblocking(true).unwrap();
+
+    poll.registry()
+        .register(&mut receiver, RECEIVER, Interest::READABLE)
+        .unwrap();
+    poll.registry()
+        .register(&mut sender, SENDER, Interest::WRITABLE)
+        .unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(SENDER, Interest::WRITABLE)],
+    );
+
+    assert_eq!(sender.write(DATA1).unwrap(), DATA1.len());
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(RECEIVER, Interest::READABLE)],
+    );
+    let mut buf = [0; 20];
+    assert_eq!(receiver.read(&mut buf).unwrap(), DATA1.len());
+    assert_eq!(&buf[..DATA1.len()], DATA1);
+
+    drop(sender);
+    assert!(child.wait().unwrap().success());
+}
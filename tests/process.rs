@@ -0,0 +1,228 @@
+#![cfg(all(unix, feature = "os-poll", feature = "os-util"))]
+
+use mio::unix::process::{ChildStderr, ChildStdin, ChildStdout};
+use mio::{Interest, Token};
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+
+mod util;
+use util::{
+    assert_send, assert_socket_close_on_exec, assert_socket_non_blocking, assert_sync,
+    assert_would_block, expect_events, init_with_poll, ExpectEvent, Readiness,
+};
+
+const STDIN: Token = Token(0);
+const STDOUT: Token = Token(1);
+const STDERR: Token = Token(2);
+
+#[test]
+fn is_send_and_sync() {
+    assert_send::<ChildStdin>();
+    assert_sync::<ChildStdin>();
+    assert_send::<ChildStdout>();
+    assert_sync::<ChildStdout>();
+    assert_send::<ChildStderr>();
+    assert_sync::<ChildStderr>();
+}
+
+#[test]
+fn stdio_pipes() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut child = Command::new("cat")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = ChildStdin::from(child.stdin.take().unwrap());
+    stdin.set_nonblocking(true).unwrap();
+    let mut stdout = ChildStdout::from(child.stdout.take().unwrap());
+    stdout.set_nonblocking(true).unwrap();
+    assert_socket_non_blocking(&stdin);
+    assert_socket_close_on_exec(&stdin);
+    assert_socket_non_blocking(&stdout);
+    assert_socket_close_on_exec(&stdout);
+
+    poll.registry()
+        .register(&mut stdin, STDIN, Interest::WRITABLE)
+        .unwrap();
+    poll.registry()
+        .register(&mut stdout, STDOUT, Interest::READABLE)
+        .unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(STDIN, Interest::WRITABLE)],
+    );
+    assert_would_block(stdout.read(&mut [0; 16]));
+
+    stdin.write_all(b"Hello world").unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(STDOUT, Interest::READABLE)],
+    );
+    let mut buf = [0; 16];
+    assert_eq!(stdout.read(&mut buf).unwrap(), 11);
+    assert_eq!(&buf[..11], b"Hello world");
+
+    // Closing stdin causes `cat` to exit.
+    poll.registry().deregister(&mut stdin).unwrap();
+    drop(stdin);
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(STDOUT, Readiness::READ_CLOSED)],
+    );
+    assert_eq!(stdout.read(&mut buf).unwrap(), 0);
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn stderr_pipe() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut child = Command::new("sh")
+        .args(&["-c", "echo error >&2"])
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stderr = ChildStderr::from(child.stderr.take().unwrap());
+    stderr.set_nonblocking(true).unwrap();
+    poll.registry()
+        .register(&mut stderr, STDERR, Interest::READABLE)
+        .unwrap();
+
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(STDERR, Interest::READABLE)],
+    );
+    let mut output = Vec::new();
+    loop {
+        let mut buf = [0; 16];
+        match stderr.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => output.extend_from_slice(&buf[..n]),
+            Err(ref err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                poll.poll(&mut events, None).unwrap()
+            }
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+    }
+    assert_eq!(output, b"error\n");
+    assert!(child.wait().unwrap().success());
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod child {
+    use mio::unix::process::Child;
+    use mio::{Interest, Token};
+    use std::io::Read;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::{Command, Stdio};
+    use std::thread;
+    use std::time::Duration;
+
+    use super::util::{
+        assert_send, assert_sync, expect_events, expect_no_events, init_with_poll, ExpectEvent,
+    };
+
+    const CHILD: Token = Token(10);
+
+    #[test]
+    fn is_send_and_sync() {
+        assert_send::<Child>();
+        assert_sync::<Child>();
+    }
+
+    #[test]
+    fn child_exit() {
+        let (mut poll, mut events) = init_with_poll();
+
+        let child = Command::new("sh").args(&["-c", "exit 3"]).spawn().unwrap();
+        let mut child = Child::from_std(child).unwrap();
+        poll.registry()
+            .register(&mut child, CHILD, Interest::READABLE)
+            .unwrap();
+
+        expect_events(
+            &mut poll,
+            &mut events,
+            vec![ExpectEvent::new(CHILD, Interest::READABLE)],
+        );
+        assert_eq!(child.try_wait().unwrap().unwrap().code(), Some(3));
+    }
+
+    #[test]
+    fn child_already_exited() {
+        let (mut poll, mut events) = init_with_poll();
+
+        let child = Command::new("true").spawn().unwrap();
+        thread::sleep(Duration::from_millis(100));
+        let mut child = Child::from_std(child).unwrap();
+        poll.registry()
+            .register(&mut child, CHILD, Interest::READABLE)
+            .unwrap();
+
+        expect_events(
+            &mut poll,
+            &mut events,
+            vec![ExpectEvent::new(CHILD, Interest::READABLE)],
+        );
+        assert!(child.try_wait().unwrap().unwrap().success());
+    }
+
+    #[test]
+    fn child_kill() {
+        let (mut poll, mut events) = init_with_poll();
+
+        let child = Command::new("sleep").arg("10").spawn().unwrap();
+        let mut child = Child::from_std(child).unwrap();
+        poll.registry()
+            .register(&mut child, CHILD, Interest::READABLE)
+            .unwrap();
+
+        expect_no_events(&mut poll, &mut events);
+        assert!(child.try_wait().unwrap().is_none());
+
+        child.kill().unwrap();
+        expect_events(
+            &mut poll,
+            &mut events,
+            vec![ExpectEvent::new(CHILD, Interest::READABLE)],
+        );
+        let status = child.try_wait().unwrap().unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGKILL));
+    }
+
+    #[test]
+    fn child_stdio() {
+        let (mut poll, mut events) = init_with_poll();
+
+        let child = Command::new("echo")
+            .arg("Hello world")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut child = Child::from_std(child).unwrap();
+        assert!(child.stdin.is_none());
+        assert!(child.stderr.is_none());
+        let mut stdout = child.stdout.take().unwrap();
+
+        poll.registry()
+            .register(&mut child, CHILD, Interest::READABLE)
+            .unwrap();
+        expect_events(
+            &mut poll,
+            &mut events,
+            vec![ExpectEvent::new(CHILD, Interest::READABLE)],
+        );
+        assert!(child.try_wait().unwrap().unwrap().success());
+
+        let mut output = String::new();
+        stdout.read_to_string(&mut output).unwrap();
+        assert_eq!(output, "Hello world\n");
+    }
+}
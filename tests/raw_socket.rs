@@ -0,0 +1,137 @@
+#![cfg(all(unix, feature = "os-poll", feature = "udp"))]
+
+use mio::net::RawSocket;
+use mio::{Interest, Token};
+use std::io;
+use std::net::SocketAddr;
+
+mod util;
+use util::{
+    assert_send, assert_socket_close_on_exec, assert_socket_non_blocking, assert_sync,
+    assert_would_block, expect_events, init_with_poll, ExpectEvent,
+};
+
+const ID1: Token = Token(0);
+
+const IPPROTO_ICMP: i32 = 1;
+
+/// ICMP echo request with identifier 0x1234, sequence number 1 and the
+/// checksum filled in.
+const ECHO_REQUEST: [u8; 12] = [8, 0, 0x09, 0x40, 0x12, 0x34, 0, 1, b'm', b'i', b'o', b'!'];
+
+/// Returns `None` if we don't have the required privileges.
+fn skip_unprivileged(result: io::Result<RawSocket>) -> Option<RawSocket> {
+    match result {
+        Ok(socket) => Some(socket),
+        Err(ref err) if err.kind() == io::ErrorKind::PermissionDenied => {
+            eprintln!("skipping test, insufficient privileges: {}", err);
+            None
+        }
+        Err(err) => panic!("unexpected error creating socket: {}", err),
+    }
+}
+
+#[test]
+fn is_send_and_sync() {
+    assert_send::<RawSocket>();
+    assert_sync::<RawSocket>();
+}
+
+#[test]
+fn raw_socket_icmp_echo() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut socket = match skip_unprivileged(RawSocket::new_v4(IPPROTO_ICMP)) {
+        Some(socket) => socket,
+        None => return,
+    };
+    assert_socket_non_blocking(&socket);
+    assert_socket_close_on_exec(&socket);
+
+    let localhost: SocketAddr = "127.0.0.1:0".parse().unwrap();
+    socket.bind(localhost).unwrap();
+    assert_eq!(socket.local_addr().unwrap().ip(), localhost.ip());
+    assert!(socket.take_error().unwrap().is_none());
+    poll.registry()
+        .register(&mut socket, ID1, Interest::READABLE)
+        .unwrap();
+
+    let mut buf = [0; 128];
+    assert_would_block(socket.recv_from(&mut buf));
+
+    assert_eq!(
+        socket.send_to(&ECHO_REQUEST, localhost).unwrap(),
+        ECHO_REQUEST.len()
+    );
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::READABLE)],
+    );
+
+    // A raw ICMP socket receives all ICMP datagrams, including our own
+    // request, so look for the echo reply.
+    loop {
+        let (n, addr) = socket.recv_from(&mut buf).unwrap();
+        assert_eq!(addr.ip(), localhost.ip());
+        // Received IPv4 datagrams include the IP header.
+        let header_len = ((buf[0] & 0x0f) * 4) as usize;
+        let icmp = &buf[header_len..n];
+        if icmp[0] == 0 {
+            assert_eq!(&icmp[4..], &ECHO_REQUEST[4..]);
+            break;
+        }
+        assert_eq!(icmp, &ECHO_REQUEST[..]);
+    }
+}
+
+#[test]
+fn raw_socket_header_included() {
+    let socket = match skip_unprivileged(RawSocket::new_v4(IPPROTO_ICMP)) {
+        Some(socket) => socket,
+        None => return,
+    };
+
+    assert!(!socket.header_included().unwrap());
+    socket.set_header_included(true).unwrap();
+    assert!(socket.header_included().unwrap());
+    socket.set_header_included(false).unwrap();
+    assert!(!socket.header_included().unwrap());
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn ping_socket() {
+    let (mut poll, mut events) = init_with_poll();
+
+    // Ping sockets must be enabled using the `net.ipv4.ping_group_range`
+    // sysctl.
+    let mut socket = match skip_unprivileged(RawSocket::ping_v4()) {
+        Some(socket) => socket,
+        None => return,
+    };
+    assert_socket_non_blocking(&socket);
+    assert_socket_close_on_exec(&socket);
+    poll.registry()
+        .register(&mut socket, ID1, Interest::READABLE)
+        .unwrap();
+
+    let localhost: SocketAddr = "127.0.0.1:0".parse().unwrap();
+    socket.connect(localhost).unwrap();
+    socket.send(&ECHO_REQUEST).unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::READABLE)],
+    );
+
+    // Only the reply is received, without IP header. The kernel replaces the
+    // identifier with the local port.
+    let mut buf = [0; 128];
+    let n = socket.recv(&mut buf).unwrap();
+    assert_eq!(n, ECHO_REQUEST.len());
+    assert_eq!(buf[0], 0);
+    let port = socket.local_addr().unwrap().port();
+    assert_eq!(&buf[4..6], &port.to_be_bytes());
+    assert_eq!(&buf[6..n], &ECHO_REQUEST[6..]);
+}
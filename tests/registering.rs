@@ -1,16 +1,17 @@
 #![cfg(all(feature = "os-poll", feature = "tcp", feature = "udp"))]
 
 use log::{debug, info, trace};
-#[cfg(debug_assertions)]
-use mio::net::UdpSocket;
-use mio::net::{TcpListener, TcpStream};
-use mio::{Events, Interest, Poll, Registry, Token};
+use mio::net::{TcpListener, TcpStream, UdpSocket};
+use mio::{Events, Interest, Mode, Poll, Registry, Token};
 use std::io::{self, Write};
 use std::thread::sleep;
 use std::time::Duration;
 
 mod util;
-use util::{any_local_address, assert_error, init};
+use util::{
+    any_local_address, assert_error, expect_events, expect_no_events, init, init_with_poll,
+    ExpectEvent,
+};
 
 const SERVER: Token = Token(0);
 const CLIENT: Token = Token(1);
@@ -240,3 +241,224 @@ fn registering_after_deregistering() {
         .unwrap();
     assert!(events.is_empty());
 }
+
+#[test]
+#[cfg(unix)]
+fn level_triggered() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut receiver = UdpSocket::bind(any_local_address()).unwrap();
+    let sender = UdpSocket::bind(any_local_address()).unwrap();
+    poll.registry()
+        .register(
+            &mut receiver,
+            CLIENT,
+            Interest::READABLE.with_mode(Mode::Level),
+        )
+        .unwrap();
+
+    sender
+        .send_to(b"hello", receiver.local_addr().unwrap())
+        .unwrap();
+
+    // Without reading the datagram the event must be returned every time.
+    for _ in 0..3 {
+        expect_events(
+            &mut poll,
+            &mut events,
+            vec![ExpectEvent::new(CLIENT, Interest::READABLE)],
+        );
+    }
+
+    let mut buf = [0; 16];
+    assert_eq!(receiver.recv(&mut buf).unwrap(), 5);
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+#[cfg(unix)]
+fn oneshot() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut receiver = UdpSocket::bind(any_local_address()).unwrap();
+    let sender = UdpSocket::bind(any_local_address()).unwrap();
+    let interests = Interest::READABLE.with_mode(Mode::Oneshot);
+    poll.registry()
+        .register(&mut receiver, CLIENT, interests)
+        .unwrap();
+
+    let address = receiver.local_addr().unwrap();
+    sender.send_to(b"hello", address).unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(CLIENT, Interest::READABLE)],
+    );
+
+    // The registration is disabled until it's reregistered.
+    sender.send_to(b"world", address).unwrap();
+    expect_no_events(&mut poll, &mut events);
+
+    poll.registry()
+        .reregister(&mut receiver, SERVER, interests)
+        .unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(SERVER, Interest::READABLE)],
+    );
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+#[cfg(unix)]
+fn oneshot_multiple_interests() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut receiver = UdpSocket::bind(any_local_address()).unwrap();
+    let sender = UdpSocket::bind(any_local_address()).unwrap();
+    let interests = (Interest::READABLE | Interest::WRITABLE).with_mode(Mode::Oneshot);
+    let res = poll.registry().register(&mut receiver, CLIENT, interests);
+    if cfg!(any(
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )) {
+        assert_error(
+            res,
+            "oneshot registrations can't be both readable and writable",
+        );
+        return;
+    }
+    res.unwrap();
+
+    // The socket is writable right away, which disables the registration for
+    // both interests.
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(CLIENT, Interest::WRITABLE)],
+    );
+    let address = receiver.local_addr().unwrap();
+    sender.send_to(b"hello", address).unwrap();
+    expect_no_events(&mut poll, &mut events);
+
+    poll.registry()
+        .reregister(&mut receiver, CLIENT, interests)
+        .unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(
+            CLIENT,
+            Interest::READABLE | Interest::WRITABLE,
+        )],
+    );
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+#[cfg(windows)]
+fn unsupported_mode() {
+    init();
+
+    let mut socket = UdpSocket::bind(any_local_address()).unwrap();
+    let poll = Poll::new().unwrap();
+    let res = poll.registry().register(
+        &mut socket,
+        CLIENT,
+        Interest::READABLE.with_mode(Mode::Level),
+    );
+    assert_error(res, "registration mode not supported on Windows");
+}
+
+#[test]
+#[cfg(all(
+    any(target_os = "android", target_os = "linux"),
+    not(feature = "io-uring")
+))]
+fn exclusive_wakes_single_poller() {
+    use std::net;
+    use std::sync::{Arc, Barrier, Mutex};
+    use std::thread;
+
+    init();
+
+    const POLLERS: usize = 4;
+    const ROUNDS: usize = 7;
+
+    let listener = net::TcpListener::bind(any_local_address()).unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let barrier = Arc::new(Barrier::new(POLLERS + 1));
+    let woken = Arc::new(Mutex::new([0; ROUNDS]));
+    let handles: Vec<_> = (0..POLLERS)
+        .map(|_| {
+            let mut listener = TcpListener::from_std(listener.try_clone().unwrap());
+            let mut poll = Poll::new().unwrap();
+            poll.registry()
+                .register_exclusive(&mut listener, SERVER, Interest::READABLE)
+                .unwrap();
+            // Exclusive registrations can't be modified.
+            let res = poll
+                .registry()
+                .reregister(&mut listener, SERVER, Interest::READABLE);
+            assert_error(res, "exclusive registrations can't be reregistered");
+
+            let barrier = barrier.clone();
+            let woken = woken.clone();
+            thread::spawn(move || {
+                let mut events = Events::with_capacity(8);
+                for round in 0..ROUNDS {
+                    barrier.wait();
+                    poll.poll(&mut events, Some(Duration::from_millis(250)))
+                        .unwrap();
+                    // Don't accept the connection, otherwise the other threads
+                    // would find the listener no longer ready after being
+                    // woken.
+                    if !events.is_empty() {
+                        woken.lock().unwrap()[round] += 1;
+                    }
+                    barrier.wait();
+                }
+                drop(listener);
+            })
+        })
+        .collect();
+
+    for _ in 0..ROUNDS {
+        barrier.wait();
+        // Give all threads time to block in `poll`.
+        sleep(Duration::from_millis(50));
+        let _stream = net::TcpStream::connect(address).unwrap();
+        // Wait for all threads to return from `poll`.
+        barrier.wait();
+        listener.accept().unwrap();
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    // `EPOLLEXCLUSIVE` wakes up "one or more" pollers, but usually only one.
+    let mut woken = *woken.lock().unwrap();
+    woken.sort_unstable();
+    assert_eq!(woken[ROUNDS / 2], 1, "woken: {:?}", woken);
+}
+
+#[test]
+fn exclusive_oneshot() {
+    init();
+
+    let mut listener = TcpListener::bind(any_local_address()).unwrap();
+    let poll = Poll::new().unwrap();
+    let res = poll.registry().register_exclusive(
+        &mut listener,
+        SERVER,
+        Interest::READABLE.with_mode(Mode::Oneshot),
+    );
+    assert_error(res, "oneshot registrations can't be exclusive");
+}
@@ -0,0 +1,168 @@
+#![cfg(all(unix, feature = "os-poll", feature = "os-util"))]
+
+use mio::unix::Signals;
+use mio::{Interest, Token};
+use std::process;
+
+mod util;
+use util::{
+    assert_send, assert_socket_close_on_exec, assert_socket_non_blocking, assert_sync,
+    assert_would_block, expect_events, expect_no_events, init_with_poll, ExpectEvent, Readiness,
+};
+
+const TOKEN: Token = Token(0);
+
+/// Sends `signal` to the current thread.
+fn raise(signal: i32) {
+    assert_eq!(unsafe { libc::raise(signal) }, 0);
+}
+
+#[test]
+fn is_send_and_sync() {
+    assert_send::<Signals>();
+    assert_sync::<Signals>();
+}
+
+#[test]
+fn signals_flags() {
+    let signals = Signals::new(&[]).unwrap();
+    assert_socket_non_blocking(&signals);
+    assert_socket_close_on_exec(&signals);
+}
+
+#[test]
+fn receive_signal() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut signals = Signals::new(&[libc::SIGUSR1]).unwrap();
+    poll.registry()
+        .register(&mut signals, TOKEN, Interest::READABLE)
+        .unwrap();
+    assert_would_block(signals.receive());
+
+    raise(libc::SIGUSR1);
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN, Readiness::READABLE)],
+    );
+
+    let info = signals.receive().unwrap();
+    assert_eq!(info.signal(), libc::SIGUSR1);
+    assert_eq!(info.pid(), process::id());
+    assert_eq!(info.uid(), unsafe { libc::getuid() });
+    assert_would_block(signals.receive());
+}
+
+#[test]
+fn receive_multiple_signals() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut signals = Signals::new(&[libc::SIGHUP]).unwrap();
+    signals.add(libc::SIGWINCH).unwrap();
+    // Adding a signal twice should be fine.
+    signals.add(libc::SIGWINCH).unwrap();
+    poll.registry()
+        .register(&mut signals, TOKEN, Interest::READABLE)
+        .unwrap();
+
+    raise(libc::SIGHUP);
+    raise(libc::SIGWINCH);
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN, Readiness::READABLE)],
+    );
+
+    let mut received = vec![
+        signals.receive().unwrap().signal(),
+        signals.receive().unwrap().signal(),
+    ];
+    received.sort();
+    let mut expected = vec![libc::SIGHUP, libc::SIGWINCH];
+    expected.sort();
+    assert_eq!(received, expected);
+    assert_would_block(signals.receive());
+}
+
+#[test]
+fn remove_signal() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut signals1 = Signals::new(&[libc::SIGUSR2]).unwrap();
+    poll.registry()
+        .register(&mut signals1, Token(1), Interest::READABLE)
+        .unwrap();
+    signals1.remove(libc::SIGUSR2).unwrap();
+    // Removing a signal that isn't handled should be fine.
+    signals1.remove(libc::SIGUSR2).unwrap();
+
+    let mut signals2 = Signals::new(&[libc::SIGUSR2]).unwrap();
+    poll.registry()
+        .register(&mut signals2, Token(2), Interest::READABLE)
+        .unwrap();
+
+    raise(libc::SIGUSR2);
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(Token(2), Readiness::READABLE)],
+    );
+    assert_eq!(signals2.receive().unwrap().signal(), libc::SIGUSR2);
+    assert_would_block(signals1.receive());
+}
+
+#[test]
+fn signals_deregister() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut signals = Signals::new(&[libc::SIGURG]).unwrap();
+    poll.registry()
+        .register(&mut signals, TOKEN, Interest::READABLE)
+        .unwrap();
+    poll.registry().deregister(&mut signals).unwrap();
+
+    raise(libc::SIGURG);
+    expect_no_events(&mut poll, &mut events);
+    assert_eq!(signals.receive().unwrap().signal(), libc::SIGURG);
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn drop_restores_signal_mask() {
+    fn is_blocked(signal: i32) -> bool {
+        unsafe {
+            let mut mask = std::mem::zeroed();
+            libc::sigemptyset(&mut mask);
+            assert_eq!(
+                libc::pthread_sigmask(libc::SIG_BLOCK, std::ptr::null(), &mut mask),
+                0
+            );
+            libc::sigismember(&mask, signal) == 1
+        }
+    }
+
+    // A signal blocked by `Signals` stays blocked while another `Signals`
+    // still handles it.
+    let signals1 = Signals::new(&[libc::SIGPROF]).unwrap();
+    let signals2 = Signals::new(&[libc::SIGPROF]).unwrap();
+    assert!(is_blocked(libc::SIGPROF));
+    drop(signals1);
+    assert!(is_blocked(libc::SIGPROF));
+    drop(signals2);
+    assert!(!is_blocked(libc::SIGPROF));
+
+    // A signal blocked before it was added stays blocked.
+    unsafe {
+        let mut mask = std::mem::zeroed();
+        libc::sigemptyset(&mut mask);
+        libc::sigaddset(&mut mask, libc::SIGVTALRM);
+        assert_eq!(
+            libc::pthread_sigmask(libc::SIG_BLOCK, &mask, std::ptr::null_mut()),
+            0
+        );
+    }
+    let signals = Signals::new(&[libc::SIGVTALRM]).unwrap();
+    drop(signals);
+    assert!(is_blocked(libc::SIGVTALRM));
+}
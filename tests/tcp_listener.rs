@@ -114,6 +114,33 @@ fn get_ttl_without_previous_set() {
     assert!(listener.take_error().unwrap().is_none());
 }
 
+#[cfg(unix)]
+#[test]
+fn set_get_tos_v4() {
+    init();
+
+    let listener = TcpListener::bind(any_local_address()).unwrap();
+
+    const TOS: u32 = 0x28;
+    listener.set_tos_v4(TOS).unwrap();
+    assert_eq!(listener.tos_v4().unwrap(), TOS);
+    assert!(listener.take_error().unwrap().is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn set_get_tclass_v6() {
+    init();
+
+    let listener = TcpListener::bind(any_local_ipv6_address()).unwrap();
+    assert_eq!(listener.tclass_v6().unwrap(), 0);
+
+    const TCLASS: u32 = 0x28;
+    listener.set_tclass_v6(TCLASS).unwrap();
+    assert_eq!(listener.tclass_v6().unwrap(), TCLASS);
+    assert!(listener.take_error().unwrap().is_none());
+}
+
 #[cfg(unix)]
 #[test]
 fn raw_fd() {
@@ -0,0 +1,167 @@
+#![cfg(all(feature = "os-poll", feature = "tcp"))]
+
+use mio::net::TcpSocket;
+use mio::{Interest, Token};
+use std::time::Duration;
+
+mod util;
+use util::{
+    any_local_address, any_local_ipv6_address, assert_send, assert_socket_close_on_exec,
+    assert_socket_non_blocking, assert_sync, expect_events, init, init_with_poll, ExpectEvent,
+};
+
+const ID1: Token = Token(0);
+const ID2: Token = Token(1);
+
+#[test]
+fn is_send_and_sync() {
+    assert_send::<TcpSocket>();
+    assert_sync::<TcpSocket>();
+}
+
+#[test]
+fn socket_flags() {
+    init();
+
+    let socket = TcpSocket::new_v4().unwrap();
+    assert_socket_non_blocking(&socket);
+    assert_socket_close_on_exec(&socket);
+}
+
+#[test]
+fn set_reuseaddr() {
+    init();
+
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.set_reuseaddr(true).unwrap();
+    assert!(socket.reuseaddr().unwrap());
+    socket.set_reuseaddr(false).unwrap();
+    assert!(!socket.reuseaddr().unwrap());
+}
+
+#[test]
+#[cfg(all(unix, not(target_os = "solaris")))]
+fn set_reuseport() {
+    init();
+
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.set_reuseport(true).unwrap();
+    assert!(socket.reuseport().unwrap());
+
+    // A second socket can bind the same address.
+    socket.bind(any_local_address()).unwrap();
+    let addr = socket.local_addr().unwrap();
+    let other = TcpSocket::new_v4().unwrap();
+    other.set_reuseport(true).unwrap();
+    other.bind(addr).unwrap();
+}
+
+#[test]
+fn set_linger() {
+    init();
+
+    let socket = TcpSocket::new_v4().unwrap();
+    assert_eq!(socket.linger().unwrap(), None);
+    socket.set_linger(Some(Duration::from_secs(1))).unwrap();
+    assert_eq!(socket.linger().unwrap(), Some(Duration::from_secs(1)));
+    socket.set_linger(None).unwrap();
+    assert_eq!(socket.linger().unwrap(), None);
+}
+
+#[test]
+fn set_buffer_sizes() {
+    init();
+
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.set_send_buffer_size(16 * 1024).unwrap();
+    // The OS may round up, or double, the requested size.
+    assert!(socket.send_buffer_size().unwrap() >= 16 * 1024);
+    socket.set_recv_buffer_size(16 * 1024).unwrap();
+    assert!(socket.recv_buffer_size().unwrap() >= 16 * 1024);
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn bind_device() {
+    init();
+
+    let socket = TcpSocket::new_v4().unwrap();
+    match socket.bind_device(Some(b"lo")) {
+        Ok(()) => socket.bind_device(None).unwrap(),
+        // Requires `CAP_NET_RAW`.
+        Err(ref err) if err.raw_os_error() == Some(libc::EPERM) => {}
+        Err(err) => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn listen_connect() {
+    smoke_test_listen_connect(any_local_address(), TcpSocket::new_v4);
+}
+
+#[test]
+fn listen_connect_ipv6() {
+    smoke_test_listen_connect(any_local_ipv6_address(), TcpSocket::new_v6);
+}
+
+fn smoke_test_listen_connect<F>(addr: std::net::SocketAddr, new_socket: F)
+where
+    F: Fn() -> std::io::Result<TcpSocket>,
+{
+    let (mut poll, mut events) = init_with_poll();
+
+    let socket = new_socket().unwrap();
+    socket.set_reuseaddr(true).unwrap();
+    socket.bind(addr).unwrap();
+    let addr = socket.local_addr().unwrap();
+    let mut listener = socket.listen(8).unwrap();
+    assert_eq!(listener.local_addr().unwrap(), addr);
+
+    let socket = new_socket().unwrap();
+    socket.set_send_buffer_size(32 * 1024).unwrap();
+    // Windows requires the socket to be bound before connecting.
+    #[cfg(windows)]
+    socket
+        .bind(if addr.is_ipv4() {
+            "0.0.0.0:0".parse().unwrap()
+        } else {
+            "[::]:0".parse().unwrap()
+        })
+        .unwrap();
+    let mut stream = socket.connect(addr).unwrap();
+
+    poll.registry()
+        .register(&mut listener, ID1, Interest::READABLE)
+        .unwrap();
+    poll.registry()
+        .register(&mut stream, ID2, Interest::WRITABLE)
+        .unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![
+            ExpectEvent::new(ID1, Interest::READABLE),
+            ExpectEvent::new(ID2, Interest::WRITABLE),
+        ],
+    );
+
+    let (accepted, peer_addr) = listener.accept().unwrap();
+    assert_eq!(peer_addr, stream.local_addr().unwrap());
+    assert_eq!(accepted.local_addr().unwrap(), addr);
+    assert_eq!(stream.peer_addr().unwrap(), addr);
+}
+
+#[test]
+fn bind_address_in_use() {
+    init();
+
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.bind(any_local_address()).unwrap();
+    let addr = socket.local_addr().unwrap();
+    drop(socket);
+
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.bind(addr).unwrap();
+    let other = TcpSocket::new_v4().unwrap();
+    assert!(other.bind(addr).is_err());
+}
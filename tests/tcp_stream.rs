@@ -1,5 +1,7 @@
 #![cfg(all(feature = "os-poll", feature = "tcp"))]
 
+#[cfg(unix)]
+use mio::net::MsgFlags;
 use mio::net::TcpStream;
 use mio::{Interest, Token};
 use std::io::{self, IoSlice, IoSliceMut, Read, Write};
@@ -8,6 +10,7 @@ use std::net::{self, Shutdown, SocketAddr};
 use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd};
 use std::sync::{mpsc::channel, Arc, Barrier};
 use std::thread;
+use std::time::Duration;
 
 #[macro_use]
 mod util;
@@ -163,6 +166,220 @@ fn set_get_ttl() {
     thread_handle.join().expect("unable to join thread");
 }
 
+#[cfg(unix)]
+#[test]
+fn set_get_tos_v4() {
+    init();
+
+    let listener = net::TcpListener::bind(any_local_address()).unwrap();
+    let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+
+    const TOS: u32 = 0x28;
+    stream.set_tos_v4(TOS).unwrap();
+    assert_eq!(stream.tos_v4().unwrap(), TOS);
+    assert!(stream.take_error().unwrap().is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn set_get_tclass_v6() {
+    init();
+
+    let listener = net::TcpListener::bind(any_local_ipv6_address()).unwrap();
+    let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+
+    const TCLASS: u32 = 0x28;
+    stream.set_tclass_v6(TCLASS).unwrap();
+    assert_eq!(stream.tclass_v6().unwrap(), TCLASS);
+    assert!(stream.take_error().unwrap().is_none());
+}
+
+/// Returns a connected `TcpStream` and the accepted end of the connection.
+fn connected_stream() -> (TcpStream, net::TcpStream) {
+    let (mut poll, mut events) = init_with_poll();
+
+    let listener = net::TcpListener::bind(any_local_address()).unwrap();
+    let mut stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    poll.registry()
+        .register(&mut stream, ID1, Interest::WRITABLE)
+        .unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::WRITABLE)],
+    );
+    poll.registry().deregister(&mut stream).unwrap();
+    let (accepted, _) = listener.accept().unwrap();
+    (stream, accepted)
+}
+
+#[test]
+fn set_get_keepalive() {
+    let (stream, _accepted) = connected_stream();
+
+    stream.set_keepalive(true).unwrap();
+    assert!(stream.keepalive().unwrap());
+    stream.set_keepalive(false).unwrap();
+    assert!(!stream.keepalive().unwrap());
+
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd"
+    ))]
+    {
+        stream.set_keepalive_idle(Duration::from_secs(60)).unwrap();
+        assert_eq!(stream.keepalive_idle().unwrap(), Duration::from_secs(60));
+        stream
+            .set_keepalive_interval(Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(
+            stream.keepalive_interval().unwrap(),
+            Duration::from_secs(10)
+        );
+        stream.set_keepalive_count(5).unwrap();
+        assert_eq!(stream.keepalive_count().unwrap(), 5);
+    }
+    assert!(stream.take_error().unwrap().is_none());
+}
+
+#[test]
+fn set_get_linger() {
+    let (stream, _accepted) = connected_stream();
+
+    assert_eq!(stream.linger().unwrap(), None);
+    stream.set_linger(Some(Duration::from_secs(2))).unwrap();
+    assert_eq!(stream.linger().unwrap(), Some(Duration::from_secs(2)));
+    stream.set_linger(None).unwrap();
+    assert_eq!(stream.linger().unwrap(), None);
+}
+
+#[test]
+fn set_get_buffer_sizes() {
+    let (stream, _accepted) = connected_stream();
+
+    stream.set_send_buffer_size(32 * 1024).unwrap();
+    // The OS may round up, or double, the requested size.
+    assert!(stream.send_buffer_size().unwrap() >= 32 * 1024);
+    stream.set_recv_buffer_size(32 * 1024).unwrap();
+    assert!(stream.recv_buffer_size().unwrap() >= 32 * 1024);
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn set_get_linux_options() {
+    let (stream, _accepted) = connected_stream();
+
+    assert_eq!(stream.user_timeout().unwrap(), None);
+    stream
+        .set_user_timeout(Some(Duration::from_millis(1500)))
+        .unwrap();
+    assert_eq!(
+        stream.user_timeout().unwrap(),
+        Some(Duration::from_millis(1500))
+    );
+    stream.set_user_timeout(None).unwrap();
+    assert_eq!(stream.user_timeout().unwrap(), None);
+
+    stream.set_quickack(true).unwrap();
+    assert!(stream.quickack().unwrap());
+
+    stream.set_cork(true).unwrap();
+    assert!(stream.cork().unwrap());
+    stream.set_cork(false).unwrap();
+    assert!(!stream.cork().unwrap());
+
+    stream.set_notsent_lowat(16 * 1024).unwrap();
+    assert_eq!(stream.notsent_lowat().unwrap(), 16 * 1024);
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn set_get_congestion() {
+    let (stream, _accepted) = connected_stream();
+
+    let current = stream.congestion().unwrap();
+    assert!(!current.is_empty());
+    stream.set_congestion(&current).unwrap();
+    assert_eq!(stream.congestion().unwrap(), current);
+
+    // Reno is always built in, but might not be allowed for unprivileged
+    // processes.
+    match stream.set_congestion("reno") {
+        Ok(()) => assert_eq!(stream.congestion().unwrap(), "reno"),
+        Err(ref err) if err.raw_os_error() == Some(libc::EPERM) => {}
+        Err(err) => panic!("unexpected error: {}", err),
+    }
+
+    assert!(stream.set_congestion("no-such-algorithm").is_err());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn tcp_info() {
+    let (mut stream, mut accepted) = connected_stream();
+
+    stream.write_all(DATA1).unwrap();
+    let mut buf = [0; DATA1_LEN];
+    accepted.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, DATA1);
+
+    let info = stream.tcp_info().unwrap();
+    // `TCP_ESTABLISHED`.
+    assert_eq!(info.state(), 1);
+    assert!(info.snd_mss() > 0);
+    assert!(info.rcv_mss() > 0);
+    assert!(info.snd_cwnd() > 0);
+    assert_eq!(info.total_retrans(), 0);
+}
+
+#[cfg(unix)]
+#[test]
+fn send_recv_with_flags() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let (mut stream, mut accepted) = connected_stream();
+    poll.registry()
+        .register(&mut stream, ID1, Interest::READABLE)
+        .unwrap();
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    {
+        // Signal more data is coming, the data will be send with the next
+        // `send` without `MSG_MORE`.
+        let n = stream.send_with_flags(DATA1, MsgFlags::MORE).unwrap();
+        assert_eq!(n, DATA1_LEN);
+    }
+    #[cfg(not(any(target_os = "android", target_os = "linux")))]
+    stream.write_all(DATA1).unwrap();
+    let n = stream.send_with_flags(DATA2, MsgFlags::empty()).unwrap();
+    assert_eq!(n, DATA2_LEN);
+
+    let mut buf = [0; DATA1_LEN + DATA2_LEN];
+    accepted.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf[..DATA1_LEN], DATA1);
+    assert_eq!(&buf[DATA1_LEN..], DATA2);
+
+    accepted.write_all(DATA1).unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::READABLE)],
+    );
+
+    let mut buf = [0; 32];
+    expect_read!(stream.recv_with_flags(&mut buf, MsgFlags::PEEK), DATA1);
+    expect_read!(stream.peek(&mut buf), DATA1);
+    // `MSG_WAITALL` doesn't block on a non-blocking socket, only the available
+    // bytes are returned.
+    expect_read!(stream.recv_with_flags(&mut buf, MsgFlags::WAITALL), DATA1);
+    assert_would_block(stream.recv_with_flags(&mut buf, MsgFlags::WAITALL));
+}
+
 #[test]
 fn get_ttl_without_previous_set() {
     let (mut poll, mut events) = init_with_poll();
@@ -0,0 +1,200 @@
+#![cfg(all(
+    any(target_os = "linux", target_os = "android"),
+    feature = "os-poll",
+    feature = "time"
+))]
+
+use mio::time::{Clock, Timer};
+use mio::{Interest, Token};
+use std::thread;
+use std::time::Duration;
+
+mod util;
+use util::{
+    assert_send, assert_socket_close_on_exec, assert_socket_non_blocking, assert_sync,
+    assert_would_block, expect_events, expect_no_events, init_with_poll, ExpectEvent, Readiness,
+};
+
+const TOKEN: Token = Token(0);
+
+#[test]
+fn is_send_and_sync() {
+    assert_send::<Timer>();
+    assert_sync::<Timer>();
+}
+
+#[test]
+fn timer_flags() {
+    let timer = Timer::new(Clock::Monotonic).unwrap();
+    assert_socket_non_blocking(&timer);
+    assert_socket_close_on_exec(&timer);
+}
+
+#[test]
+fn timer_disarmed_by_default() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut timer = Timer::new(Clock::Monotonic).unwrap();
+    poll.registry()
+        .register(&mut timer, TOKEN, Interest::READABLE)
+        .unwrap();
+
+    assert_eq!(timer.remaining().unwrap(), None);
+    assert_eq!(timer.interval().unwrap(), None);
+    assert_would_block(timer.read());
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+fn timer_timeout() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut timer = Timer::new(Clock::Monotonic).unwrap();
+    poll.registry()
+        .register(&mut timer, TOKEN, Interest::READABLE)
+        .unwrap();
+
+    timer.set_timeout(Duration::from_millis(20)).unwrap();
+    assert!(timer.remaining().unwrap().is_some());
+    assert_eq!(timer.interval().unwrap(), None);
+
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN, Readiness::READABLE)],
+    );
+    assert_eq!(timer.read().unwrap(), 1);
+    assert_would_block(timer.read());
+
+    // One-shot timers should not expire again.
+    assert_eq!(timer.remaining().unwrap(), None);
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+fn timer_interval() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut timer = Timer::new(Clock::Monotonic).unwrap();
+    poll.registry()
+        .register(&mut timer, TOKEN, Interest::READABLE)
+        .unwrap();
+
+    let interval = Duration::from_millis(10);
+    timer.set_interval(interval).unwrap();
+    assert_eq!(timer.interval().unwrap(), Some(interval));
+
+    for _ in 0..3 {
+        expect_events(
+            &mut poll,
+            &mut events,
+            vec![ExpectEvent::new(TOKEN, Readiness::READABLE)],
+        );
+        assert!(timer.read().unwrap() >= 1);
+    }
+
+    // Missed expirations are accumulated.
+    thread::sleep(interval * 5);
+    assert!(timer.read().unwrap() >= 2);
+
+    timer.disarm().unwrap();
+    assert_eq!(timer.remaining().unwrap(), None);
+    assert_eq!(timer.interval().unwrap(), None);
+    assert_would_block(timer.read());
+}
+
+#[test]
+fn timer_disarm() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut timer = Timer::new(Clock::Monotonic).unwrap();
+    poll.registry()
+        .register(&mut timer, TOKEN, Interest::READABLE)
+        .unwrap();
+
+    timer.set_timeout(Duration::from_millis(10)).unwrap();
+    timer.disarm().unwrap();
+
+    thread::sleep(Duration::from_millis(20));
+    expect_no_events(&mut poll, &mut events);
+    assert_would_block(timer.read());
+}
+
+#[test]
+fn timer_deadline() {
+    for &clock in &[Clock::Monotonic, Clock::Realtime] {
+        let (mut poll, mut events) = init_with_poll();
+
+        let mut timer = Timer::new(clock).unwrap();
+        poll.registry()
+            .register(&mut timer, TOKEN, Interest::READABLE)
+            .unwrap();
+
+        let deadline = clock.now().unwrap() + Duration::from_millis(20);
+        timer.set_deadline(deadline).unwrap();
+
+        expect_events(
+            &mut poll,
+            &mut events,
+            vec![ExpectEvent::new(TOKEN, Readiness::READABLE)],
+        );
+        assert!(clock.now().unwrap() >= deadline);
+        assert_eq!(timer.read().unwrap(), 1);
+    }
+}
+
+#[test]
+fn timer_deadline_in_the_past() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut timer = Timer::new(Clock::Monotonic).unwrap();
+    poll.registry()
+        .register(&mut timer, TOKEN, Interest::READABLE)
+        .unwrap();
+
+    let deadline = Clock::Monotonic.now().unwrap() - Duration::from_millis(10);
+    timer
+        .set_deadline_interval(deadline, Duration::from_secs(60))
+        .unwrap();
+
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN, Readiness::READABLE)],
+    );
+    assert_eq!(timer.read().unwrap(), 1);
+    assert_eq!(timer.interval().unwrap(), Some(Duration::from_secs(60)));
+}
+
+#[test]
+fn timer_deregister() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut timer = Timer::new(Clock::Monotonic).unwrap();
+    poll.registry()
+        .register(&mut timer, TOKEN, Interest::READABLE)
+        .unwrap();
+    poll.registry().deregister(&mut timer).unwrap();
+
+    timer.set_timeout(Duration::from_millis(1)).unwrap();
+    thread::sleep(Duration::from_millis(10));
+    expect_no_events(&mut poll, &mut events);
+    assert_eq!(timer.read().unwrap(), 1);
+}
+
+#[test]
+fn timer_drop_without_deregister() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut timer = Timer::new(Clock::Monotonic).unwrap();
+    poll.registry()
+        .register(&mut timer, TOKEN, Interest::READABLE)
+        .unwrap();
+    timer.set_interval(Duration::from_millis(20)).unwrap();
+    // Dropping the timer must remove its registration, otherwise the (still
+    // armed) timer keeps returning events.
+    drop(timer);
+
+    thread::sleep(Duration::from_millis(30));
+    expect_no_events(&mut poll, &mut events);
+}
@@ -0,0 +1,143 @@
+#![cfg(all(feature = "os-poll", feature = "time"))]
+
+use mio::{Events, Poll, Token, Waker};
+use std::time::{Duration, Instant};
+
+mod util;
+use util::{expect_events, expect_no_events, init_with_poll, ExpectEvent, Readiness};
+
+const TOKEN_1: Token = Token(0);
+const TOKEN_2: Token = Token(1);
+const WAKE_TOKEN: Token = Token(10);
+
+#[test]
+fn timeout_expires() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let start = Instant::now();
+    let timeout = poll.timers().insert(TOKEN_1, Duration::from_millis(20));
+    assert_eq!(poll.timers().len(), 1);
+
+    // Without a timeout `poll` should still return once the timeout expires.
+    poll.poll(&mut events, None).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(20));
+
+    let tokens: Vec<Token> = events.iter().map(|event| event.token()).collect();
+    assert_eq!(tokens, vec![TOKEN_1]);
+    assert!(events.iter().next().unwrap().is_readable());
+
+    assert!(poll.timers().is_empty());
+    assert!(!poll.timers().cancel(timeout));
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+fn timeout_in_higher_level_expires() {
+    let (mut poll, mut events) = init_with_poll();
+
+    // More than 64 milliseconds, so not in the first level of the wheel.
+    let start = Instant::now();
+    let _ = poll.timers().insert(TOKEN_1, Duration::from_millis(150));
+    let _ = poll.timers().insert(TOKEN_2, Duration::from_millis(70));
+
+    poll.poll(&mut events, Some(Duration::from_secs(1)))
+        .unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(70));
+    assert_eq!(events.iter().next().unwrap().token(), TOKEN_2);
+
+    poll.poll(&mut events, Some(Duration::from_secs(1)))
+        .unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(150));
+    assert_eq!(events.iter().next().unwrap().token(), TOKEN_1);
+}
+
+#[test]
+fn timeout_cancel() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let timeout = poll.timers().insert(TOKEN_1, Duration::from_millis(10));
+    assert!(poll.timers().cancel(timeout));
+    assert!(!poll.timers().cancel(timeout));
+    assert!(poll.timers().is_empty());
+    assert_eq!(poll.timers().next_timeout(), None);
+
+    std::thread::sleep(Duration::from_millis(20));
+    expect_no_events(&mut poll, &mut events);
+}
+
+#[test]
+fn timeout_reset() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let start = Instant::now();
+    let timeout = poll.timers().insert(TOKEN_1, Duration::from_millis(10));
+    assert!(poll.timers().reset(timeout, Duration::from_millis(100)));
+
+    poll.poll(&mut events, Some(Duration::from_millis(50)))
+        .unwrap();
+    assert!(events.is_empty());
+
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN_1, Readiness::READABLE)],
+    );
+    assert!(start.elapsed() >= Duration::from_millis(100));
+    assert!(!poll.timers().reset(timeout, Duration::from_millis(100)));
+}
+
+#[test]
+fn timeout_deadline_in_the_past() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let _ = poll.timers().insert_at(TOKEN_1, Instant::now());
+    assert_eq!(poll.timers().next_timeout(), Some(Duration::from_millis(0)));
+    poll.poll(&mut events, None).unwrap();
+    assert_eq!(events.iter().next().unwrap().token(), TOKEN_1);
+}
+
+#[test]
+fn timeouts_together_with_other_events() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let waker = Waker::new(poll.registry(), WAKE_TOKEN).unwrap();
+    let _ = poll.timers().insert(TOKEN_1, Duration::from_millis(0));
+    waker.wake().unwrap();
+    std::thread::sleep(Duration::from_millis(5));
+
+    let mut tokens: Vec<Token> = Vec::new();
+    for _ in 0..3 {
+        poll.poll(&mut events, Some(Duration::from_millis(100)))
+            .unwrap();
+        tokens.extend(events.iter().map(|event| event.token()));
+        if tokens.len() == 2 {
+            break;
+        }
+    }
+    tokens.sort();
+    assert_eq!(tokens, vec![TOKEN_1, WAKE_TOKEN]);
+}
+
+#[test]
+fn more_timeouts_than_events_capacity() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(4);
+
+    for n in 0..10 {
+        let _ = poll.timers().insert(Token(n), Duration::from_millis(1));
+    }
+    std::thread::sleep(Duration::from_millis(5));
+
+    let mut tokens = Vec::new();
+    for _ in 0..3 {
+        // Remaining timeouts must be returned without blocking.
+        let start = Instant::now();
+        poll.poll(&mut events, None).unwrap();
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(events.iter().count() <= events.capacity());
+        tokens.extend(events.iter().map(|event| event.token()));
+    }
+    tokens.sort();
+    assert_eq!(tokens, (0..10).map(Token).collect::<Vec<_>>());
+    assert!(poll.timers().is_empty());
+}
@@ -1,8 +1,9 @@
 #![cfg(all(feature = "os-poll", feature = "udp"))]
 
 use log::{debug, info};
-use mio::net::UdpSocket;
+use mio::net::{UdpSocket, UdpSocketBuilder};
 use mio::{Events, Interest, Poll, Registry, Token};
+use std::io::{self, IoSlice, IoSliceMut};
 use std::net::{self, IpAddr, SocketAddr};
 #[cfg(unix)]
 use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd};
@@ -32,6 +33,9 @@ const ID3: Token = Token(4);
 fn is_send_and_sync() {
     assert_send::<UdpSocket>();
     assert_sync::<UdpSocket>();
+
+    assert_send::<UdpSocketBuilder>();
+    assert_sync::<UdpSocketBuilder>();
 }
 
 #[test]
@@ -229,6 +233,118 @@ fn get_multicast_loop_v6_without_previous_set() {
         .expect("unable to get multicast_loop_v6 for UDP socket");
 }
 
+#[test]
+fn builder_set_options() {
+    init();
+
+    let builder = UdpSocketBuilder::new_v6().unwrap();
+    builder.set_reuseaddr(true).unwrap();
+    assert!(builder.reuseaddr().unwrap());
+    builder.set_send_buffer_size(16 * 1024).unwrap();
+    // The OS may round up, or double, the requested size.
+    assert!(builder.send_buffer_size().unwrap() >= 16 * 1024);
+    builder.set_recv_buffer_size(16 * 1024).unwrap();
+    assert!(builder.recv_buffer_size().unwrap() >= 16 * 1024);
+    builder.set_only_v6(true).unwrap();
+    assert!(builder.only_v6().unwrap());
+    builder.set_only_v6(false).unwrap();
+    assert!(!builder.only_v6().unwrap());
+
+    let socket = builder.bind(any_local_ipv6_address()).unwrap();
+    assert_socket_non_blocking(&socket);
+    assert_socket_close_on_exec(&socket);
+}
+
+#[test]
+fn builder_dual_stack() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let builder = UdpSocketBuilder::new_v6().unwrap();
+    builder.set_only_v6(false).unwrap();
+    let mut socket = builder.bind("[::]:0".parse().unwrap()).unwrap();
+    let port = socket.local_addr().unwrap().port();
+    poll.registry()
+        .register(&mut socket, ID1, Interest::READABLE)
+        .unwrap();
+
+    let sender = net::UdpSocket::bind(any_local_address()).unwrap();
+    sender.send_to(DATA1, ("127.0.0.1", port)).unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::READABLE)],
+    );
+
+    let mut buf = [0; 20];
+    let (n, addr) = socket.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..n], DATA1);
+    // IPv4-mapped IPv6 address.
+    match addr.ip() {
+        IpAddr::V6(ip) => assert_eq!(ip.to_ipv4(), Some("127.0.0.1".parse().unwrap())),
+        ip => panic!("unexpected address: {}", ip),
+    }
+
+    // With `IPV6_V6ONLY` set an IPv4 socket can bind the same port.
+    let builder = UdpSocketBuilder::new_v6().unwrap();
+    builder.set_only_v6(true).unwrap();
+    let socket = builder.bind("[::]:0".parse().unwrap()).unwrap();
+    let port = socket.local_addr().unwrap().port();
+    UdpSocket::bind(SocketAddr::new("0.0.0.0".parse().unwrap(), port)).unwrap();
+}
+
+#[test]
+#[cfg(all(unix, not(target_os = "solaris")))]
+fn builder_reuseport_sharding() {
+    init();
+
+    const N: usize = 16;
+
+    let mut shards = Vec::new();
+    let mut addr = any_local_address();
+    for _ in 0..2 {
+        let builder = UdpSocketBuilder::new_v4().unwrap();
+        builder.set_reuseport(true).unwrap();
+        assert!(builder.reuseport().unwrap());
+        let socket = builder.bind(addr).unwrap();
+        addr = socket.local_addr().unwrap();
+        shards.push(socket);
+    }
+
+    // Send from different source ports, so the datagrams get distributed
+    // across the shards.
+    let senders: Vec<_> = (0..N)
+        .map(|_| net::UdpSocket::bind(any_local_address()).unwrap())
+        .collect();
+    for sender in senders.iter() {
+        sender.send_to(DATA1, addr).unwrap();
+    }
+
+    // Each shard uses its own `Poll` instance, as it would on its own thread.
+    let mut received = 0;
+    for (i, socket) in shards.iter_mut().enumerate() {
+        let mut poll = Poll::new().unwrap();
+        let mut events = Events::with_capacity(8);
+        poll.registry()
+            .register(socket, Token(i), Interest::READABLE)
+            .unwrap();
+        poll.poll(&mut events, Some(Duration::from_millis(100)))
+            .unwrap();
+
+        let mut buf = [0; 20];
+        loop {
+            match socket.recv_from(&mut buf) {
+                Ok((n, _)) => {
+                    assert_eq!(&buf[..n], DATA1);
+                    received += 1;
+                }
+                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) => panic!("unexpected error: {}", err),
+            }
+        }
+    }
+    assert_eq!(received, N);
+}
+
 #[test]
 fn connected_udp_socket_ipv4() {
     let socket1 = UdpSocket::bind(any_local_address()).unwrap();
@@ -1085,3 +1201,757 @@ fn et_behavior_recv_from() {
     assert!(socket1.take_error().unwrap().is_none());
     assert!(socket2.take_error().unwrap().is_none());
 }
+
+#[test]
+fn send_recv_batch() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut sender = UdpSocket::bind(any_local_address()).unwrap();
+    let mut receiver = UdpSocket::bind(any_local_address()).unwrap();
+    let sender_address = sender.local_addr().unwrap();
+    let receiver_address = receiver.local_addr().unwrap();
+
+    poll.registry()
+        .register(&mut sender, ID1, Interest::WRITABLE)
+        .unwrap();
+    poll.registry()
+        .register(&mut receiver, ID2, Interest::READABLE)
+        .unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::WRITABLE)],
+    );
+
+    // Nothing to send.
+    assert_eq!(sender.send_to_batch(&[], &[receiver_address]).unwrap(), 0);
+
+    let bufs = [
+        IoSlice::new(DATA1),
+        IoSlice::new(DATA2),
+        IoSlice::new(DATA1),
+    ];
+    let targets = [receiver_address; 3];
+    assert_eq!(sender.send_to_batch(&bufs, &targets).unwrap(), 3);
+
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID2, Interest::READABLE)],
+    );
+
+    // Only receive two datagrams.
+    let mut buf1 = [0; 20];
+    let mut buf2 = [0; 20];
+    let mut buf3 = [0; 20];
+    let mut results = [(0, None); 2];
+    let received = {
+        let mut bufs = [
+            IoSliceMut::new(&mut buf1),
+            IoSliceMut::new(&mut buf2),
+            IoSliceMut::new(&mut buf3),
+        ];
+        receiver.recv_from_batch(&mut bufs, &mut results).unwrap()
+    };
+    assert_eq!(received, 2);
+    assert_eq!(
+        results,
+        [
+            (DATA1.len(), Some(sender_address)),
+            (DATA2.len(), Some(sender_address))
+        ]
+    );
+    assert_eq!(&buf1[..DATA1.len()], DATA1);
+    assert_eq!(&buf2[..DATA2.len()], DATA2);
+
+    let mut results = [(0, None); 3];
+    let mut bufs = [
+        IoSliceMut::new(&mut buf1),
+        IoSliceMut::new(&mut buf2),
+        IoSliceMut::new(&mut buf3),
+    ];
+    assert_eq!(
+        receiver.recv_from_batch(&mut bufs, &mut results).unwrap(),
+        1
+    );
+    assert_eq!(results[0], (DATA1.len(), Some(sender_address)));
+    assert_would_block(receiver.recv_from_batch(&mut bufs, &mut results));
+
+    assert!(sender.take_error().unwrap().is_none());
+    assert!(receiver.take_error().unwrap().is_none());
+}
+
+#[test]
+fn send_batch_larger_than_max() {
+    let sender = UdpSocket::bind(any_local_address()).unwrap();
+    let mut receiver = UdpSocket::bind(any_local_address()).unwrap();
+    let receiver_address = receiver.local_addr().unwrap();
+    let (mut poll, mut events) = init_with_poll();
+    poll.registry()
+        .register(&mut receiver, ID1, Interest::READABLE)
+        .unwrap();
+
+    let bufs = vec![IoSlice::new(DATA1); 100];
+    let targets = vec![receiver_address; 100];
+    let mut sent = 0;
+    while sent < bufs.len() {
+        let n = sender
+            .send_to_batch(&bufs[sent..], &targets[sent..])
+            .unwrap();
+        assert!(n > 0);
+        sent += n;
+    }
+
+    let mut received = 0;
+    let mut storage = vec![[0; 20]; 100];
+    let mut results = vec![(0, None); 100];
+    while received < 100 {
+        let mut bufs: Vec<_> = storage.iter_mut().map(|b| IoSliceMut::new(b)).collect();
+        match receiver.recv_from_batch(&mut bufs[received..], &mut results[received..]) {
+            Ok(n) => received += n,
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {
+                poll.poll(&mut events, Some(Duration::from_millis(500)))
+                    .unwrap();
+                assert!(!events.is_empty(), "missing datagrams");
+            }
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+    }
+    assert!(results
+        .iter()
+        .all(|&(n, addr)| n == DATA1.len() && addr.is_some()));
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod offload {
+    use mio::net::{ControlMessageEncoder, ControlMessages, MsgFlags, SegmentSize, UdpSocket};
+    use mio::{Interest, Token};
+    use std::io::{self, IoSlice, IoSliceMut};
+    use std::time::Duration;
+
+    use super::util::{any_local_address, assert_would_block, init_with_poll};
+
+    const ID: Token = Token(0);
+
+    #[test]
+    fn set_get_gro() {
+        let socket = UdpSocket::bind(any_local_address()).unwrap();
+        match socket.set_gro(true) {
+            Ok(()) => {}
+            // Kernel before 5.0.
+            Err(ref err) if err.raw_os_error() == Some(libc::ENOPROTOOPT) => return,
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+        assert!(socket.gro().unwrap());
+        socket.set_gro(false).unwrap();
+        assert!(!socket.gro().unwrap());
+    }
+
+    #[test]
+    fn set_get_gso_segment_size() {
+        let socket = UdpSocket::bind(any_local_address()).unwrap();
+        match socket.set_gso_segment_size(1200) {
+            Ok(()) => {}
+            // Kernel before 4.18.
+            Err(ref err) if err.raw_os_error() == Some(libc::ENOPROTOOPT) => return,
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+        assert_eq!(socket.gso_segment_size().unwrap(), 1200);
+        socket.set_gso_segment_size(0).unwrap();
+        assert_eq!(socket.gso_segment_size().unwrap(), 0);
+    }
+
+    #[test]
+    fn send_msg_gso() {
+        let (mut poll, mut events) = init_with_poll();
+
+        let sender = UdpSocket::bind(any_local_address()).unwrap();
+        let mut receiver = UdpSocket::bind(any_local_address()).unwrap();
+        let receiver_address = receiver.local_addr().unwrap();
+        if sender.gso_segment_size().is_err() {
+            // Kernel doesn't support GSO.
+            return;
+        }
+        poll.registry()
+            .register(&mut receiver, ID, Interest::READABLE)
+            .unwrap();
+
+        let data: Vec<u8> = (0..250).collect();
+        let mut control = [0; 32];
+        let mut encoder = ControlMessageEncoder::new(&mut control);
+        SegmentSize::new(100).encode(&mut encoder).unwrap();
+        let n = sender
+            .send_msg(
+                &[IoSlice::new(&data)],
+                Some(receiver_address),
+                encoder.as_bytes(),
+                MsgFlags::empty(),
+            )
+            .unwrap();
+        assert_eq!(n, data.len());
+
+        // Without GRO enabled on the receiver we should receive the separate
+        // datagrams.
+        let mut received = Vec::new();
+        let mut buf = [0; 300];
+        while received.len() < 3 {
+            match receiver.recv_from(&mut buf) {
+                Ok((n, addr)) => {
+                    assert_eq!(addr, sender.local_addr().unwrap());
+                    received.push(buf[..n].to_vec());
+                }
+                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {
+                    poll.poll(&mut events, Some(Duration::from_millis(500)))
+                        .unwrap();
+                    assert!(!events.is_empty(), "missing datagrams");
+                }
+                Err(err) => panic!("unexpected error: {}", err),
+            }
+        }
+        assert_eq!(received[0], &data[..100]);
+        assert_eq!(received[1], &data[100..200]);
+        assert_eq!(received[2], &data[200..]);
+        assert_would_block(receiver.recv_from(&mut buf));
+    }
+
+    #[test]
+    fn send_to_gso() {
+        let (mut poll, mut events) = init_with_poll();
+
+        let sender = UdpSocket::bind(any_local_address()).unwrap();
+        let mut receiver = UdpSocket::bind(any_local_address()).unwrap();
+        poll.registry()
+            .register(&mut receiver, ID, Interest::READABLE)
+            .unwrap();
+
+        let data: Vec<u8> = (0..250).collect();
+        let n = sender
+            .send_to_gso(&data, receiver.local_addr().unwrap(), 100)
+            .unwrap();
+        assert_eq!(n, data.len());
+
+        let received = recv_datagrams(&mut poll, &mut events, &receiver, 3);
+        assert_eq!(received[0], &data[..100]);
+        assert_eq!(received[1], &data[100..200]);
+        assert_eq!(received[2], &data[200..]);
+    }
+
+    #[test]
+    fn send_to_gso_fallback() {
+        let (mut poll, mut events) = init_with_poll();
+
+        let sender = UdpSocket::bind(any_local_address()).unwrap();
+        let mut receiver = UdpSocket::bind(any_local_address()).unwrap();
+        poll.registry()
+            .register(&mut receiver, ID, Interest::READABLE)
+            .unwrap();
+
+        // The kernel rejects more than 64 (128 since Linux 6.13) segments with
+        // `EINVAL`, forcing the per-segment fallback.
+        const SEGMENTS: usize = 200;
+        let data: Vec<u8> = (0..SEGMENTS * 10).map(|i| i as u8).collect();
+        let n = sender
+            .send_to_gso(&data, receiver.local_addr().unwrap(), 10)
+            .unwrap();
+        assert_eq!(n, data.len());
+
+        let received = recv_datagrams(&mut poll, &mut events, &receiver, SEGMENTS);
+        for (datagram, expected) in received.iter().zip(data.chunks(10)) {
+            assert_eq!(datagram, expected);
+        }
+    }
+
+    #[test]
+    fn send_to_gso_zero_segment_size() {
+        let socket = UdpSocket::bind(any_local_address()).unwrap();
+        let err = socket
+            .send_to_gso(&[1; 10], socket.local_addr().unwrap(), 0)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    /// Receives exactly `count` datagrams from `receiver`.
+    fn recv_datagrams(
+        poll: &mut mio::Poll,
+        events: &mut mio::Events,
+        receiver: &UdpSocket,
+        count: usize,
+    ) -> Vec<Vec<u8>> {
+        let mut received = Vec::new();
+        let mut buf = [0; 300];
+        while received.len() < count {
+            match receiver.recv_from(&mut buf) {
+                Ok((n, _)) => received.push(buf[..n].to_vec()),
+                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {
+                    poll.poll(events, Some(Duration::from_millis(500))).unwrap();
+                    assert!(!events.is_empty(), "missing datagrams");
+                }
+                Err(err) => panic!("unexpected error: {}", err),
+            }
+        }
+        assert_would_block(receiver.recv_from(&mut buf));
+        received
+    }
+
+    #[test]
+    fn recv_msg_gro() {
+        let (mut poll, mut events) = init_with_poll();
+
+        let sender = UdpSocket::bind(any_local_address()).unwrap();
+        let mut receiver = UdpSocket::bind(any_local_address()).unwrap();
+        let receiver_address = receiver.local_addr().unwrap();
+        if receiver.set_gro(true).is_err() || sender.gso_segment_size().is_err() {
+            // Kernel doesn't support GRO or GSO.
+            return;
+        }
+        poll.registry()
+            .register(&mut receiver, ID, Interest::READABLE)
+            .unwrap();
+
+        let data: Vec<u8> = (0..250).collect();
+        let mut control = [0; 32];
+        let mut encoder = ControlMessageEncoder::new(&mut control);
+        SegmentSize::new(100).encode(&mut encoder).unwrap();
+        let n = sender
+            .send_msg(
+                &[IoSlice::new(&data)],
+                Some(receiver_address),
+                encoder.as_bytes(),
+                MsgFlags::empty(),
+            )
+            .unwrap();
+        assert_eq!(n, data.len());
+
+        poll.poll(&mut events, Some(Duration::from_millis(500)))
+            .unwrap();
+        // The segmented datagram is delivered as is on the loopback device.
+        let mut buf = [0; 1000];
+        let mut control = [0; 32];
+        let msg = receiver
+            .recv_msg(
+                &mut [IoSliceMut::new(&mut buf)],
+                &mut control,
+                MsgFlags::empty(),
+            )
+            .unwrap();
+        assert_eq!(msg.len(), data.len());
+        assert_eq!(msg.addr(), Some(sender.local_addr().unwrap()));
+        assert_eq!(&buf[..msg.len()], &*data);
+        let segment_size = ControlMessages::new(&control[..msg.control_len()])
+            .filter_map(|cmsg| SegmentSize::from_control_message(&cmsg))
+            .next();
+        assert_eq!(segment_size, Some(SegmentSize::new(100)));
+    }
+}
+
+#[cfg(unix)]
+mod msg {
+    use mio::net::{ControlMessageEncoder, ControlMessages, MsgFlags, PacketInfo, Tos, UdpSocket};
+    use mio::{Interest, Token};
+    use std::io::{IoSlice, IoSliceMut};
+    use std::net::SocketAddr;
+
+    use super::util::{
+        any_local_address, any_local_ipv6_address, assert_would_block, expect_events,
+        init_with_poll, ExpectEvent,
+    };
+    use super::DATA1;
+
+    const ID: Token = Token(0);
+
+    fn socket_pair(ipv6: bool) -> (UdpSocket, UdpSocket) {
+        let addr = if ipv6 {
+            any_local_ipv6_address()
+        } else {
+            any_local_address()
+        };
+        let sender = UdpSocket::bind(addr).unwrap();
+        let receiver = UdpSocket::bind(addr).unwrap();
+        (sender, receiver)
+    }
+
+    fn wait_readable(socket: &mut UdpSocket) {
+        let (mut poll, mut events) = init_with_poll();
+        poll.registry()
+            .register(socket, ID, Interest::READABLE)
+            .unwrap();
+        expect_events(
+            &mut poll,
+            &mut events,
+            vec![ExpectEvent::new(ID, Interest::READABLE)],
+        );
+        poll.registry().deregister(socket).unwrap();
+    }
+
+    #[test]
+    fn vectored_send_recv() {
+        for &ipv6 in &[false, true] {
+            let (sender, mut receiver) = socket_pair(ipv6);
+            let receiver_addr = receiver.local_addr().unwrap();
+
+            let bufs = [IoSlice::new(&DATA1[..5]), IoSlice::new(&DATA1[5..])];
+            let n = sender.send_vectored_to(&bufs, receiver_addr).unwrap();
+            assert_eq!(n, DATA1.len());
+
+            wait_readable(&mut receiver);
+            let mut buf1 = [0; 4];
+            let mut buf2 = [0; 20];
+            let mut bufs = [IoSliceMut::new(&mut buf1), IoSliceMut::new(&mut buf2)];
+            let (n, addr) = receiver.recv_vectored_from(&mut bufs).unwrap();
+            assert_eq!(n, DATA1.len());
+            assert_eq!(addr, sender.local_addr().unwrap());
+            assert_eq!(&buf1, &DATA1[..4]);
+            assert_eq!(&buf2[..n - 4], &DATA1[4..]);
+
+            let mut bufs = [IoSliceMut::new(&mut buf1), IoSliceMut::new(&mut buf2)];
+            assert_would_block(receiver.recv_vectored_from(&mut bufs));
+        }
+    }
+
+    #[test]
+    fn recv_msg_flags() {
+        let (sender, mut receiver) = socket_pair(false);
+        let receiver_addr = receiver.local_addr().unwrap();
+        sender.send_to(DATA1, receiver_addr).unwrap();
+        wait_readable(&mut receiver);
+
+        // Peeking doesn't remove the datagram.
+        let mut buf = [0; 20];
+        let msg = receiver
+            .recv_msg(&mut [IoSliceMut::new(&mut buf)], &mut [], MsgFlags::PEEK)
+            .unwrap();
+        assert_eq!(msg.len(), DATA1.len());
+        assert_eq!(msg.addr(), Some(sender.local_addr().unwrap()));
+        assert_eq!(msg.control_len(), 0);
+        assert!(!msg.flags().contains(MsgFlags::TRUNC));
+        assert_eq!(&buf[..msg.len()], DATA1);
+
+        // Datagram doesn't fit in the buffer.
+        let mut buf = [0; 5];
+        let msg = receiver
+            .recv_msg(&mut [IoSliceMut::new(&mut buf)], &mut [], MsgFlags::empty())
+            .unwrap();
+        assert_eq!(msg.len(), 5);
+        assert!(msg.flags().contains(MsgFlags::TRUNC));
+        assert_eq!(&buf, &DATA1[..5]);
+
+        assert_would_block(receiver.recv_msg(
+            &mut [IoSliceMut::new(&mut buf)],
+            &mut [],
+            MsgFlags::DONTWAIT,
+        ));
+    }
+
+    #[test]
+    fn send_msg_connected() {
+        let (sender, mut receiver) = socket_pair(false);
+        sender.connect(receiver.local_addr().unwrap()).unwrap();
+
+        let n = sender
+            .send_msg(&[IoSlice::new(DATA1)], None, &[], MsgFlags::empty())
+            .unwrap();
+        assert_eq!(n, DATA1.len());
+
+        wait_readable(&mut receiver);
+        let mut buf = [0; 20];
+        let (n, addr) = receiver.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..n], DATA1);
+        assert_eq!(addr, sender.local_addr().unwrap());
+    }
+
+    #[test]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn send_recv_control_messages() {
+        use std::mem::size_of;
+        use std::os::unix::io::AsRawFd;
+
+        let (sender, mut receiver) = socket_pair(false);
+        let receiver_addr = receiver.local_addr().unwrap();
+        let enable: libc::c_int = 1;
+        let res = unsafe {
+            libc::setsockopt(
+                receiver.as_raw_fd(),
+                libc::IPPROTO_IP,
+                libc::IP_RECVTOS,
+                &enable as *const _ as *const libc::c_void,
+                size_of::<libc::c_int>() as libc::socklen_t,
+            )
+        };
+        assert_eq!(res, 0);
+
+        let tos: libc::c_int = 0x10;
+        let mut control = [0; 64];
+        let mut encoder = ControlMessageEncoder::new(&mut control);
+        encoder
+            .push(libc::IPPROTO_IP, libc::IP_TOS, &tos.to_ne_bytes())
+            .unwrap();
+        let n = sender
+            .send_msg(
+                &[IoSlice::new(DATA1)],
+                Some(receiver_addr),
+                encoder.as_bytes(),
+                MsgFlags::empty(),
+            )
+            .unwrap();
+        assert_eq!(n, DATA1.len());
+
+        wait_readable(&mut receiver);
+        let mut buf = [0; 20];
+        let mut control = [0; 64];
+        let msg = receiver
+            .recv_msg(
+                &mut [IoSliceMut::new(&mut buf)],
+                &mut control,
+                MsgFlags::empty(),
+            )
+            .unwrap();
+        assert_eq!(&buf[..msg.len()], DATA1);
+        assert!(!msg.flags().contains(MsgFlags::CTRUNC));
+
+        let mut cmsgs = ControlMessages::new(&control[..msg.control_len()]);
+        let cmsg = cmsgs.next().unwrap();
+        assert_eq!(cmsg.level(), libc::IPPROTO_IP);
+        assert_eq!(cmsg.kind(), libc::IP_TOS);
+        assert_eq!(cmsg.data(), &[0x10]);
+        assert!(cmsgs.next().is_none());
+    }
+
+    #[test]
+    #[cfg(any(
+        target_os = "android",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos"
+    ))]
+    fn pktinfo_v4() {
+        let (sender, receiver) = socket_pair(false);
+        assert!(!receiver.recv_pktinfo_v4().unwrap());
+        receiver.set_recv_pktinfo_v4(true).unwrap();
+        assert!(receiver.recv_pktinfo_v4().unwrap());
+        smoke_test_pktinfo(sender, receiver);
+    }
+
+    #[test]
+    fn pktinfo_v6() {
+        let (sender, receiver) = socket_pair(true);
+        assert!(!receiver.recv_pktinfo_v6().unwrap());
+        receiver.set_recv_pktinfo_v6(true).unwrap();
+        assert!(receiver.recv_pktinfo_v6().unwrap());
+        smoke_test_pktinfo(sender, receiver);
+    }
+
+    fn smoke_test_pktinfo(mut sender: UdpSocket, mut receiver: UdpSocket) {
+        let sender_addr = sender.local_addr().unwrap();
+        let receiver_addr = receiver.local_addr().unwrap();
+
+        sender.send_to(DATA1, receiver_addr).unwrap();
+        wait_readable(&mut receiver);
+        let mut buf = [0; 20];
+        let (n, addr, info) = recv_pktinfo(&receiver, &mut buf);
+        assert_eq!(&buf[..n], DATA1);
+        assert_eq!(addr, sender_addr);
+        let info = info.unwrap();
+        assert_eq!(info.addr(), receiver_addr.ip());
+        assert_ne!(info.ifindex(), 0);
+
+        // Reply from the address the datagram was received on.
+        let mut control = [0; 64];
+        let mut encoder = ControlMessageEncoder::new(&mut control);
+        info.encode(&mut encoder).unwrap();
+        receiver
+            .send_msg(
+                &[IoSlice::new(DATA1)],
+                Some(addr),
+                encoder.as_bytes(),
+                MsgFlags::empty(),
+            )
+            .unwrap();
+        wait_readable(&mut sender);
+        let (n, addr, info) = recv_pktinfo(&sender, &mut buf);
+        assert_eq!(&buf[..n], DATA1);
+        assert_eq!(addr, receiver_addr);
+        // Not enabled for the sender.
+        assert!(info.is_none());
+    }
+
+    /// Receive a datagram and its packet information, if any.
+    fn recv_pktinfo(socket: &UdpSocket, buf: &mut [u8]) -> (usize, SocketAddr, Option<PacketInfo>) {
+        let mut control = [0; 128];
+        let msg = socket
+            .recv_msg(&mut [IoSliceMut::new(buf)], &mut control, MsgFlags::empty())
+            .unwrap();
+        let info = ControlMessages::new(&control[..msg.control_len()])
+            .filter_map(|cmsg| PacketInfo::from_control_message(&cmsg))
+            .next();
+        (msg.len(), msg.addr().unwrap(), info)
+    }
+
+    #[test]
+    fn packet_info_control_message() {
+        let info = PacketInfo::new("::1".parse().unwrap(), 1);
+        let mut control = [0; 64];
+        let mut encoder = ControlMessageEncoder::new(&mut control);
+        info.encode(&mut encoder).unwrap();
+        let len = encoder.len();
+
+        let mut cmsgs = ControlMessages::new(&control[..len]);
+        let cmsg = cmsgs.next().unwrap();
+        assert_eq!(cmsg.level(), libc::IPPROTO_IPV6);
+        assert_eq!(cmsg.kind(), libc::IPV6_PKTINFO);
+        assert_eq!(PacketInfo::from_control_message(&cmsg), Some(info));
+        assert!(cmsgs.next().is_none());
+
+        let mut control = [0; 64];
+        let mut encoder = ControlMessageEncoder::new(&mut control);
+        encoder
+            .push(libc::IPPROTO_IPV6, libc::IPV6_PKTINFO, &[0; 4])
+            .unwrap();
+        let len = encoder.len();
+        // Data too small.
+        let cmsg = ControlMessages::new(&control[..len]).next().unwrap();
+        assert_eq!(PacketInfo::from_control_message(&cmsg), None);
+    }
+
+    #[test]
+    fn set_get_tos() {
+        let socket = UdpSocket::bind(any_local_address()).unwrap();
+        socket.set_tos_v4(0x28).unwrap();
+        assert_eq!(socket.tos_v4().unwrap(), 0x28);
+
+        let socket = UdpSocket::bind(any_local_ipv6_address()).unwrap();
+        assert_eq!(socket.tclass_v6().unwrap(), 0);
+        socket.set_tclass_v6(0x28).unwrap();
+        assert_eq!(socket.tclass_v6().unwrap(), 0x28);
+    }
+
+    #[test]
+    #[cfg(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos"
+    ))]
+    fn ecn_v4() {
+        let (sender, receiver) = socket_pair(false);
+        assert!(!receiver.recv_tos_v4().unwrap());
+        receiver.set_recv_tos_v4(true).unwrap();
+        assert!(receiver.recv_tos_v4().unwrap());
+        smoke_test_ecn(sender, receiver);
+    }
+
+    #[test]
+    fn ecn_v6() {
+        let (sender, receiver) = socket_pair(true);
+        assert!(!receiver.recv_tclass_v6().unwrap());
+        receiver.set_recv_tclass_v6(true).unwrap();
+        assert!(receiver.recv_tclass_v6().unwrap());
+        smoke_test_ecn(sender, receiver);
+    }
+
+    fn recv_tos(socket: &UdpSocket, buf: &mut [u8]) -> (usize, Option<Tos>) {
+        let mut control = [0; 64];
+        let msg = socket
+            .recv_msg(&mut [IoSliceMut::new(buf)], &mut control, MsgFlags::empty())
+            .unwrap();
+        let tos = ControlMessages::new(&control[..msg.control_len()])
+            .filter_map(|cmsg| Tos::from_control_message(&cmsg))
+            .next();
+        (msg.len(), tos)
+    }
+
+    fn smoke_test_ecn(sender: UdpSocket, mut receiver: UdpSocket) {
+        // ECN Capable Transport (0) and Congestion Experienced codepoints.
+        const ECT_0: u8 = 0b10;
+        const CE: u8 = 0b11;
+
+        let receiver_addr = receiver.local_addr().unwrap();
+        let mut buf = [0; 20];
+
+        // Per datagram.
+        let mut control = [0; 64];
+        let mut encoder = ControlMessageEncoder::new(&mut control);
+        if receiver_addr.is_ipv4() {
+            Tos::new(ECT_0).encode_v4(&mut encoder).unwrap();
+        } else {
+            Tos::new(ECT_0).encode_v6(&mut encoder).unwrap();
+        }
+        let bufs = [IoSlice::new(DATA1)];
+        let n = sender
+            .send_msg(
+                &bufs,
+                Some(receiver_addr),
+                encoder.as_bytes(),
+                MsgFlags::empty(),
+            )
+            .unwrap();
+        assert_eq!(n, DATA1.len());
+        wait_readable(&mut receiver);
+        let (n, tos) = recv_tos(&receiver, &mut buf);
+        assert_eq!(&buf[..n], DATA1);
+        assert_eq!(tos.map(|tos| tos.ecn()), Some(ECT_0));
+
+        // Per socket.
+        if receiver_addr.is_ipv4() {
+            sender.set_tos_v4(u32::from(CE)).unwrap();
+        } else {
+            sender.set_tclass_v6(u32::from(CE)).unwrap();
+        }
+        sender.send_to(DATA1, receiver_addr).unwrap();
+        wait_readable(&mut receiver);
+        let (n, tos) = recv_tos(&receiver, &mut buf);
+        assert_eq!(&buf[..n], DATA1);
+        assert_eq!(tos, Some(Tos::new(CE)));
+    }
+
+    #[test]
+    fn tos_control_message() {
+        let mut control = [0; 64];
+        let mut encoder = ControlMessageEncoder::new(&mut control);
+        Tos::new(0x28).encode_v6(&mut encoder).unwrap();
+        let len = encoder.len();
+
+        let cmsg = ControlMessages::new(&control[..len]).next().unwrap();
+        assert_eq!(cmsg.level(), libc::IPPROTO_IPV6);
+        assert_eq!(cmsg.kind(), libc::IPV6_TCLASS);
+        let tos = Tos::from_control_message(&cmsg).unwrap();
+        assert_eq!(tos.value(), 0x28);
+        assert_eq!(tos.ecn(), 0);
+    }
+
+    #[test]
+    fn control_message_encoder() {
+        let mut control = [0; 64];
+        let mut encoder = ControlMessageEncoder::new(&mut control);
+        assert!(encoder.is_empty());
+        encoder.push(1, 2, &[1, 2, 3]).unwrap();
+        encoder.push(3, 4, &[]).unwrap();
+        assert_eq!(
+            encoder.len(),
+            ControlMessageEncoder::space(3) + ControlMessageEncoder::space(0)
+        );
+        let err = encoder.push(5, 6, &[0; 64]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+        let len = encoder.len();
+        let cmsgs: Vec<_> = ControlMessages::new(&control[..len])
+            .map(|cmsg| (cmsg.level(), cmsg.kind(), cmsg.data().to_vec()))
+            .collect();
+        assert_eq!(cmsgs, vec![(1, 2, vec![1, 2, 3]), (3, 4, vec![])]);
+
+        // Truncated buffers must not panic.
+        assert!(ControlMessages::new(&control[..len - 1]).nth(1).is_none());
+        assert!(ControlMessages::new(&control[..4]).next().is_none());
+    }
+
+    #[test]
+    fn msg_flags() {
+        let flags = MsgFlags::PEEK | MsgFlags::DONTWAIT;
+        assert!(flags.contains(MsgFlags::PEEK));
+        assert!(!flags.contains(MsgFlags::TRUNC));
+        assert_eq!(flags.bits(), libc::MSG_PEEK | libc::MSG_DONTWAIT);
+        assert!(MsgFlags::empty().is_empty());
+        assert_eq!(format!("{:?}", flags), "PEEK | DONTWAIT");
+        assert_eq!(format!("{:?}", MsgFlags::empty()), "(empty)");
+    }
+}
@@ -1,9 +1,10 @@
 #![cfg(all(unix, feature = "os-poll", feature = "uds"))]
 
-use mio::net::UnixDatagram;
+use mio::net::{SocketAddr, UnixDatagram};
 use mio::{Interest, Token};
 use std::io;
 use std::net::Shutdown;
+use std::os::unix::io::{AsRawFd, FromRawFd};
 use std::os::unix::net;
 
 #[macro_use]
@@ -223,6 +224,171 @@ fn unix_datagram_shutdown() {
     assert!(datagram1.take_error().unwrap().is_none());
 }
 
+#[test]
+fn unix_datagram_send_recv_with_fds() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let (d1, mut d2) = UnixDatagram::pair().unwrap();
+    poll.registry()
+        .register(&mut d2, TOKEN_1, Interest::READABLE)
+        .unwrap();
+
+    // Send one end of another socket pair over the datagram socket.
+    let (a, b) = net::UnixDatagram::pair().unwrap();
+    let n = d1.send_with_fds(DATA1, &[b.as_raw_fd()]).unwrap();
+    assert_eq!(n, DATA1.len());
+    drop(b);
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN_1, Interest::READABLE)],
+    );
+
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    let mut fds = [-1; 2];
+    let (n, n_fds, truncated) = d2.recv_with_fds(&mut buf, &mut fds).unwrap();
+    assert_eq!(&buf[..n], DATA1);
+    assert_eq!(n_fds, 1);
+    assert!(!truncated);
+    let b = unsafe { net::UnixDatagram::from_raw_fd(fds[0]) };
+    assert_socket_close_on_exec(&b);
+
+    // The received descriptor must refer to the same socket.
+    a.send(DATA2).unwrap();
+    let n = b.recv(&mut buf).unwrap();
+    assert_eq!(&buf[..n], DATA2);
+
+    // A datagram with file descriptors, but no room to receive them.
+    d1.send_with_fds(DATA2, &[a.as_raw_fd()]).unwrap();
+    let (n, n_fds, truncated) = d2.recv_with_fds(&mut buf, &mut []).unwrap();
+    assert_eq!(&buf[..n], DATA2);
+    assert_eq!(n_fds, 0);
+    assert!(truncated);
+    assert_would_block(d2.recv_with_fds(&mut buf, &mut fds));
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn unix_datagram_send_recv_with_creds() {
+    use mio::net::UCred;
+
+    let (mut poll, mut events) = init_with_poll();
+
+    let (d1, mut d2) = UnixDatagram::pair().unwrap();
+    poll.registry()
+        .register(&mut d2, TOKEN_1, Interest::READABLE)
+        .unwrap();
+
+    assert!(!d2.passcred().unwrap());
+    d2.set_passcred(true).unwrap();
+    assert!(d2.passcred().unwrap());
+
+    let own = UCred {
+        uid: unsafe { libc::getuid() },
+        gid: unsafe { libc::getgid() },
+        pid: Some(unsafe { libc::getpid() }),
+    };
+
+    // Explicitly send credentials, using our own process id.
+    let creds = UCred { pid: None, ..own };
+    d1.send_with_creds(DATA1, &creds).unwrap();
+    // With `SO_PASSCRED` set the kernel attaches the credentials to all
+    // datagrams.
+    d1.send(DATA2).unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN_1, Interest::READABLE)],
+    );
+
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    let (n, got) = d2.recv_with_creds(&mut buf).unwrap();
+    assert_eq!(&buf[..n], DATA1);
+    assert_eq!(got, Some(own));
+    let (n, got) = d2.recv_with_creds(&mut buf).unwrap();
+    assert_eq!(&buf[..n], DATA2);
+    assert_eq!(got, Some(own));
+    assert_would_block(d2.recv_with_creds(&mut buf));
+
+    // Without `SO_PASSCRED` no credentials are received.
+    d2.set_passcred(false).unwrap();
+    d1.send_with_creds(DATA1, &creds).unwrap();
+    let (n, got) = d2.recv_with_creds(&mut buf).unwrap();
+    assert_eq!(&buf[..n], DATA1);
+    assert_eq!(got, None);
+}
+
+#[test]
+fn unix_datagram_addr() {
+    let path1 = temp_file("unix_datagram_addr1");
+    let path2 = temp_file("unix_datagram_addr2");
+    let address1 = SocketAddr::from_pathname(&path1).unwrap();
+    let address2 = SocketAddr::from_pathname(&path2).unwrap();
+
+    let datagram1 = UnixDatagram::bind_addr(&address1).unwrap();
+    let datagram2 = UnixDatagram::bind_addr(&address2).unwrap();
+    datagram1.send_to_addr(DATA1, &address2).unwrap();
+    datagram2.connect_addr(&address1).unwrap();
+    assert_eq!(datagram2.peer_addr().unwrap().as_pathname(), Some(&*path1));
+    datagram2.send(DATA2).unwrap();
+
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    let (n, addr) = datagram2.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..n], DATA1);
+    assert_eq!(addr.as_pathname(), Some(&*path1));
+    let (n, addr) = datagram1.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..n], DATA2);
+    assert_eq!(addr.as_pathname(), Some(&*path2));
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn unix_datagram_autobind() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let name = format!("mio-unix_datagram_autobind-{}", std::process::id());
+    let address = SocketAddr::from_abstract_name(&name).unwrap();
+    let mut server = UnixDatagram::bind_addr(&address).unwrap();
+    poll.registry()
+        .register(&mut server, TOKEN_1, Interest::READABLE)
+        .unwrap();
+
+    let mut client = UnixDatagram::unbound().unwrap();
+    assert!(client.local_addr().unwrap().is_unnamed());
+    client.autobind().unwrap();
+    // The kernel picks a name of five hex digits.
+    let client_addr = client.local_addr().unwrap();
+    assert_eq!(
+        client_addr.as_abstract_name().map(|name| name.len()),
+        Some(5)
+    );
+    poll.registry()
+        .register(&mut client, TOKEN_2, Interest::READABLE)
+        .unwrap();
+
+    client.send_to_addr(DATA1, &address).unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN_1, Interest::READABLE)],
+    );
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    let (n, addr) = server.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..n], DATA1);
+    assert_eq!(addr.as_abstract_name(), client_addr.as_abstract_name());
+
+    // Reply to the autobound address.
+    server.send_to_addr(DATA2, &addr).unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN_2, Interest::READABLE)],
+    );
+    let (n, addr) = client.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..n], DATA2);
+    assert_eq!(addr.as_abstract_name(), Some(name.as_bytes()));
+}
+
 #[test]
 fn unix_datagram_register() {
     let (mut poll, mut events) = init_with_poll();
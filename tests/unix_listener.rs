@@ -1,6 +1,6 @@
 #![cfg(all(unix, feature = "os-poll", feature = "uds"))]
 
-use mio::net::UnixListener;
+use mio::net::{SocketAddr, UnixListener, UnixStream};
 use mio::{Interest, Token};
 use std::io::{self, Read};
 use std::os::unix::net;
@@ -74,6 +74,50 @@ fn unix_listener_local_addr() {
     handle.join().unwrap();
 }
 
+#[test]
+fn unix_listener_bind_addr() {
+    let path = temp_file("unix_listener_bind_addr");
+    let address = SocketAddr::from_pathname(&path).unwrap();
+    assert_eq!(address.as_pathname(), Some(&*path));
+
+    let listener = UnixListener::bind_addr(&address).unwrap();
+    assert_eq!(listener.local_addr().unwrap().as_pathname(), Some(&*path));
+    let _stream = UnixStream::connect_addr(&address).unwrap();
+    let (_, peer_addr) = listener.accept().unwrap();
+    assert!(peer_addr.is_unnamed());
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn unix_listener_abstract_address() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let name = format!("mio-unix_listener_abstract_address-{}", std::process::id());
+    let address = SocketAddr::from_abstract_name(&name).unwrap();
+    assert_eq!(address.as_abstract_name(), Some(name.as_bytes()));
+    assert!(address.as_pathname().is_none());
+
+    let mut listener = UnixListener::bind_addr(&address).unwrap();
+    let local_addr = listener.local_addr().unwrap();
+    assert_eq!(local_addr.as_abstract_name(), Some(name.as_bytes()));
+    poll.registry()
+        .register(&mut listener, TOKEN_1, Interest::READABLE)
+        .unwrap();
+
+    let _stream = UnixStream::connect_addr(&address).unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN_1, Interest::READABLE)],
+    );
+    let (_, peer_addr) = listener.accept().unwrap();
+    assert!(peer_addr.is_unnamed());
+
+    // The name can't be longer than `sun_path`, minus the leading null byte.
+    assert!(SocketAddr::from_abstract_name(&[b'a'; 108][..]).is_err());
+    assert!(SocketAddr::from_abstract_name(&[b'a'; 107][..]).is_ok());
+}
+
 #[test]
 fn unix_listener_register() {
     let (mut poll, mut events) = init_with_poll();
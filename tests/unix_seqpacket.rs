@@ -0,0 +1,164 @@
+#![cfg(all(
+    unix,
+    not(any(target_os = "ios", target_os = "macos")),
+    feature = "os-poll",
+    feature = "uds"
+))]
+
+use mio::net::{UnixSeqpacket, UnixSeqpacketListener};
+use mio::{Interest, Token};
+use std::net::Shutdown;
+
+mod util;
+use util::{
+    assert_send, assert_socket_close_on_exec, assert_socket_non_blocking, assert_sync,
+    assert_would_block, expect_events, expect_no_events, init_with_poll, temp_file, ExpectEvent,
+    Readiness,
+};
+
+const DATA1: &[u8] = b"Hello same host!";
+const DATA2: &[u8] = b"Why hello mio!";
+const DEFAULT_BUF_SIZE: usize = 64;
+const TOKEN_1: Token = Token(0);
+const TOKEN_2: Token = Token(1);
+
+#[test]
+fn is_send_and_sync() {
+    assert_send::<UnixSeqpacketListener>();
+    assert_sync::<UnixSeqpacketListener>();
+    assert_send::<UnixSeqpacket>();
+    assert_sync::<UnixSeqpacket>();
+}
+
+#[test]
+fn unix_seqpacket_smoke() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let path = temp_file("unix_seqpacket_smoke");
+    let mut listener = UnixSeqpacketListener::bind(&path).unwrap();
+    assert_socket_non_blocking(&listener);
+    assert_socket_close_on_exec(&listener);
+    assert_eq!(listener.local_addr().unwrap().as_pathname(), Some(&*path));
+    poll.registry()
+        .register(&mut listener, TOKEN_1, Interest::READABLE)
+        .unwrap();
+    assert_would_block(listener.accept());
+
+    let mut client = UnixSeqpacket::connect(&path).unwrap();
+    assert_socket_non_blocking(&client);
+    assert_socket_close_on_exec(&client);
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN_1, Interest::READABLE)],
+    );
+
+    let (mut server, addr) = listener.accept().unwrap();
+    assert!(addr.is_unnamed());
+    assert_socket_non_blocking(&server);
+    assert_socket_close_on_exec(&server);
+    assert_eq!(client.peer_addr().unwrap().as_pathname(), Some(&*path));
+    assert!(listener.take_error().unwrap().is_none());
+
+    poll.registry()
+        .register(&mut server, TOKEN_2, Interest::READABLE)
+        .unwrap();
+    poll.registry()
+        .register(&mut client, TOKEN_1, Interest::WRITABLE)
+        .unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN_1, Interest::WRITABLE)],
+    );
+
+    // Message boundaries must be preserved.
+    assert_eq!(client.send(DATA1).unwrap(), DATA1.len());
+    assert_eq!(client.send(DATA2).unwrap(), DATA2.len());
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN_2, Interest::READABLE)],
+    );
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    assert_eq!(server.recv(&mut buf).unwrap(), (DATA1.len(), false));
+    assert_eq!(&buf[..DATA1.len()], DATA1);
+    assert_eq!(server.recv(&mut buf).unwrap(), (DATA2.len(), false));
+    assert_eq!(&buf[..DATA2.len()], DATA2);
+    assert_would_block(server.recv(&mut buf));
+
+    drop(client);
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN_2, Readiness::READ_CLOSED)],
+    );
+    assert_eq!(server.recv(&mut buf).unwrap(), (0, false));
+}
+
+#[test]
+fn unix_seqpacket_pair() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let (s1, mut s2) = UnixSeqpacket::pair().unwrap();
+    assert_socket_non_blocking(&s1);
+    assert_socket_close_on_exec(&s1);
+    assert!(s1.local_addr().unwrap().is_unnamed());
+    assert!(s1.peer_addr().unwrap().is_unnamed());
+    poll.registry()
+        .register(&mut s2, TOKEN_1, Interest::READABLE)
+        .unwrap();
+    expect_no_events(&mut poll, &mut events);
+
+    s1.send(DATA1).unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN_1, Interest::READABLE)],
+    );
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    assert_eq!(s2.recv(&mut buf).unwrap(), (DATA1.len(), false));
+    assert_eq!(&buf[..DATA1.len()], DATA1);
+
+    s1.shutdown(Shutdown::Write).unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN_1, Readiness::READ_CLOSED)],
+    );
+    assert_eq!(s2.recv(&mut buf).unwrap(), (0, false));
+    assert!(s1.send(DATA1).is_err());
+}
+
+#[test]
+fn unix_seqpacket_truncated() {
+    let (s1, s2) = UnixSeqpacket::pair().unwrap();
+
+    s1.send(DATA1).unwrap();
+    s1.send(DATA2).unwrap();
+
+    // The remainder of a truncated message is discarded.
+    let mut buf = [0; 5];
+    assert_eq!(s2.recv(&mut buf).unwrap(), (5, true));
+    assert_eq!(&buf, &DATA1[..5]);
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    assert_eq!(s2.recv(&mut buf).unwrap(), (DATA2.len(), false));
+    assert_eq!(&buf[..DATA2.len()], DATA2);
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn unix_seqpacket_abstract_address() {
+    use mio::net::SocketAddr;
+
+    let name = format!("mio-unix_seqpacket_abstract_address-{}", std::process::id());
+    let address = SocketAddr::from_abstract_name(&name).unwrap();
+    let listener = UnixSeqpacketListener::bind_addr(&address).unwrap();
+    let client = UnixSeqpacket::connect_addr(&address).unwrap();
+    let (server, _) = listener.accept().unwrap();
+
+    let name = Some(name.as_bytes());
+    assert_eq!(listener.local_addr().unwrap().as_abstract_name(), name);
+    assert_eq!(server.local_addr().unwrap().as_abstract_name(), name);
+    assert_eq!(client.peer_addr().unwrap().as_abstract_name(), name);
+}
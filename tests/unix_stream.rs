@@ -1,9 +1,10 @@
 #![cfg(all(unix, feature = "os-poll", feature = "uds"))]
 
-use mio::net::UnixStream;
+use mio::net::{MsgFlags, UnixStream};
 use mio::{Interest, Token};
 use std::io::{self, IoSlice, IoSliceMut, Read, Write};
 use std::net::Shutdown;
+use std::os::unix::io::{AsRawFd, FromRawFd};
 use std::os::unix::net;
 use std::path::Path;
 use std::sync::mpsc::channel;
@@ -345,6 +346,175 @@ fn unix_stream_shutdown_listener_write() {
     handle.join().unwrap();
 }
 
+#[test]
+fn unix_stream_peek() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let (mut s1, mut s2) = UnixStream::pair().unwrap();
+    poll.registry()
+        .register(&mut s2, TOKEN_1, Interest::READABLE)
+        .unwrap();
+
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    assert_would_block(s2.peek(&mut buf));
+
+    assert_eq!(s1.write(DATA1).unwrap(), DATA1_LEN);
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN_1, Interest::READABLE)],
+    );
+
+    // Peeking doesn't remove the data.
+    expect_read!(s2.peek(&mut buf), DATA1);
+    expect_read!(s2.peek(&mut buf), DATA1);
+    expect_read!(s2.read(&mut buf), DATA1);
+    assert_would_block(s2.peek(&mut buf));
+}
+
+#[test]
+fn unix_stream_send_recv_with_flags() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let (s1, mut s2) = UnixStream::pair().unwrap();
+    poll.registry()
+        .register(&mut s2, TOKEN_1, Interest::READABLE)
+        .unwrap();
+
+    let n = s1.send_with_flags(DATA1, MsgFlags::DONTWAIT).unwrap();
+    assert_eq!(n, DATA1_LEN);
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN_1, Interest::READABLE)],
+    );
+
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    expect_read!(s2.recv_with_flags(&mut buf, MsgFlags::PEEK), DATA1);
+    // `MSG_WAITALL` doesn't block on a non-blocking socket, only the available
+    // bytes are returned.
+    expect_read!(s2.recv_with_flags(&mut buf, MsgFlags::WAITALL), DATA1);
+    assert_would_block(s2.recv_with_flags(&mut buf, MsgFlags::WAITALL));
+
+    // Writing to a closed socket returns an error, rather than raising
+    // `SIGPIPE`.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "linux",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        drop(s2);
+        let err = s1.send_with_flags(DATA2, MsgFlags::NOSIGNAL).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    }
+}
+
+#[test]
+fn unix_stream_send_recv_with_fds() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let (s1, mut s2) = UnixStream::pair().unwrap();
+    poll.registry()
+        .register(&mut s2, TOKEN_1, Interest::READABLE)
+        .unwrap();
+
+    // Send one end of another socket pair over the stream.
+    let (mut a, b) = net::UnixStream::pair().unwrap();
+    let n = s1.send_with_fds(DATA1, &[b.as_raw_fd()]).unwrap();
+    assert_eq!(n, DATA1_LEN);
+    drop(b);
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN_1, Interest::READABLE)],
+    );
+
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    let mut fds = [-1; 2];
+    let (n, n_fds, truncated) = s2.recv_with_fds(&mut buf, &mut fds).unwrap();
+    assert_eq!(&buf[..n], DATA1);
+    assert_eq!(n_fds, 1);
+    assert!(!truncated);
+    let mut b = unsafe { net::UnixStream::from_raw_fd(fds[0]) };
+    assert_socket_close_on_exec(&b);
+
+    // The received descriptor must refer to the same socket.
+    a.write_all(DATA2).unwrap();
+    let mut buf = [0; DATA2_LEN];
+    b.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, DATA2);
+
+    // Data without file descriptors.
+    s1.send_with_fds(DATA2, &[]).unwrap();
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    let (n, n_fds, truncated) = s2.recv_with_fds(&mut buf, &mut fds).unwrap();
+    assert_eq!(&buf[..n], DATA2);
+    assert_eq!(n_fds, 0);
+    assert!(!truncated);
+    assert_would_block(s2.recv_with_fds(&mut buf, &mut fds));
+}
+
+#[test]
+fn unix_stream_recv_with_fds_truncated() {
+    let (s1, s2) = UnixStream::pair().unwrap();
+
+    let (a, b) = net::UnixStream::pair().unwrap();
+    s1.send_with_fds(DATA1, &[a.as_raw_fd(), b.as_raw_fd()])
+        .unwrap();
+
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    let mut fds = [-1; 1];
+    let (n, n_fds, truncated) = s2.recv_with_fds(&mut buf, &mut fds).unwrap();
+    assert_eq!(n_fds, 1);
+    assert!(truncated);
+    drop(unsafe { net::UnixStream::from_raw_fd(fds[0]) });
+
+    // No data may be lost because the descriptors were truncated.
+    s1.send_with_fds(DATA2, &[]).unwrap();
+    let mut received = buf[..n].to_vec();
+    while received.len() < DATA1_LEN + DATA2_LEN {
+        let (n, n_fds, truncated) = s2.recv_with_fds(&mut buf, &mut fds).unwrap();
+        assert_eq!(n_fds, 0);
+        assert!(!truncated);
+        received.extend_from_slice(&buf[..n]);
+    }
+    assert_eq!(&received[..DATA1_LEN], DATA1);
+    assert_eq!(&received[DATA1_LEN..], DATA2);
+    assert_would_block(s2.recv_with_fds(&mut buf, &mut fds));
+}
+
+#[test]
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn unix_stream_peer_cred() {
+    let (s1, s2) = UnixStream::pair().unwrap();
+    let cred = s1.peer_cred().unwrap();
+    assert_eq!(cred, s2.peer_cred().unwrap());
+    assert_eq!(cred.uid, unsafe { libc::geteuid() });
+    assert_eq!(cred.gid, unsafe { libc::getegid() });
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    assert_eq!(cred.pid, Some(unsafe { libc::getpid() }));
+
+    let path = temp_file("unix_stream_peer_cred");
+    let listener = net::UnixListener::bind(&path).unwrap();
+    let stream = UnixStream::connect(path).unwrap();
+    let (accepted, _) = listener.accept().unwrap();
+    let accepted = UnixStream::from_std(accepted);
+    assert_eq!(stream.peer_cred().unwrap(), accepted.peer_cred().unwrap());
+}
+
 #[test]
 fn unix_stream_register() {
     let (mut poll, mut events) = init_with_poll();